@@ -10,10 +10,10 @@ const LIMIT: usize = 100 * 1024 * 1024; // 100 MiB
 #[derive(Parser)]
 #[command(version, about, long_about = "Cli инструмент для тестирования работы библиотеки.")]
 struct Cli {
-    /// Выберите формат ввода: "Csv", "mt940", "camt053",
+    /// Выберите формат ввода: "Csv", "mt940", "mt942", "mt950", "mt900", "mt910", "camt053", "camt052", "camt054", "1c", "ofx", "qif", "bai2", "coda", "json", "jsonl", "xlsx",
     #[arg(short = 'I', long, value_enum)]
     in_format: Format,
-    /// Выберите формат вывода данных: "csv", "mt940", "camt053",
+    /// Выберите формат вывода данных: "csv", "mt940", "mt942", "mt950", "camt053", "camt052", "camt054", "1c", "ofx", "qif", "bai2", "coda", "json", "jsonl", "xlsx", "parquet", "ledger", "beancount", "pain001",
     #[arg(short = 'O', long, value_enum)]
     out_format: Format,
     /// Опционально. При указании, ожидает путь к файлу. Пример: "path/to/file.format". Дефолтно - stdin()
@@ -22,13 +22,114 @@ struct Cli {
     /// Опционально. При указании, ожидает путь куда будет сохранен файл. Пример: "path/to/file.format".  Дефолтно - stdout()
     #[arg(short = 'o', long)]
     output: Option<String>,
+    /// Опционально. Граница столетия для дат YYMMDD: годы ниже нее - 20xx, остальные - 19xx. Дефолтно - 70
+    #[arg(long)]
+    century_pivot: Option<u8>,
+    /// Опционально. Версия camt.052/053/054 для вывода: "001.02" - "001.13". Дефолтно - 001.02
+    #[arg(long)]
+    camt_version: Option<converter::CamtVersion>,
+    /// Опционально. Версия OFX для вывода: "102" (SGML) или "220" (XML). Дефолтно - 220
+    #[arg(long)]
+    ofx_version: Option<converter::OfxVersion>,
+    /// Опционально. Порядок дня и месяца в датах QIF: "us" (MM/DD/YYYY) или "european" (DD/MM/YYYY). Дефолтно - us
+    #[arg(long)]
+    qif_date_format: Option<converter::QifDateFormat>,
+    /// Опционально. Валюта операций при чтении QIF. Дефолтно - XXX
+    #[arg(long)]
+    qif_currency: Option<String>,
+    /// Опционально. Путь к TOML-профилю сопоставления колонок CSV (сумма, дата, валюта...).
+    #[arg(long)]
+    csv_profile: Option<String>,
+    /// Опционально. Разделитель CSV: ",", ";", "tab", "|". Дефолтно - определяется по файлу, при записи ","
+    #[arg(long, value_parser = parse_delimiter)]
+    csv_delimiter: Option<u8>,
+    /// Опционально. Кодировка CSV: "utf-8", "windows-1251"... Дефолтно - по BOM или содержимому, при записи utf-8
+    #[arg(long)]
+    csv_encoding: Option<String>,
+    /// Суммы в CSV с десятичной запятой: "1.234,56".
+    #[arg(long)]
+    csv_decimal_comma: bool,
+    /// CSV без строки заголовка.
+    #[arg(long)]
+    csv_no_header: bool,
+    /// Писать BOM в начале UTF-8 CSV.
+    #[arg(long)]
+    csv_bom: bool,
+    /// Опционально. Лист xlsx для чтения; колонки задаются тем же --csv-profile. Дефолтно - первый лист
+    #[arg(long)]
+    xlsx_sheet: Option<String>,
+    /// Опционально. Путь к TOML-профилю счетов для ledger и beancount: счет актива по номеру, правила для второго счета.
+    #[arg(long)]
+    ledger_rules: Option<String>,
+    /// Опционально. Версия pain.001 для вывода: "001.03" или "001.09". Дефолтно - 001.09
+    #[arg(long)]
+    pain_version: Option<converter::PainVersion>,
+    /// Наименование плательщика для pain.001. Обязательно при выводе в pain001
+    #[arg(long)]
+    sepa_debtor_name: Option<String>,
+    /// Опционально. IBAN плательщика для pain.001. Дефолтно - счет из входных данных
+    #[arg(long)]
+    sepa_debtor_iban: Option<String>,
+    /// Опционально. BIC банка плательщика для pain.001. Дефолтно - NOTPROVIDED
+    #[arg(long)]
+    sepa_debtor_bic: Option<String>,
+    /// Опционально. Кавычки при записи CSV: "necessary", "always", "never". Дефолтно - necessary
+    #[arg(long, value_enum)]
+    csv_quoting: Option<Quoting>,
+}
+
+#[derive(ValueEnum, Clone, Debug)]
+enum Quoting {
+    Necessary,
+    Always,
+    Never,
+}
+
+impl From<Quoting> for converter::CsvQuoting {
+    fn from(q: Quoting) -> Self {
+        match q {
+            Quoting::Necessary => converter::CsvQuoting::Necessary,
+            Quoting::Always => converter::CsvQuoting::Always,
+            Quoting::Never => converter::CsvQuoting::Never,
+        }
+    }
+}
+
+fn parse_delimiter(s: &str) -> Result<u8, String> {
+    match s {
+        "tab" | "\\t" => Ok(b'\t'),
+        _ if s.len() == 1 && s.is_ascii() => Ok(s.as_bytes()[0]),
+        _ => Err(format!("разделитель должен быть одним ASCII-символом или \"tab\": {}", s)),
+    }
 }
 
 #[derive(ValueEnum, Clone, Debug)]
 enum Format {
     Csv,
     Mt940,
+    Mt942,
+    Mt950,
+    Mt900,
+    Mt910,
     Camt053,
+    Camt052,
+    Camt054,
+    #[value(name = "1c")]
+    ClientBank1C,
+    Ofx,
+    Qif,
+    Bai2,
+    Coda,
+    Json,
+    #[value(name = "jsonl", alias = "ndjson")]
+    JsonLines,
+    Xlsx,
+    Parquet,
+    #[value(alias = "hledger")]
+    Ledger,
+    Beancount,
+    #[value(alias = "sepa")]
+    Pain001,
 }
 
 impl From<Format> for converter::Format {
@@ -36,7 +137,25 @@ impl From<Format> for converter::Format {
         match f {
             Format::Csv => converter::Format::Csv,
             Format::Mt940 => converter::Format::Mt940,
+            Format::Mt942 => converter::Format::Mt942,
+            Format::Mt950 => converter::Format::Mt950,
+            Format::Mt900 => converter::Format::Mt900,
+            Format::Mt910 => converter::Format::Mt910,
             Format::Camt053 => converter::Format::Camt053,
+            Format::Camt052 => converter::Format::Camt052,
+            Format::Camt054 => converter::Format::Camt054,
+            Format::ClientBank1C => converter::Format::ClientBank1C,
+            Format::Ofx => converter::Format::Ofx,
+            Format::Qif => converter::Format::Qif,
+            Format::Bai2 => converter::Format::Bai2,
+            Format::Coda => converter::Format::Coda,
+            Format::Json => converter::Format::Json,
+            Format::JsonLines => converter::Format::JsonLines,
+            Format::Xlsx => converter::Format::Xlsx,
+            Format::Parquet => converter::Format::Parquet,
+            Format::Ledger => converter::Format::Ledger,
+            Format::Beancount => converter::Format::Beancount,
+            Format::Pain001 => converter::Format::Pain001,
         }
     }
 }
@@ -75,11 +194,55 @@ fn main() -> Result<(), Box<dyn std::error::Error>> {
     } else {
         Box::new(io::stdout())
     };
-    converter::convert(
+    let mut options = converter::ConvertOptions::default();
+    if let Some(pivot) = cli.century_pivot {
+        options.century_pivot = pivot;
+    }
+    if let Some(version) = cli.camt_version {
+        options.camt_version = version;
+    }
+    if let Some(version) = cli.ofx_version {
+        options.ofx_version = version;
+    }
+    if let Some(date_format) = cli.qif_date_format {
+        options.qif_date_format = date_format;
+    }
+    if let Some(currency) = cli.qif_currency {
+        options.qif_currency = currency.to_uppercase();
+    }
+    options.xlsx_sheet = cli.xlsx_sheet;
+    if let Some(profile) = cli.csv_profile.as_deref() {
+        options.csv_mapping = converter::CsvMapping::load(profile)?;
+    }
+    if let Some(rules) = cli.ledger_rules.as_deref() {
+        options.ledger_rules = converter::LedgerRules::load(rules)?;
+    }
+    if let Some(version) = cli.pain_version {
+        options.pain_version = version;
+    }
+    options.sepa_debtor = converter::SepaDebtor {
+        name: cli.sepa_debtor_name.unwrap_or_default(),
+        iban: cli.sepa_debtor_iban,
+        bic: cli.sepa_debtor_bic,
+    };
+    let dialect = &mut options.csv_dialect;
+    dialect.delimiter = cli.csv_delimiter;
+    if let Some(label) = cli.csv_encoding.as_deref() {
+        dialect.encoding =
+            Some(converter::CsvDialect::encoding_for_label(label).ok_or_else(|| format!("неизвестная кодировка: {}", label))?);
+    }
+    dialect.decimal_comma = cli.csv_decimal_comma;
+    dialect.has_header = !cli.csv_no_header;
+    dialect.bom = cli.csv_bom;
+    if let Some(quoting) = cli.csv_quoting {
+        dialect.quoting = quoting.into();
+    }
+    converter::convert_with_options(
         &input_data[..],
         &converter::Format::from(cli.in_format),
         &converter::Format::from(cli.out_format),
         output_writer,
+        &options,
     )?;
 
     Ok(())
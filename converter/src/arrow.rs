@@ -0,0 +1,168 @@
+//! Транзакции в колонках Apache Arrow для [`Format::Parquet`](crate::Format::Parquet) и аналитики.
+//!
+//! Схема [`transaction_schema`] стабильна: порядок и типы колонок меняются только
+//! вместе с [`ARROW_SCHEMA_VERSION`], новые колонки добавляются в конец. Версия записана
+//! в метаданных схемы под ключом `schema_version` и не зависит от версии JSON-схемы.
+//!
+//! - `amount` — `Decimal128(18, 4)`: точное значение, дебет со знаком минус;
+//! - `currency` — словарь `Int32 → Utf8`: кодов валют мало, а строк много;
+//! - `value_date`, `booking_date` — `Date32`;
+//! - остальные поля [`Transaction`] — `Utf8` и `Boolean`, контрагент развернут в колонки.
+//!
+//! Разбивка по категориям ([`Transaction::splits`]) не выгружается.
+
+use std::{collections::HashMap, sync::Arc};
+
+use arrow_array::{
+    ArrayRef, BooleanArray, Date32Array, Decimal128Array, DictionaryArray, RecordBatch, StringArray,
+    types::{Date32Type, Int32Type},
+};
+use arrow_schema::{ArrowError, DataType, Field, Schema, SchemaRef};
+
+use crate::{error::ConvertError, model::Transaction};
+
+/// Версия схемы [`transaction_schema`]; повышается при изменении порядка или типов колонок.
+pub const ARROW_SCHEMA_VERSION: u32 = 1;
+
+/// Общее число знаков колонки `amount`: помещается в `INT64` Parquet.
+pub const AMOUNT_PRECISION: u8 = 18;
+/// Знаков после запятой в колонке `amount`: хватает валютам с тремя знаками (BHD, KWD).
+pub const AMOUNT_SCALE: i8 = 4;
+
+fn arrow_error(error: ArrowError) -> ConvertError {
+    ConvertError::Io(std::io::Error::other(error))
+}
+
+fn text(name: &str, nullable: bool) -> Field {
+    Field::new(name, DataType::Utf8, nullable)
+}
+
+/// Схема [`RecordBatch`] транзакций.
+pub fn transaction_schema() -> SchemaRef {
+    let currency = DataType::Dictionary(Box::new(DataType::Int32), Box::new(DataType::Utf8));
+    Arc::new(
+        Schema::new(vec![
+            text("reference", false),
+            text("account", false),
+            Field::new("amount", DataType::Decimal128(AMOUNT_PRECISION, AMOUNT_SCALE), false),
+            Field::new("currency", currency, false),
+            Field::new("value_date", DataType::Date32, false),
+            Field::new("booking_date", DataType::Date32, true),
+            text("description", false),
+            text("customer_reference", true),
+            text("bank_reference", true),
+            Field::new("reversal", DataType::Boolean, false),
+            text("transaction_type", true),
+            text("gvc", true),
+            text("category", true),
+            text("counterparty_name", true),
+            text("counterparty_account", true),
+            text("counterparty_bic", true),
+            text("remittance_information", true),
+        ])
+        .with_metadata(HashMap::from([("schema_version".to_string(), ARROW_SCHEMA_VERSION.to_string())])),
+    )
+}
+
+/// Сумма в единицах `10^-AMOUNT_SCALE`; ошибка, если она не помещается в колонку.
+fn amount(tx: &Transaction) -> Result<i128, ConvertError> {
+    let limit = 10i64.pow(u32::from(AMOUNT_PRECISION));
+    tx.amount
+        .rescale(AMOUNT_SCALE as u8)
+        .map(|money| money.minor_units())
+        .filter(|units| units.unsigned_abs() < limit as u64)
+        .map(i128::from)
+        .ok_or_else(|| {
+            ConvertError::InvalidAmount(format!(
+                "{} {} не помещается в Decimal128({}, {})",
+                tx.amount, tx.currency, AMOUNT_PRECISION, AMOUNT_SCALE
+            ))
+        })
+}
+
+fn strings<'a>(txs: &'a [Transaction], field: impl Fn(&'a Transaction) -> Option<&'a str>) -> ArrayRef {
+    Arc::new(txs.iter().map(field).collect::<StringArray>())
+}
+
+/// Транзакции одной порцией по схеме [`transaction_schema`].
+pub fn record_batch(txs: &[Transaction]) -> Result<RecordBatch, ConvertError> {
+    let amounts = txs.iter().map(amount).collect::<Result<Vec<_>, _>>()?;
+    let amounts = Decimal128Array::from(amounts)
+        .with_precision_and_scale(AMOUNT_PRECISION, AMOUNT_SCALE)
+        .map_err(arrow_error)?;
+    let currencies: DictionaryArray<Int32Type> = txs.iter().map(|tx| tx.currency.as_str()).collect();
+    let value_dates: Date32Array = txs.iter().map(|tx| Some(Date32Type::from_naive_date(tx.value_date))).collect();
+    let booking_dates: Date32Array = txs.iter().map(|tx| tx.booking_date.map(Date32Type::from_naive_date)).collect();
+    let reversals: BooleanArray = txs.iter().map(|tx| Some(tx.reversal)).collect();
+    let columns: Vec<ArrayRef> = vec![
+        strings(txs, |tx| Some(&tx.reference)),
+        strings(txs, |tx| Some(&tx.account)),
+        Arc::new(amounts),
+        Arc::new(currencies),
+        Arc::new(value_dates),
+        Arc::new(booking_dates),
+        strings(txs, |tx| Some(&tx.description)),
+        strings(txs, |tx| tx.customer_reference.as_deref()),
+        strings(txs, |tx| tx.bank_reference.as_deref()),
+        Arc::new(reversals),
+        strings(txs, |tx| tx.transaction_type.as_deref()),
+        strings(txs, |tx| tx.gvc.as_deref()),
+        strings(txs, |tx| tx.category.as_deref()),
+        strings(txs, |tx| tx.counterparty.as_ref().and_then(|c| c.name.as_deref())),
+        strings(txs, |tx| tx.counterparty.as_ref().and_then(|c| c.account.as_deref())),
+        strings(txs, |tx| tx.counterparty.as_ref().and_then(|c| c.bic.as_deref())),
+        strings(txs, |tx| tx.remittance_information.as_deref()),
+    ];
+    RecordBatch::try_new(transaction_schema(), columns).map_err(arrow_error)
+}
+
+#[cfg(test)]
+mod tests {
+    use arrow_array::{Array, cast::AsArray};
+    use chrono::NaiveDate;
+
+    use super::*;
+    use crate::{model::Counterparty, money::Money};
+
+    #[test]
+    fn batch_follows_schema() {
+        let tx = |amount: Money, currency: &str| Transaction {
+            reference: "ST1".to_string(),
+            account: "ACC".to_string(),
+            amount,
+            currency: currency.to_string(),
+            value_date: NaiveDate::from_ymd_opt(1970, 1, 3).unwrap(),
+            description: "Fee".to_string(),
+            ..Default::default()
+        };
+        let mut first = tx(Money::new(-10010, 2), "EUR");
+        first.counterparty = Some(Counterparty {
+            bic: Some("GKCCBEBB".to_string()),
+            ..Default::default()
+        });
+        let txs = [first, tx(Money::new(1500, 3), "KWD"), tx(Money::new(7, 0), "EUR")];
+        let batch = record_batch(&txs).unwrap();
+        assert_eq!(batch.schema(), transaction_schema());
+        assert_eq!(batch.num_rows(), 3);
+        assert_eq!(batch.schema().metadata()["schema_version"], ARROW_SCHEMA_VERSION.to_string());
+
+        let amounts = batch
+            .column_by_name("amount")
+            .unwrap()
+            .as_primitive::<arrow_array::types::Decimal128Type>();
+        assert_eq!(amounts.values(), &[-1_001_000, 15_000, 70_000]);
+        assert_eq!(amounts.value_as_string(0), "-100.1000");
+        let currencies = batch.column_by_name("currency").unwrap().as_dictionary::<Int32Type>();
+        assert_eq!(currencies.values().len(), 2);
+        assert_eq!(currencies.keys().values(), &[0, 1, 0]);
+        let dates = batch.column_by_name("value_date").unwrap().as_primitive::<Date32Type>();
+        assert_eq!(dates.value(0), 2);
+        assert!(batch.column_by_name("booking_date").unwrap().is_null(0));
+        let bics = batch.column_by_name("counterparty_bic").unwrap().as_string::<i32>();
+        assert_eq!(bics.value(0), "GKCCBEBB");
+        assert!(bics.is_null(1));
+
+        let precise = tx(Money::new(1, 5), "EUR");
+        assert!(matches!(record_batch(&[precise]), Err(ConvertError::InvalidAmount(_))));
+    }
+}
@@ -0,0 +1,483 @@
+//! Сопоставление колонок CSV полям [`Transaction`].
+//!
+//! Профиль описывается в TOML:
+//!
+//! ```toml
+//! reference = "Номер документа"
+//! account = 0
+//! debit = "Дебет"
+//! credit = "Кредит"
+//! default_currency = "RUB"
+//! value_date = "Дата"
+//! date_format = "%d.%m.%Y"
+//! description = "Назначение платежа"
+//! counterparty_name = "Контрагент"
+//! ```
+
+use std::path::Path;
+
+use chrono::NaiveDate;
+use parser::{CsvDialect, CsvRow, ParseError};
+use serde::Deserialize;
+
+use crate::{
+    error::ConvertError,
+    model::{Counterparty, Transaction},
+    money::{Money, currency_exponent, overflow},
+};
+
+/// Колонка CSV: по номеру (с нуля) или по имени из заголовка.
+#[derive(Debug, Clone, PartialEq, Deserialize)]
+#[serde(untagged)]
+pub enum CsvColumn {
+    /// Номер колонки, начиная с нуля.
+    Index(usize),
+    /// Имя колонки в заголовке, без учета регистра.
+    Name(String),
+}
+
+impl From<usize> for CsvColumn {
+    fn from(index: usize) -> Self {
+        CsvColumn::Index(index)
+    }
+}
+
+impl From<&str> for CsvColumn {
+    fn from(name: &str) -> Self {
+        CsvColumn::Name(name.to_string())
+    }
+}
+
+/// Профиль разбора CSV-выгрузки банка.
+///
+/// Значение по умолчанию: колонки 0/1/2 — референс, счет и описание, 3 — дата
+/// валютирования в `%Y-%m-%d`, без суммы. Наличие заголовка задается
+/// в [`parser::CsvDialect::has_header`].
+#[derive(Debug, Clone, PartialEq, Deserialize)]
+#[serde(default, deny_unknown_fields)]
+pub struct CsvMapping {
+    /// Сколько строк пропустить до заголовка или данных (шапка выгрузки).
+    pub skip_rows: usize,
+    /// Референс выписки.
+    pub reference: Option<CsvColumn>,
+    /// Номер счета.
+    pub account: Option<CsvColumn>,
+    /// Сумма со знаком.
+    pub amount: Option<CsvColumn>,
+    /// Сумма дебета (списание), если суммы разнесены по двум колонкам.
+    pub debit: Option<CsvColumn>,
+    /// Сумма кредита (зачисление).
+    pub credit: Option<CsvColumn>,
+    /// Валюта.
+    pub currency: Option<CsvColumn>,
+    /// Валюта, если колонки нет или значение пустое.
+    pub default_currency: String,
+    /// Дата валютирования.
+    pub value_date: Option<CsvColumn>,
+    /// Дата проводки.
+    pub booking_date: Option<CsvColumn>,
+    /// Формат дат в терминах [`chrono::format::strftime`], например `%d.%m.%Y`.
+    pub date_format: String,
+    /// Описание / назначение платежа.
+    pub description: Option<CsvColumn>,
+    /// Референс клиента.
+    pub customer_reference: Option<CsvColumn>,
+    /// Референс банка.
+    pub bank_reference: Option<CsvColumn>,
+    /// Наименование контрагента.
+    pub counterparty_name: Option<CsvColumn>,
+    /// Счет или IBAN контрагента.
+    pub counterparty_account: Option<CsvColumn>,
+    /// BIC банка контрагента.
+    pub counterparty_bic: Option<CsvColumn>,
+}
+
+impl Default for CsvMapping {
+    fn default() -> Self {
+        Self {
+            skip_rows: 0,
+            reference: Some(CsvColumn::Index(0)),
+            account: Some(CsvColumn::Index(1)),
+            amount: None,
+            debit: None,
+            credit: None,
+            currency: None,
+            default_currency: "XXX".to_string(),
+            value_date: Some(CsvColumn::Index(3)),
+            booking_date: None,
+            date_format: "%Y-%m-%d".to_string(),
+            description: Some(CsvColumn::Index(2)),
+            customer_reference: None,
+            bank_reference: None,
+            counterparty_name: None,
+            counterparty_account: None,
+            counterparty_bic: None,
+        }
+    }
+}
+
+/// Колонки профиля, сведенные к номерам по заголовку.
+struct Resolved {
+    reference: Option<usize>,
+    account: Option<usize>,
+    amount: Option<usize>,
+    debit: Option<usize>,
+    credit: Option<usize>,
+    currency: Option<usize>,
+    value_date: Option<usize>,
+    booking_date: Option<usize>,
+    description: Option<usize>,
+    customer_reference: Option<usize>,
+    bank_reference: Option<usize>,
+    counterparty_name: Option<usize>,
+    counterparty_account: Option<usize>,
+    counterparty_bic: Option<usize>,
+}
+
+impl Resolved {
+    /// Колонки, без которых транзакцию не собрать. Остальные (валюта, референсы,
+    /// контрагент) в короткой строке дают `None`.
+    fn required(&self) -> [Option<usize>; 8] {
+        [
+            self.reference,
+            self.account,
+            self.amount,
+            self.debit,
+            self.credit,
+            self.value_date,
+            self.booking_date,
+            self.description,
+        ]
+    }
+}
+
+fn cell(row: &CsvRow, column: Option<usize>) -> Option<&str> {
+    column
+        .and_then(|index| row.row.get(index))
+        .map(|value| value.trim())
+        .filter(|value| !value.is_empty())
+}
+
+/// Сумма из ячейки по диалекту: при десятичной запятой (`1.234,56`) точка — разделитель
+/// разрядов, иначе (`1,234.56`) — запятая. Пробелы и апострофы — тоже разделители разрядов.
+///
+/// Разделитель разрядов допускается только между группами по три цифры: `1,2` или `12,50`
+/// при десятичной точке — ошибка, а не угадывание.
+fn parse_amount(value: &str, exponent: u8, decimal_comma: bool) -> Result<Money, ConvertError> {
+    let (decimal, group) = if decimal_comma { (',', '.') } else { ('.', ',') };
+    let cleaned: String = value.chars().filter(|c| !c.is_whitespace() && *c != '\'').collect();
+    let integer = cleaned.split(decimal).next().unwrap_or_default();
+    let integer = integer.trim_start_matches(['-', '+']);
+    let mut groups = integer.split(group);
+    let first = groups.next().unwrap_or_default();
+    let grouped = groups.all(|g| g.len() == 3) && (integer.len() == first.len() || (1..=3).contains(&first.len()));
+    if !grouped || cleaned.matches(decimal).count() > 1 {
+        return Err(ConvertError::InvalidAmount(format!(
+            "{} (разделитель дробной части — '{}')",
+            value, decimal
+        )));
+    }
+    let cleaned: String = cleaned.chars().filter(|c| *c != group).collect();
+    Money::parse(&cleaned, exponent)
+}
+
+impl CsvMapping {
+    /// Читает профиль из TOML-строки.
+    pub fn from_toml(input: &str) -> Result<Self, ConvertError> {
+        toml::from_str(input).map_err(|e| ConvertError::Profile(e.to_string()))
+    }
+
+    /// Читает профиль из TOML-файла.
+    pub fn load(path: impl AsRef<Path>) -> Result<Self, ConvertError> {
+        Self::from_toml(&std::fs::read_to_string(path)?)
+    }
+
+    /// Номера колонок по заголовку. Профиль без даты валютирования и без даты проводки
+    /// отклоняется: подставлять 1970-01-01 нельзя.
+    fn resolve(&self, header: Option<&CsvRow>) -> Result<Resolved, ConvertError> {
+        if self.value_date.is_none() && self.booking_date.is_none() {
+            return Err(ConvertError::Profile("не задана ни value_date, ни booking_date".to_string()));
+        }
+        let index = |column: &Option<CsvColumn>| -> Result<Option<usize>, ConvertError> {
+            match column {
+                None => Ok(None),
+                Some(CsvColumn::Index(index)) => Ok(Some(*index)),
+                Some(CsvColumn::Name(name)) => {
+                    let header = header
+                        .ok_or_else(|| ConvertError::Profile(format!("колонка \"{}\" задана по имени, но заголовка нет", name)))?;
+                    let name = name.trim().to_lowercase();
+                    header
+                        .row
+                        .iter()
+                        .position(|h| h.trim().to_lowercase() == name)
+                        .map(Some)
+                        .ok_or_else(|| ConvertError::Profile(format!("нет колонки \"{}\" в заголовке", name)))
+                }
+            }
+        };
+        Ok(Resolved {
+            reference: index(&self.reference)?,
+            account: index(&self.account)?,
+            amount: index(&self.amount)?,
+            debit: index(&self.debit)?,
+            credit: index(&self.credit)?,
+            currency: index(&self.currency)?,
+            value_date: index(&self.value_date)?,
+            booking_date: index(&self.booking_date)?,
+            description: index(&self.description)?,
+            customer_reference: index(&self.customer_reference)?,
+            bank_reference: index(&self.bank_reference)?,
+            counterparty_name: index(&self.counterparty_name)?,
+            counterparty_account: index(&self.counterparty_account)?,
+            counterparty_bic: index(&self.counterparty_bic)?,
+        })
+    }
+
+    /// Номер колонки валюты: по нему чтение xlsx округляет числа до знаков валюты строки.
+    pub(crate) fn currency_column(&self, header: Option<&CsvRow>) -> Option<usize> {
+        self.resolve(header).ok()?.currency
+    }
+
+    /// Номер строки заголовка: первая строка, где есть все колонки, заданные по имени.
+    ///
+    /// `None`, если по имени не задано ни одной колонки или такой строки нет.
+    pub(crate) fn header_row(&self, rows: &[CsvRow]) -> Option<usize> {
+        let columns = [
+            &self.reference,
+            &self.account,
+            &self.amount,
+            &self.debit,
+            &self.credit,
+            &self.currency,
+            &self.value_date,
+            &self.booking_date,
+            &self.description,
+            &self.customer_reference,
+            &self.bank_reference,
+            &self.counterparty_name,
+            &self.counterparty_account,
+            &self.counterparty_bic,
+        ];
+        let names: Vec<String> = columns
+            .into_iter()
+            .filter_map(|column| match column {
+                Some(CsvColumn::Name(name)) => Some(name.trim().to_lowercase()),
+                _ => None,
+            })
+            .collect();
+        if names.is_empty() {
+            return None;
+        }
+        rows.iter()
+            .position(|row| names.iter().all(|name| row.row.iter().any(|h| h.trim().to_lowercase() == *name)))
+    }
+
+    fn parse_date(&self, value: &str) -> Result<NaiveDate, ConvertError> {
+        NaiveDate::parse_from_str(value, &self.date_format)
+            .map_err(|e| ConvertError::InvalidDate(format!("{} (формат {}): {}", value, self.date_format, e)))
+    }
+
+    /// Строки CSV в транзакции. Строка короче обязательных колонок — ошибка с ее номером.
+    pub(crate) fn transactions(&self, rows: &[CsvRow], dialect: &CsvDialect) -> Result<Vec<Transaction>, ConvertError> {
+        let rows = rows.get(self.skip_rows..).unwrap_or_default();
+        let (header, data) = match rows.split_first() {
+            Some((header, data)) if dialect.has_header => (Some(header), data),
+            _ => (None, rows),
+        };
+        let columns = self.resolve(header)?;
+        let width = columns.required().into_iter().flatten().max().map_or(0, |max| max + 1);
+        // Номер первой строки данных в файле, с единицы.
+        let first_line = self.skip_rows + usize::from(header.is_some()) + 1;
+
+        let mut txs = Vec::new();
+        for (line, row) in data.iter().enumerate().map(|(index, row)| (first_line + index, row)) {
+            if row.row.len() < width {
+                return Err(ParseError::InvalidFormat(format!(
+                    "CSV: в строке {} колонок {}, профилю нужно не меньше {}",
+                    line,
+                    row.row.len(),
+                    width
+                ))
+                .into());
+            }
+            let text = |column: Option<usize>| cell(row, column).unwrap_or_default().to_string();
+            let optional = |column: Option<usize>| cell(row, column).map(str::to_string);
+
+            let currency = cell(row, columns.currency).unwrap_or(&self.default_currency).to_uppercase();
+            let exponent = currency_exponent(&currency);
+            let mut amount = Money::zero(exponent);
+            if let Some(value) = cell(row, columns.amount) {
+                amount = parse_amount(value, exponent, dialect.decimal_comma)?;
+            }
+            if let Some(value) = cell(row, columns.credit) {
+                amount = amount
+                    .checked_add(parse_amount(value, exponent, dialect.decimal_comma)?.abs())
+                    .ok_or_else(overflow)?;
+            }
+            if let Some(value) = cell(row, columns.debit) {
+                amount = amount
+                    .checked_sub(parse_amount(value, exponent, dialect.decimal_comma)?.abs())
+                    .ok_or_else(overflow)?;
+            }
+
+            let booking_date = cell(row, columns.booking_date).map(|d| self.parse_date(d)).transpose()?;
+            let value_date = match cell(row, columns.value_date) {
+                Some(date) => self.parse_date(date)?,
+                None if columns.value_date.is_some() => booking_date
+                    .ok_or_else(|| ConvertError::InvalidDate(format!("пустая дата валютирования в строке {:?}", row.row)))?,
+                None => {
+                    booking_date.ok_or_else(|| ConvertError::InvalidDate(format!("пустая дата проводки в строке {:?}", row.row)))?
+                }
+            };
+
+            let counterparty = Counterparty {
+                name: optional(columns.counterparty_name),
+                account: optional(columns.counterparty_account),
+                bic: optional(columns.counterparty_bic),
+                ..Default::default()
+            };
+            txs.push(Transaction {
+                reference: text(columns.reference),
+                account: text(columns.account),
+                amount,
+                currency,
+                value_date,
+                booking_date,
+                customer_reference: optional(columns.customer_reference),
+                bank_reference: optional(columns.bank_reference),
+                counterparty: Some(counterparty).filter(|c| !c.is_empty()),
+                description: text(columns.description),
+                ..Default::default()
+            });
+        }
+        Ok(txs)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn rows(lines: &[&[&str]]) -> Vec<CsvRow> {
+        lines
+            .iter()
+            .map(|line| CsvRow {
+                row: line.iter().map(|s| s.to_string()).collect(),
+            })
+            .collect()
+    }
+
+    #[test]
+    fn profile_maps_columns_by_name() {
+        let mapping = CsvMapping::from_toml(
+            r#"
+            skip_rows = 1
+            reference = "Номер"
+            account = 5
+            debit = "Дебет"
+            credit = "Кредит"
+            default_currency = "RUB"
+            value_date = "Дата"
+            date_format = "%d.%m.%Y"
+            description = "назначение"
+            counterparty_name = "Контрагент"
+            "#,
+        )
+        .unwrap();
+        let rows = rows(&[
+            &["Выписка за январь"],
+            &["Номер", "Дата", "Дебет", "Кредит", "Назначение", "Счет", "Контрагент"],
+            &["1", "01.02.2023", "1 500,50", "", "Аренда", "40702810900000000001", "ООО Ромашка"],
+            &["2", "02.02.2023", "", "100", "Возврат", "40702810900000000001"],
+        ]);
+        let dialect = CsvDialect {
+            decimal_comma: true,
+            ..Default::default()
+        };
+        let txs = mapping.transactions(&rows, &dialect).unwrap();
+        assert_eq!(txs.len(), 2);
+        assert_eq!(txs[0].amount, Money::new(-150050, 2));
+        assert_eq!(txs[0].currency, "RUB");
+        assert_eq!(txs[0].value_date, NaiveDate::from_ymd_opt(2023, 2, 1).unwrap());
+        assert_eq!(txs[0].account, "40702810900000000001");
+        assert_eq!(txs[0].description, "Аренда");
+        assert_eq!(txs[0].counterparty.as_ref().unwrap().name.as_deref(), Some("ООО Ромашка"));
+        assert_eq!(txs[1].amount, Money::new(10000, 2));
+        assert_eq!(txs[1].counterparty, None);
+
+        // Строка итогов короче обязательных колонок: ошибка, а не молчаливый пропуск.
+        let mut rows = rows;
+        rows.push(CsvRow {
+            row: vec!["итого".to_string()],
+        });
+        let error = mapping.transactions(&rows, &dialect).unwrap_err().to_string();
+        assert!(error.contains("в строке 5"), "{}", error);
+    }
+
+    #[test]
+    fn signed_amount_and_currency_column() {
+        let dialect = CsvDialect {
+            has_header: false,
+            ..Default::default()
+        };
+        let mapping = CsvMapping {
+            amount: Some(3.into()),
+            currency: Some(4.into()),
+            value_date: Some(2.into()),
+            description: None,
+            ..Default::default()
+        };
+        let txs = mapping.transactions(&rows(&[&["R1", "ACC", "2023-01-05", "-12.5", "jpy"]]), &dialect);
+        assert!(matches!(txs, Err(ConvertError::InvalidAmount(_))), "иены без дробной части");
+        let txs = mapping
+            .transactions(&rows(&[&["R1", "ACC", "2023-01-05", "-1,012.5", "eur"]]), &dialect)
+            .unwrap();
+        assert_eq!(txs[0].amount, Money::new(-101250, 2));
+        assert_eq!(txs[0].currency, "EUR");
+
+        let dialect = CsvDialect {
+            decimal_comma: true,
+            ..dialect
+        };
+        let txs = mapping
+            .transactions(&rows(&[&["R1", "ACC", "2023-01-05", "1.012,5", "eur"]]), &dialect)
+            .unwrap();
+        assert_eq!(txs[0].amount, Money::new(101250, 2));
+    }
+
+    #[test]
+    fn comma_is_grouping_with_decimal_point() {
+        assert_eq!(parse_amount("1,200", 2, false).unwrap(), Money::new(120000, 2));
+        assert_eq!(parse_amount("1,200.50", 2, false).unwrap(), Money::new(120050, 2));
+        assert_eq!(parse_amount("-12,500", 2, false).unwrap(), Money::new(-1250000, 2));
+        assert_eq!(parse_amount("1 200,50", 2, true).unwrap(), Money::new(120050, 2));
+        assert!(matches!(parse_amount("100,50", 2, false), Err(ConvertError::InvalidAmount(_))));
+        assert!(matches!(parse_amount("1,2", 2, false), Err(ConvertError::InvalidAmount(_))));
+        assert!(matches!(parse_amount("1.2.3", 2, false), Err(ConvertError::InvalidAmount(_))));
+        assert!(matches!(parse_amount("1.200", 2, true), Ok(m) if m == Money::new(120000, 2)));
+    }
+
+    #[test]
+    fn profile_errors() {
+        assert!(matches!(CsvMapping::from_toml("unknown = 1"), Err(ConvertError::Profile(_))));
+        let mapping = CsvMapping {
+            amount: Some("Сумма".into()),
+            ..Default::default()
+        };
+        let result = mapping.transactions(&rows(&[&["a", "b", "c"], &["1", "2", "3"]]), &CsvDialect::default());
+        assert!(matches!(result, Err(ConvertError::Profile(_))));
+        let mapping = CsvMapping {
+            value_date: Some(2.into()),
+            ..Default::default()
+        };
+        let result = mapping.transactions(&rows(&[&["a", "b", "c"], &["1", "2", "31.02.2023"]]), &CsvDialect::default());
+        assert!(matches!(result, Err(ConvertError::InvalidDate(_))));
+        // Без колонки даты транзакции получили бы 1970-01-01.
+        let mapping = CsvMapping {
+            value_date: None,
+            ..Default::default()
+        };
+        let result = mapping.transactions(&rows(&[&["a", "b", "c"]]), &CsvDialect::default());
+        assert!(matches!(result, Err(ConvertError::Profile(_))));
+    }
+}
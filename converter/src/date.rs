@@ -0,0 +1,139 @@
+//! Разбор дат из банковских форматов.
+
+use chrono::{Datelike, NaiveDate, NaiveDateTime, NaiveTime};
+
+use crate::{error::ConvertError, options::QifDateFormat};
+
+/// Дата формата YYMMDD (`230131`).
+///
+/// Годы `00..pivot` относятся к 20xx, `pivot..=99` — к 19xx.
+pub(crate) fn parse_yymmdd(s: &str, century_pivot: u8) -> Result<NaiveDate, ConvertError> {
+    let invalid = || ConvertError::InvalidDate(s.to_string());
+    if s.len() != 6 || !s.bytes().all(|b| b.is_ascii_digit()) {
+        return Err(invalid());
+    }
+    let yy: i32 = s[0..2].parse().map_err(|_| invalid())?;
+    let month: u32 = s[2..4].parse().map_err(|_| invalid())?;
+    let day: u32 = s[4..6].parse().map_err(|_| invalid())?;
+    let year = if yy < i32::from(century_pivot) { 2000 + yy } else { 1900 + yy };
+    NaiveDate::from_ymd_opt(year, month, day).ok_or_else(invalid)
+}
+
+/// Дата формата MMDD без года, ближайшая к `anchor` (не дальше полугода).
+///
+/// Так в MT940 записывается дата проводки рядом с датой валютирования:
+/// `:61:2312290102` — валютирование 29.12.2023, проводка 02.01.2024.
+pub(crate) fn parse_mmdd_near(s: &str, anchor: NaiveDate) -> Result<NaiveDate, ConvertError> {
+    let invalid = || ConvertError::InvalidDate(s.to_string());
+    if s.len() != 4 || !s.bytes().all(|b| b.is_ascii_digit()) {
+        return Err(invalid());
+    }
+    let month: u32 = s[0..2].parse().map_err(|_| invalid())?;
+    let day: u32 = s[2..4].parse().map_err(|_| invalid())?;
+    [anchor.year(), anchor.year() - 1, anchor.year() + 1]
+        .into_iter()
+        .filter_map(|year| NaiveDate::from_ymd_opt(year, month, day))
+        .min_by_key(|date| (*date - anchor).num_days().abs())
+        .filter(|date| (*date - anchor).num_days().abs() <= 183)
+        .ok_or_else(invalid)
+}
+
+/// Дата и время OFX: `YYYYMMDD[HHMM[SS[.XXX]]][[-5:EST]]`.
+///
+/// Часовой пояс отбрасывается: дата берется такой, как ее записал банк.
+pub(crate) fn parse_ofx_date_time(s: &str) -> Result<NaiveDateTime, ConvertError> {
+    let invalid = || ConvertError::InvalidDate(s.to_string());
+    let digits = s.trim().split(['.', '[']).next().unwrap_or_default();
+    if digits.len() < 8 || !digits.bytes().all(|b| b.is_ascii_digit()) {
+        return Err(invalid());
+    }
+    let date = NaiveDate::parse_from_str(&digits[..8], "%Y%m%d").map_err(|_| invalid())?;
+    let time = match digits.len() {
+        8 => NaiveTime::MIN,
+        12 => NaiveTime::parse_from_str(&digits[8..], "%H%M").map_err(|_| invalid())?,
+        14 => NaiveTime::parse_from_str(&digits[8..], "%H%M%S").map_err(|_| invalid())?,
+        _ => return Err(invalid()),
+    };
+    Ok(date.and_time(time))
+}
+
+/// Дата QIF: `01/05/2023`, `1/ 5/23`, `1/5'23`, `05.01.2023`.
+///
+/// Апостроф перед годом означает 20xx; двузначный год без него — по `century_pivot`.
+pub(crate) fn parse_qif_date(s: &str, format: QifDateFormat, century_pivot: u8) -> Result<NaiveDate, ConvertError> {
+    let invalid = || ConvertError::InvalidDate(s.to_string());
+    let parts: Vec<&str> = s.trim().split(['/', '.', '-', '\'']).map(str::trim).collect();
+    let [first, second, year] = parts[..] else {
+        return Err(invalid());
+    };
+    let number = |part: &str| part.parse::<u32>().map_err(|_| invalid());
+    let (month, day) = match format {
+        QifDateFormat::Us => (number(first)?, number(second)?),
+        QifDateFormat::European => (number(second)?, number(first)?),
+    };
+    let year = match year.len() {
+        4 => number(year)? as i32,
+        2 if s.contains('\'') => 2000 + number(year)? as i32,
+        2 => parse_yymmdd(&format!("{}0101", year), century_pivot)?.year(),
+        _ => return Err(invalid()),
+    };
+    NaiveDate::from_ymd_opt(year, month, day).ok_or_else(invalid)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn yymmdd_uses_century_pivot() {
+        assert_eq!(parse_yymmdd("230131", 70).unwrap(), NaiveDate::from_ymd_opt(2023, 1, 31).unwrap());
+        assert_eq!(parse_yymmdd("991231", 70).unwrap(), NaiveDate::from_ymd_opt(1999, 12, 31).unwrap());
+        assert_eq!(parse_yymmdd("991231", 100).unwrap(), NaiveDate::from_ymd_opt(2099, 12, 31).unwrap());
+    }
+
+    #[test]
+    fn yymmdd_rejects_invalid_dates() {
+        assert!(parse_yymmdd("230230", 70).is_err());
+        assert!(parse_yymmdd("231301", 70).is_err());
+        assert!(parse_yymmdd("2301", 70).is_err());
+        assert!(parse_yymmdd("23O101", 70).is_err());
+    }
+
+    #[test]
+    fn mmdd_crosses_year_boundary() {
+        let anchor = NaiveDate::from_ymd_opt(2023, 12, 29).unwrap();
+        assert_eq!(
+            parse_mmdd_near("0102", anchor).unwrap(),
+            NaiveDate::from_ymd_opt(2024, 1, 2).unwrap()
+        );
+        assert_eq!(
+            parse_mmdd_near("1228", anchor).unwrap(),
+            NaiveDate::from_ymd_opt(2023, 12, 28).unwrap()
+        );
+        assert!(parse_mmdd_near("0230", anchor).is_err());
+    }
+
+    #[test]
+    fn ofx_date_time_ignores_time_zone() {
+        let date = NaiveDate::from_ymd_opt(2023, 1, 31).unwrap();
+        assert_eq!(parse_ofx_date_time("20230131").unwrap(), date.and_hms_opt(0, 0, 0).unwrap());
+        assert_eq!(
+            parse_ofx_date_time("20230131120000.000[-5:EST]").unwrap(),
+            date.and_hms_opt(12, 0, 0).unwrap()
+        );
+        assert_eq!(parse_ofx_date_time("202301312359").unwrap(), date.and_hms_opt(23, 59, 0).unwrap());
+        assert!(parse_ofx_date_time("20230231").is_err());
+        assert!(parse_ofx_date_time("2023013112").is_err());
+    }
+
+    #[test]
+    fn qif_date_formats() {
+        let date = NaiveDate::from_ymd_opt(2023, 1, 5).unwrap();
+        assert_eq!(parse_qif_date("01/05/2023", QifDateFormat::Us, 70).unwrap(), date);
+        assert_eq!(parse_qif_date("1/ 5'23", QifDateFormat::Us, 70).unwrap(), date);
+        assert_eq!(parse_qif_date("05.01.23", QifDateFormat::European, 70).unwrap(), date);
+        assert_eq!(parse_qif_date("5/1/99", QifDateFormat::European, 70).unwrap().year(), 1999);
+        assert!(parse_qif_date("13/05/2023", QifDateFormat::Us, 70).is_err());
+        assert!(parse_qif_date("2023-01", QifDateFormat::Us, 70).is_err());
+    }
+}
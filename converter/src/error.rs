@@ -13,6 +13,21 @@ pub enum ConvertError {
     /// и указывает, что входные данные не удалось правильно проанализировать
     #[error("Parse error: {0}")]
     Parse(#[from] ParseError),
+    /// Ошибка записи результата.
+    #[error("IO error: {0}")]
+    Io(#[from] std::io::Error),
+    /// Сумма не разобрана или не может быть представлена без потери точности.
+    #[error("Invalid amount: {0}")]
+    InvalidAmount(String),
+    /// Дата не разобрана или не существует в календаре.
+    #[error("Invalid date: {0}")]
+    InvalidDate(String),
+    /// IBAN или BIC не прошли проверку.
+    #[error("Invalid account: {0}")]
+    InvalidAccount(String),
+    /// Профиль настроек (например, сопоставление колонок CSV) не разобран или не подходит к файлу.
+    #[error("Invalid profile: {0}")]
+    Profile(String),
     /// Запрошенное преобразование не поддерживается.
     /// Содержит имена исходного и целевого типов в виде строк.
     /// Например: `from = "Csv"`, `to = "Camt053"`.
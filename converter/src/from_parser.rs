@@ -1,100 +1,336 @@
-use crate::model::Transaction;
-use parser::{Camt053Parser, CsvParser, Mt940Parser};
+pub(crate) mod bai2;
+pub(crate) mod camt;
+pub(crate) mod client_bank;
+pub(crate) mod coda;
+mod confirmation;
+mod json;
+pub(crate) mod ofx;
+mod qif;
+mod xlsx;
+
+use chrono::{NaiveDateTime, NaiveTime};
+
+use crate::{
+    date::{parse_mmdd_near, parse_yymmdd},
+    error::ConvertError,
+    model::{Balance, BalanceKind, Statement, Transaction},
+    money::{Money, currency_exponent, overflow},
+    options::ConvertOptions,
+};
+use camt::CamtMessage;
+use parser::{
+    Bai2Parser, Camt052Parser, Camt053Parser, Camt054Parser, ClientBank1CParser, CodaParser, CsvParser, JsonLinesParser, JsonParser,
+    Mt900Parser, Mt910Parser, Mt940Message, Mt940Parser, Mt942Parser, Mt950Parser, OfxParser, ParseError, QifParser, XlsxParser,
+};
 use regex::Regex;
 
 /// Трэйт для *Parser.
 pub trait FromParser {
-    /// Конвертация в определенный формат.
-    fn to_transactions(&self) -> Vec<Transaction>;
+    /// Конвертация в выписки [`Statement`].
+    fn to_statements(&self, options: &ConvertOptions) -> Result<Vec<Statement>, ConvertError>;
+
+    /// Все записи всех выписок одним списком.
+    fn to_transactions(&self, options: &ConvertOptions) -> Result<Vec<Transaction>, ConvertError> {
+        Ok(self.to_statements(options)?.into_iter().flat_map(|st| st.entries).collect())
+    }
 }
 
 impl FromParser for CsvParser {
-    fn to_transactions(&self) -> Vec<Transaction> {
-        let mut txs = Vec::new();
-        for row in self.rows.iter().skip(1) {
-            if row.row.len() < 3 {
-                continue;
-            }
-            txs.push(Transaction {
-                reference: row.row[0].clone(),
-                account: row.row[1].clone(),
-                amount: 0.0,
-                currency: "XXX".to_string(),
-                value_date: "1970-01-01".to_string(),
-                description: row.row[2].clone(),
-            });
-        }
-        txs
+    fn to_statements(&self, options: &ConvertOptions) -> Result<Vec<Statement>, ConvertError> {
+        let txs = options.csv_mapping.transactions(&self.rows, &self.dialect)?;
+        Ok(vec![Statement::from_entries(txs)])
     }
 }
-impl FromParser for Mt940Parser {
-    fn to_transactions(&self) -> Vec<Transaction> {
-        let mut txs: Vec<Transaction> = Vec::new();
-        let mut reference = String::new();
-        let mut account = String::new();
 
-        let re = Regex::new(r"^[0-9]{6,10}(C|D)R?([0-9,]+)").unwrap();
-        for record in &self.data {
+/// Сальдо MT940: `C230101USD1000,00` — знак, YYMMDD, валюта, сумма.
+fn parse_mt940_balance(kind: BalanceKind, value: &str, options: &ConvertOptions) -> Result<Balance, ConvertError> {
+    let invalid = || ParseError::InvalidFormat(format!("Invalid MT940 balance: {}", value));
+    let mark = value.get(..1).ok_or_else(invalid)?;
+    let date = value.get(1..7).ok_or_else(invalid)?;
+    let currency = value.get(7..10).ok_or_else(invalid)?;
+    let amount = value.get(10..).ok_or_else(invalid)?;
+
+    let amount = Money::parse(amount, currency_exponent(currency))?;
+    Ok(Balance {
+        kind,
+        amount: match mark {
+            "C" => amount,
+            "D" => amount.checked_neg().ok_or_else(overflow)?,
+            _ => return Err(invalid().into()),
+        },
+        currency: currency.to_string(),
+        date: parse_yymmdd(date, options.century_pivot)?,
+    })
+}
+
+/// Порог MT942 `:34F:EURD100,`: валюта, необязательный признак D/C, сумма.
+fn parse_mt942_floor_limit(value: &str) -> Result<(String, Option<char>, Money), ConvertError> {
+    let invalid = || ParseError::InvalidFormat(format!("Invalid MT942 floor limit: {}", value));
+    let currency = value.get(..3).ok_or_else(invalid)?;
+    let rest = value.get(3..).ok_or_else(invalid)?;
+    let mark = rest.chars().next().filter(|c| matches!(c, 'D' | 'C'));
+    let amount = Money::parse(&rest[mark.map_or(0, char::len_utf8)..], currency_exponent(currency))?;
+    Ok((currency.to_string(), mark, amount))
+}
+
+/// Время MT942 `:13D:2301021030+0100`; смещение в модели не хранится и отбрасывается.
+fn parse_mt942_date_time(value: &str, options: &ConvertOptions) -> Result<NaiveDateTime, ConvertError> {
+    let invalid = || ConvertError::InvalidDate(value.to_string());
+    let date = parse_yymmdd(value.get(..6).ok_or_else(invalid)?, options.century_pivot)?;
+    let time = value
+        .get(6..10)
+        .and_then(|t| NaiveTime::parse_from_str(t, "%H%M").ok())
+        .ok_or_else(invalid)?;
+    Ok(date.and_time(time))
+}
+
+/// Итог MT942 `:90D:2EUR150,00` сверяется с записями своего знака.
+fn check_mt942_total(tag: &str, value: &str, statement: &Statement) -> Result<(), ConvertError> {
+    let invalid = |msg: &str| ConvertError::from(ParseError::InvalidFormat(format!("Invalid MT942 :{}:{} — {}", tag, value, msg)));
+    let digits = value.bytes().take_while(u8::is_ascii_digit).count();
+    let count: usize = value[..digits].parse().map_err(|_| invalid("нет числа операций"))?;
+    let currency = value.get(digits..digits + 3).ok_or_else(|| invalid("нет валюты"))?;
+    let sum = Money::parse(&value[digits + 3..], currency_exponent(currency))?;
+    let debit = tag == "90D";
+    let entries: Vec<&Transaction> = statement.entries.iter().filter(|tx| tx.amount.is_negative() == debit).collect();
+    let amounts = entries.iter().map(|tx| tx.amount.abs());
+    let actual = Money::checked_sum(std::iter::once(Money::zero(sum.exponent())).chain(amounts)).ok_or_else(overflow)?;
+    if entries.len() != count || actual != sum {
+        return Err(invalid(&format!("в отчете {} операций на {}", entries.len(), actual)));
+    }
+    Ok(())
+}
+
+/// Выписки из полей MT940/MT942/MT950: у MT942 нет сальдо, но есть `:34F:`, `:13D:` и итоги `:90D:`/`:90C:`.
+fn swift_statements(messages: &[Mt940Message], options: &ConvertOptions) -> Result<Vec<Statement>, ConvertError> {
+    let mut statements: Vec<Statement> = Vec::new();
+
+    // :61: 6!n[4!n]2a[1!a]15d1!a3!c16x[//16x] + [34x] на второй строке:
+    // дата валютирования, дата проводки, C/D/RC/RD, funds code, сумма,
+    // код типа операции, референс клиента, референс банка.
+    let re = Regex::new(r"^([0-9]{6})([0-9]{4})?(RC|RD|C|D)([A-Z])?([0-9]+,?[0-9]*)(?:([SNF][A-Z0-9]{3})(.*?)(?://(.*))?)?$").unwrap();
+    for message in messages {
+        for (index, record) in message.records.iter().enumerate() {
+            // Каждое сообщение и каждый :20: внутри него начинают новую выписку.
+            if record.tag == "20" || index == 0 {
+                statements.push(Statement {
+                    sender_bic: message.header.sender_bic(),
+                    receiver_bic: message.header.receiver_bic(),
+                    ..Default::default()
+                });
+            }
+            let statement = statements.last_mut().expect("выписка добавлена выше");
+
             match record.tag.as_str() {
-                "20" => reference = record.value.clone(),
-                "25" => account = record.value.clone(),
+                "20" => statement.id = record.value.clone(),
+                "25" => statement.account = record.value.clone(),
+                "28C" => statement.sequence_number = Some(record.value.clone()),
+                "60F" | "60M" => {
+                    let kind = if record.tag == "60F" {
+                        BalanceKind::Opening
+                    } else {
+                        BalanceKind::InterimOpening
+                    };
+                    let balance = parse_mt940_balance(kind, &record.value, options)?;
+                    // Валюта выписки берется из начального сальдо: C230101USD1000,00
+                    statement.currency = balance.currency.clone();
+                    statement.opening_balance = Some(balance);
+                }
+                "62F" | "62M" => {
+                    let kind = if record.tag == "62F" {
+                        BalanceKind::Closing
+                    } else {
+                        BalanceKind::InterimClosing
+                    };
+                    statement.closing_balance = Some(parse_mt940_balance(kind, &record.value, options)?);
+                }
+                "64" => {
+                    let balance = parse_mt940_balance(BalanceKind::ClosingAvailable, &record.value, options)?;
+                    statement.available_balances.push(balance);
+                }
+                "65" => {
+                    let balance = parse_mt940_balance(BalanceKind::ForwardAvailable, &record.value, options)?;
+                    statement.available_balances.push(balance);
+                }
                 "61" => {
-                    let mut current = Transaction {
-                        reference: reference.clone(),
-                        account: account.clone(),
-                        amount: 0.0,
-                        currency: "XXX".to_string(),
-                        value_date: "1970-01-01".to_string(),
-                        description: "".to_string(),
+                    let value = &record.value;
+                    let (line, supplementary) = match value.split_once('\n') {
+                        Some((line, rest)) => (line, Some(rest.replace('\n', " "))),
+                        None => (value.as_str(), None),
                     };
+                    let caps = re
+                        .captures(line.trim_end())
+                        .ok_or_else(|| ParseError::InvalidFormat(format!("Invalid :61: line. Нет даты, C/D или суммы: {}", value)))?;
 
-                    let value = &record.value;
+                    let currency = if statement.currency.is_empty() {
+                        "XXX"
+                    } else {
+                        &statement.currency
+                    };
+                    let value_date = parse_yymmdd(&caps[1], options.century_pivot)?;
+                    let booking_date = caps.get(2).map(|m| parse_mmdd_near(m.as_str(), value_date)).transpose()?;
+                    let amount = Money::parse(&caps[5], currency_exponent(currency))?;
+                    // RC — сторно кредита, т.е. дебетовая проводка; RD — наоборот.
+                    let debit = matches!(&caps[3], "D" | "RC");
+                    let text = |index: usize| {
+                        caps.get(index)
+                            .map(|m| m.as_str().trim())
+                            .filter(|s| !s.is_empty())
+                            .map(str::to_string)
+                    };
 
-                    if value.len() >= 6 {
-                        let date_part = &value[..6];
-                        current.value_date = format!("20{}-{}-{}", &date_part[0..2], &date_part[2..4], &date_part[4..6]);
+                    statement.entries.push(Transaction {
+                        reference: statement.id.clone(),
+                        account: statement.account.clone(),
+                        amount: if debit {
+                            amount.checked_neg().ok_or_else(overflow)?
+                        } else {
+                            amount
+                        },
+                        currency: currency.to_string(),
+                        value_date,
+                        booking_date,
+                        customer_reference: text(7).filter(|r| r != "NONREF"),
+                        bank_reference: text(8),
+                        reversal: caps[3].starts_with('R'),
+                        funds_code: caps.get(4).and_then(|m| m.as_str().chars().next()),
+                        transaction_type: text(6),
+                        supplementary_details: supplementary.filter(|s| !s.trim().is_empty()),
+                        description: "".to_string(),
+                        ..Default::default()
+                    });
+                }
+                "34F" => {
+                    let (currency, mark, limit) = parse_mt942_floor_limit(&record.value)?;
+                    if statement.currency.is_empty() {
+                        statement.currency = currency;
                     }
-
-                    if let Some(caps) = re.captures(value) {
-                        let op_type = &caps[1];
-                        let amount_str = &caps[2];
-                        let amount_clean = amount_str.replace(',', ".");
-                        if let Ok(mut amount) = amount_clean.parse::<f64>() {
-                            if op_type == "D" {
-                                amount = -amount;
-                            }
-                            current.amount = amount;
-                        }
+                    if mark != Some('C') {
+                        statement.debit_floor_limit = Some(limit);
+                    }
+                    if mark != Some('D') {
+                        statement.credit_floor_limit = Some(limit);
                     }
-                    txs.push(current);
                 }
+                "13D" => statement.created_at = Some(parse_mt942_date_time(&record.value, options)?),
+                "90D" | "90C" => check_mt942_total(&record.tag, &record.value, statement)?,
                 "86" => {
-                    if let Some(last) = txs.last_mut() {
-                        last.description = record.value.clone()
+                    if let Some(last) = statement.entries.last_mut() {
+                        // Исходный текст сохраняется для записи MT940 без потерь.
+                        last.description = record.value.clone();
+                        if let Some(narrative) = options.narrative_dialects.iter().find_map(|d| d.parse(&record.value)) {
+                            last.gvc = narrative.gvc;
+                            last.counterparty = Some(narrative.counterparty).filter(|c| !c.is_empty());
+                            last.remittance_information = narrative.remittance_information;
+                        }
                     }
                 }
                 _ => {}
             }
         }
-        txs
+    }
+    Ok(statements)
+}
+
+impl FromParser for Mt940Parser {
+    fn to_statements(&self, options: &ConvertOptions) -> Result<Vec<Statement>, ConvertError> {
+        swift_statements(&self.messages, options)
+    }
+}
+
+impl FromParser for Mt942Parser {
+    fn to_statements(&self, options: &ConvertOptions) -> Result<Vec<Statement>, ConvertError> {
+        swift_statements(&self.messages, options)
+    }
+}
+
+impl FromParser for Mt950Parser {
+    fn to_statements(&self, options: &ConvertOptions) -> Result<Vec<Statement>, ConvertError> {
+        swift_statements(&self.messages, options)
+    }
+}
+
+impl FromParser for Mt900Parser {
+    fn to_statements(&self, options: &ConvertOptions) -> Result<Vec<Statement>, ConvertError> {
+        confirmation::statements(&self.messages, true, options)
+    }
+}
+
+impl FromParser for Mt910Parser {
+    fn to_statements(&self, options: &ConvertOptions) -> Result<Vec<Statement>, ConvertError> {
+        confirmation::statements(&self.messages, false, options)
     }
 }
 
 impl FromParser for Camt053Parser {
-    fn to_transactions(&self) -> Vec<Transaction> {
-        vec![Transaction {
-            reference: "CAMT_REF".to_string(),
-            account: "CAMT_ACC".to_string(),
-            amount: 0.0,
-            currency: "XXX".to_string(),
-            value_date: "1970-01-01".to_string(),
-            description: "Parsed from CAMT.053".to_string(),
-        }]
+    fn to_statements(&self, _options: &ConvertOptions) -> Result<Vec<Statement>, ConvertError> {
+        camt::statements(&self.document, CamtMessage::Statement)
+    }
+}
+
+impl FromParser for Camt052Parser {
+    fn to_statements(&self, _options: &ConvertOptions) -> Result<Vec<Statement>, ConvertError> {
+        camt::statements(&self.document, CamtMessage::Report)
+    }
+}
+
+impl FromParser for Camt054Parser {
+    fn to_statements(&self, _options: &ConvertOptions) -> Result<Vec<Statement>, ConvertError> {
+        camt::statements(&self.document, CamtMessage::Notification)
+    }
+}
+
+impl FromParser for ClientBank1CParser {
+    fn to_statements(&self, _options: &ConvertOptions) -> Result<Vec<Statement>, ConvertError> {
+        client_bank::statements(self)
+    }
+}
+
+impl FromParser for OfxParser {
+    fn to_statements(&self, _options: &ConvertOptions) -> Result<Vec<Statement>, ConvertError> {
+        ofx::statements(self)
+    }
+}
+
+impl FromParser for Bai2Parser {
+    fn to_statements(&self, options: &ConvertOptions) -> Result<Vec<Statement>, ConvertError> {
+        bai2::statements(self, options)
+    }
+}
+
+impl FromParser for CodaParser {
+    fn to_statements(&self, options: &ConvertOptions) -> Result<Vec<Statement>, ConvertError> {
+        coda::statements(self, options)
+    }
+}
+
+impl FromParser for JsonParser {
+    fn to_statements(&self, _options: &ConvertOptions) -> Result<Vec<Statement>, ConvertError> {
+        json::statements(self)
+    }
+}
+
+impl FromParser for JsonLinesParser {
+    fn to_statements(&self, _options: &ConvertOptions) -> Result<Vec<Statement>, ConvertError> {
+        json::line_statements(self)
+    }
+}
+
+impl FromParser for XlsxParser {
+    fn to_statements(&self, options: &ConvertOptions) -> Result<Vec<Statement>, ConvertError> {
+        xlsx::statements(self, options)
+    }
+}
+
+impl FromParser for QifParser {
+    fn to_statements(&self, options: &ConvertOptions) -> Result<Vec<Statement>, ConvertError> {
+        qif::statements(self, options)
     }
 }
 
 #[cfg(test)]
 mod tests {
+    use chrono::NaiveDate;
     use parser::Parser;
 
     use super::*;
@@ -104,12 +340,12 @@ mod tests {
         let mt940 = ":20:REF\n:25:ACC\n:61:2301010101DR100,50NMSCNONREF\n:86:desc\n";
         let p = Mt940Parser::parse(mt940.as_bytes()).unwrap();
 
-        let txs = FromParser::to_transactions(&p);
+        let txs = FromParser::to_transactions(&p, &ConvertOptions::default()).unwrap();
 
         assert_eq!(txs.len(), 1, "должна быть ровно одна транзакция");
-        // ❗ На твоём текущем коде amount останется 0.0 → тест УПАДЁТ
-        assert!(
-            (txs[0].amount - (-100.50)).abs() < 1e-6,
+        assert_eq!(
+            txs[0].amount,
+            Money::new(-10050, 2),
             "ожидали -100.50, а получили {}",
             txs[0].amount
         );
@@ -118,9 +354,9 @@ mod tests {
     fn mt940_amount_cr_should_be_positive() {
         let mt940 = ":20:REF2\n:25:ACC2\n:61:230101CR999,99NTRFREF123\n";
         let p = Mt940Parser::parse(mt940.as_bytes()).unwrap();
-        let txs = FromParser::to_transactions(&p);
+        let txs = FromParser::to_transactions(&p, &ConvertOptions::default()).unwrap();
         assert_eq!(txs.len(), 1);
-        assert!((txs[0].amount - 999.99).abs() < 1e-6);
+        assert_eq!(txs[0].amount, Money::new(99999, 2));
     }
     #[test]
     fn mt940_multiple_61_should_produce_multiple_transactions() {
@@ -133,9 +369,193 @@ mod tests {
         :86:second
     ";
         let p = Mt940Parser::parse(mt940.as_bytes()).unwrap();
-        let txs = FromParser::to_transactions(&p);
+        let txs = FromParser::to_transactions(&p, &ConvertOptions::default()).unwrap();
 
         // ❗ На текущем коде будет len()==1 → тест УПАДЁТ
         assert_eq!(txs.len(), 2, "каждый :61: должен создавать новую транзакцию");
     }
+
+    #[test]
+    fn mt940_amount_uses_currency_exponent() {
+        let mt940 = ":20:REF\n:25:ACC\n:60F:C230101JPY1000,\n:61:230101CR1500,\n:61:230101DR0,5\n";
+        let p = Mt940Parser::parse(mt940.as_bytes()).unwrap();
+        let result = FromParser::to_transactions(&p, &ConvertOptions::default());
+        assert!(matches!(result, Err(ConvertError::InvalidAmount(_))), "иены не бывают дробными");
+
+        let mt940 = ":20:REF\n:25:ACC\n:60F:C230101RUB1000,00\n:61:230101CR100,10\n";
+        let p = Mt940Parser::parse(mt940.as_bytes()).unwrap();
+        let txs = FromParser::to_transactions(&p, &ConvertOptions::default()).unwrap();
+        assert_eq!(txs[0].currency, "RUB");
+        assert_eq!(txs[0].amount.to_string(), "100.10");
+    }
+
+    #[test]
+    fn mt940_dates_are_typed() {
+        let mt940 = ":20:REF\n:25:ACC\n:61:9912291230CR1,00\n:61:2312290102DR2,00\n";
+        let p = Mt940Parser::parse(mt940.as_bytes()).unwrap();
+        let txs = FromParser::to_transactions(&p, &ConvertOptions::default()).unwrap();
+        assert_eq!(txs[0].value_date, NaiveDate::from_ymd_opt(1999, 12, 29).unwrap());
+        assert_eq!(txs[0].booking_date, NaiveDate::from_ymd_opt(1999, 12, 30));
+        assert_eq!(txs[1].value_date, NaiveDate::from_ymd_opt(2023, 12, 29).unwrap());
+        assert_eq!(txs[1].booking_date, NaiveDate::from_ymd_opt(2024, 1, 2));
+
+        let options = ConvertOptions {
+            century_pivot: 100,
+            ..Default::default()
+        };
+        let txs = FromParser::to_transactions(&p, &options).unwrap();
+        assert_eq!(txs[0].value_date, NaiveDate::from_ymd_opt(2099, 12, 29).unwrap());
+    }
+
+    #[test]
+    fn mt940_statement_line_subfields() {
+        let mt940 = ":20:REF\n:25:ACC\n:61:2301020103RCR100,00NTRFINV-2023-001//BANK123456\nREVERSED PAYMENT\n\
+                     :61:230105RD5,25NCHGNONREF\n:61:230106C7,00\n";
+        let p = Mt940Parser::parse(mt940.as_bytes()).unwrap();
+        let txs = FromParser::to_transactions(&p, &ConvertOptions::default()).unwrap();
+
+        assert_eq!(txs[0].amount, Money::new(-10000, 2), "RC — сторно кредита, дебетовая проводка");
+        assert!(txs[0].reversal);
+        assert_eq!(txs[0].funds_code, Some('R'));
+        assert_eq!(txs[0].booking_date, NaiveDate::from_ymd_opt(2023, 1, 3));
+        assert_eq!(txs[0].transaction_type.as_deref(), Some("NTRF"));
+        assert_eq!(txs[0].customer_reference.as_deref(), Some("INV-2023-001"));
+        assert_eq!(txs[0].bank_reference.as_deref(), Some("BANK123456"));
+        assert_eq!(txs[0].supplementary_details.as_deref(), Some("REVERSED PAYMENT"));
+
+        assert_eq!(txs[1].amount, Money::new(525, 2), "RD — сторно дебета, кредитовая проводка");
+        assert!(txs[1].reversal);
+        assert_eq!(txs[1].funds_code, None);
+        assert_eq!(txs[1].transaction_type.as_deref(), Some("NCHG"));
+        assert_eq!(txs[1].customer_reference, None);
+        assert_eq!(txs[1].bank_reference, None);
+
+        assert!(!txs[2].reversal);
+        assert_eq!(txs[2].transaction_type, None);
+    }
+
+    #[test]
+    fn mt940_structured_narrative() {
+        let mt940 = ":20:REF\n:25:ACC\n:61:230101C10,00NTRFNONREF\n\
+                     :86:166?00GUTSCHRIFT?20Rechnung 4711?30COBADEFFXXX\n?31DE89370400440532013000?32Max Mustermann\n\
+                     :61:230101D1,00NMSCNONREF\n:86:Plain text\n";
+        let p = Mt940Parser::parse(mt940.as_bytes()).unwrap();
+        let txs = FromParser::to_transactions(&p, &ConvertOptions::default()).unwrap();
+        assert!(txs[0].description.starts_with("166?00GUTSCHRIFT"));
+        assert_eq!(txs[0].gvc.as_deref(), Some("166"));
+        assert_eq!(txs[0].remittance_information.as_deref(), Some("Rechnung 4711"));
+        let counterparty = txs[0].counterparty.as_ref().unwrap();
+        assert_eq!(counterparty.name.as_deref(), Some("Max Mustermann"));
+        assert_eq!(counterparty.account.as_deref(), Some("DE89370400440532013000"));
+        assert_eq!(counterparty.bic.as_deref(), Some("COBADEFFXXX"));
+        assert_eq!(txs[1].counterparty, None);
+        assert_eq!(txs[1].remittance_information, None);
+
+        let options = ConvertOptions {
+            narrative_dialects: Vec::new(),
+            ..Default::default()
+        };
+        let txs = FromParser::to_transactions(&p, &options).unwrap();
+        assert_eq!(txs[0].counterparty, None);
+    }
+
+    #[test]
+    fn mt940_invalid_date_is_error() {
+        let mt940 = ":20:REF\n:25:ACC\n:61:230230CR1,00\n";
+        let p = Mt940Parser::parse(mt940.as_bytes()).unwrap();
+        let result = FromParser::to_transactions(&p, &ConvertOptions::default());
+        assert!(matches!(result, Err(ConvertError::InvalidDate(_))));
+    }
+
+    #[test]
+    fn mt940_statement_header_and_balances() {
+        let mt940 = "\
+:20:STMT1
+:25:NL81ASNB9999999999
+:28C:00042/001
+:60F:C230101EUR1000,00
+:61:230102DR100,10NMSCNONREF
+:62F:C230102EUR899,90
+:64:C230102EUR899,90
+:65:C230103EUR899,90
+:20:STMT2
+:25:NL81ASNB9999999999
+:60F:D230102EUR5,00
+:62F:D230102EUR5,00
+";
+        let p = Mt940Parser::parse(mt940.as_bytes()).unwrap();
+        let statements = p.to_statements(&ConvertOptions::default()).unwrap();
+        assert_eq!(statements.len(), 2);
+
+        let first = &statements[0];
+        assert_eq!(first.id, "STMT1");
+        assert_eq!(first.sequence_number.as_deref(), Some("00042/001"));
+        assert_eq!(first.currency, "EUR");
+        let opening = first.opening_balance.as_ref().unwrap();
+        let closing = first.closing_balance.as_ref().unwrap();
+        assert_eq!(opening.amount.checked_add(first.turnover().unwrap()), Some(closing.amount));
+        assert_eq!(first.available_balances.len(), 2);
+        assert_eq!(first.available_balances[1].kind, BalanceKind::ForwardAvailable);
+
+        let second = &statements[1];
+        assert!(second.entries.is_empty());
+        assert_eq!(second.opening_balance.as_ref().unwrap().amount, Money::new(-500, 2));
+    }
+
+    #[test]
+    fn mt940_swift_messages_become_statements_with_bics() {
+        let mt940 = "\
+{1:F01RECVDEFFAXXX0000000000}{2:O9401200230101SENDBEBBAXXX00000000002301011200N}{3:{108:MUR}}{4:
+:20:MSG1
+:25:ACC
+:60F:C230101EUR1,00
+:61:230101CR1,00
+:62F:C230101EUR2,00
+-}{5:{CHK:0}}
+{1:F01RECVDEFFAXXX0000000000}{2:O9401200230102OTHRGB22AXXX00000000002301021200N}{4:
+:25:ACC
+:60F:C230102EUR2,00
+:62F:C230102EUR2,00
+-}";
+        let p = Mt940Parser::parse(mt940.as_bytes()).unwrap();
+        let statements = p.to_statements(&ConvertOptions::default()).unwrap();
+        assert_eq!(statements.len(), 2, "сообщения не должны сливаться");
+        assert_eq!(statements[0].id, "MSG1");
+        assert_eq!(statements[0].entries.len(), 1);
+        assert_eq!(statements[0].sender_bic.as_deref(), Some("SENDBEBBXXX"));
+        assert_eq!(statements[0].receiver_bic.as_deref(), Some("RECVDEFFXXX"));
+        assert_eq!(statements[1].sender_bic.as_deref(), Some("OTHRGB22XXX"));
+        assert!(statements[1].entries.is_empty());
+    }
+
+    #[test]
+    fn mt942_floor_limits_date_time_and_totals() {
+        let mt942 = ":20:INTRA1\n:25:ACC1\n:28C:5/1\n:34F:EURD100,\n:34F:EURC50,\n:13D:2301021030+0100\n\
+                     :61:2301020102D150,00NTRFNONREF\n:86:Rent\n:61:230102C75,50NMSCNONREF\n\
+                     :90D:1EUR150,00\n:90C:1EUR75,50\n";
+        let p = Mt942Parser::parse(mt942.as_bytes()).unwrap();
+        let statements = p.to_statements(&ConvertOptions::default()).unwrap();
+        let st = &statements[0];
+        assert_eq!((st.id.as_str(), st.currency.as_str()), ("INTRA1", "EUR"));
+        assert_eq!(st.debit_floor_limit, Some(Money::new(10000, 2)));
+        assert_eq!(st.credit_floor_limit, Some(Money::new(5000, 2)));
+        assert_eq!(st.created_at, NaiveDate::from_ymd_opt(2023, 1, 2).unwrap().and_hms_opt(10, 30, 0));
+        assert_eq!(st.opening_balance, None);
+        assert_eq!(st.entries.len(), 2);
+        assert_eq!(st.entries[0].amount, Money::new(-15000, 2));
+        assert_eq!(st.entries[0].description, "Rent");
+
+        let single = mt942.replace(":34F:EURD100,\n:34F:EURC50,", ":34F:EUR0,");
+        let st = &Mt942Parser::parse(single.as_bytes())
+            .unwrap()
+            .to_statements(&ConvertOptions::default())
+            .unwrap()[0];
+        assert_eq!(st.debit_floor_limit, Some(Money::new(0, 2)));
+        assert_eq!(st.debit_floor_limit, st.credit_floor_limit);
+
+        for broken in [":90D:2EUR150,00", ":90D:1EUR150,01", ":90D:EUR150,00"] {
+            let p = Mt942Parser::parse(mt942.replace(":90D:1EUR150,00", broken).as_bytes()).unwrap();
+            assert!(p.to_statements(&ConvertOptions::default()).is_err(), "{}", broken);
+        }
+    }
 }
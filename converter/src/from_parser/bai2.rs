@@ -0,0 +1,223 @@
+//! Разбор BAI2 в [`Statement`]: выписка на каждую запись `03`.
+
+use chrono::{NaiveDate, NaiveDateTime, NaiveTime};
+use parser::{Bai2Account, Bai2Parser, Bai2Record};
+
+use crate::{
+    date::parse_yymmdd,
+    error::ConvertError,
+    model::{Balance, BalanceKind, Statement, Summary, Transaction},
+    money::{Money, currency_exponent, overflow},
+    options::ConvertOptions,
+};
+
+/// Валюта BAI2 по умолчанию, если ее нет ни в `03`, ни в `02`.
+const DEFAULT_CURRENCY: &str = "USD";
+
+/// Коды `03`, которые соответствуют сальдо модели; остальные идут в [`Statement::summaries`].
+const BALANCE_CODES: [(&str, BalanceKind); 3] = [
+    ("010", BalanceKind::Opening),
+    ("015", BalanceKind::Closing),
+    ("045", BalanceKind::ClosingAvailable),
+];
+
+/// Коды операций `400`–`699` — дебет, остальные — кредит.
+pub(crate) fn is_debit_code(code: &str) -> bool {
+    code.parse::<u16>().is_ok_and(|code| (400..700).contains(&code))
+}
+
+fn missing(what: &str) -> ConvertError {
+    parser::ParseError::InvalidFormat(format!("BAI2: нет обязательного поля {}", what)).into()
+}
+
+fn money(value: &str, exponent: u8) -> Result<Money, ConvertError> {
+    Ok(Money::new(Bai2Parser::parse_amount(value)?, exponent))
+}
+
+/// Группы `код, сумма, количество, доступность[, ...]` записи `03`.
+fn summary_groups(identifier: &Bai2Record) -> Vec<(&str, &str, &str)> {
+    let fields: Vec<&str> = identifier.fields.iter().map(|f| f.trim()).collect();
+    let mut groups = Vec::new();
+    let mut index = 2;
+    while index + 1 < fields.len() {
+        let count = fields.get(index + 2).copied().unwrap_or_default();
+        groups.push((fields[index], fields[index + 1], count));
+        let funds = fields.get(index + 3).copied().unwrap_or_default();
+        // Переполнение уже отклонено при разборе файла.
+        match Bai2Parser::funds_type_fields(funds, fields.get(index + 4..).unwrap_or_default())
+            .and_then(|extra| index.checked_add(4)?.checked_add(extra))
+        {
+            Some(next) => index = next,
+            None => break,
+        }
+    }
+    groups
+}
+
+fn parse_detail(
+    detail: &Bai2Record,
+    statement: &Statement,
+    as_of: NaiveDate,
+    options: &ConvertOptions,
+) -> Result<Transaction, ConvertError> {
+    let code = detail.field(0).ok_or_else(|| missing("16: код операции"))?;
+    let amount = money(detail.field(1).unwrap_or_default(), currency_exponent(&statement.currency))?;
+    let following: Vec<&str> = detail.fields.iter().skip(3).map(String::as_str).collect();
+    let funds = detail.field(2).unwrap_or_default();
+    let extra = Bai2Parser::funds_type_fields(funds, &following).ok_or_else(|| missing("16: число сумм доступности"))?;
+    // `V` — дата валютирования отдельным полем.
+    let value_date = match (funds, detail.field(3)) {
+        ("V", Some(date)) => parse_yymmdd(date, options.century_pivot)?,
+        _ => as_of,
+    };
+    // Поля после сумм доступности; смещение проверено, чтобы огромное число `D` не переполнило индекс.
+    let text = |offset: usize| extra.checked_add(offset).and_then(|index| detail.field(index)).map(str::to_string);
+    Ok(Transaction {
+        reference: statement.id.clone(),
+        account: statement.account.clone(),
+        amount: if is_debit_code(code) {
+            amount.checked_neg().ok_or_else(overflow)?
+        } else {
+            amount
+        },
+        currency: statement.currency.clone(),
+        value_date,
+        booking_date: (value_date != as_of).then_some(as_of),
+        customer_reference: text(4),
+        bank_reference: text(3),
+        gvc: Some(code.to_string()),
+        description: text(5).unwrap_or_default(),
+        ..Default::default()
+    })
+}
+
+fn parse_account(
+    account: &Bai2Account,
+    id: &str,
+    group_currency: Option<&str>,
+    as_of: NaiveDate,
+    created_at: Option<NaiveDateTime>,
+    options: &ConvertOptions,
+) -> Result<Statement, ConvertError> {
+    let identifier = &account.identifier;
+    let currency = identifier.field(1).or(group_currency).unwrap_or(DEFAULT_CURRENCY);
+    let exponent = currency_exponent(currency);
+    let mut statement = Statement {
+        id: id.to_string(),
+        account: identifier.field(0).ok_or_else(|| missing("03: номер счета"))?.to_string(),
+        currency: currency.to_string(),
+        created_at,
+        ..Default::default()
+    };
+    for (code, amount, count) in summary_groups(identifier) {
+        if code.is_empty() {
+            continue;
+        }
+        let amount = money(amount, exponent)?;
+        match BALANCE_CODES.iter().find(|(balance_code, _)| *balance_code == code) {
+            Some((_, kind)) => {
+                let balance = Balance {
+                    kind: *kind,
+                    amount,
+                    currency: currency.to_string(),
+                    date: as_of,
+                };
+                match kind {
+                    BalanceKind::Opening => statement.opening_balance = Some(balance),
+                    BalanceKind::Closing => statement.closing_balance = Some(balance),
+                    _ => statement.available_balances.push(balance),
+                }
+            }
+            None => statement.summaries.push(Summary {
+                type_code: code.to_string(),
+                amount,
+                item_count: count.parse().ok(),
+            }),
+        }
+    }
+    for detail in &account.details {
+        let tx = parse_detail(detail, &statement, as_of, options)?;
+        statement.entries.push(tx);
+    }
+    Ok(statement)
+}
+
+/// Выписки всех счетов всех групп в порядке следования.
+pub(crate) fn statements(parser: &Bai2Parser, options: &ConvertOptions) -> Result<Vec<Statement>, ConvertError> {
+    let header = &parser.header;
+    let created_at = match header.field(2) {
+        Some(date) => {
+            let time = header
+                .field(3)
+                .and_then(|t| NaiveTime::parse_from_str(t, "%H%M").ok())
+                .unwrap_or_default();
+            Some(parse_yymmdd(date, options.century_pivot)?.and_time(time))
+        }
+        None => None,
+    };
+    let id = header.field(4).unwrap_or("BAI2");
+
+    let mut statements = Vec::new();
+    for group in &parser.groups {
+        let as_of = parse_yymmdd(group.header.field(3).ok_or_else(|| missing("02: дата"))?, options.century_pivot)?;
+        for account in &group.accounts {
+            statements.push(parse_account(account, id, group.header.field(5), as_of, created_at, options)?);
+        }
+    }
+    Ok(statements)
+}
+
+#[cfg(test)]
+mod tests {
+    use parser::Parser;
+
+    use super::*;
+
+    const BAI2: &str = "01,122099999,123456789,230131,2300,FILE1,,,2/
+02,123456789,122099999,1,230131,2300,USD,2/
+03,0001234567,USD,010,100000,,,015,145000,,/
+88,045,140000,,,100,65000,2,,400,20000,1,/
+16,195,50000,Z,BR1,CR1,Incoming wire, ACME Corp
+16,142,15000,V,230201,0900,BR2,,ACH credit
+16,475,20000,Z,BR3,1001,Check paid/
+49,555000,6/
+98,555000,1,8/
+99,555000,1,10/
+";
+
+    #[test]
+    fn balances_summaries_and_details() {
+        let parser = Bai2Parser::parse(BAI2.as_bytes()).unwrap();
+        let statements = statements(&parser, &ConvertOptions::default()).unwrap();
+        assert_eq!(statements.len(), 1);
+        let st = &statements[0];
+        assert_eq!(
+            (st.id.as_str(), st.account.as_str(), st.currency.as_str()),
+            ("FILE1", "0001234567", "USD")
+        );
+        assert_eq!(st.created_at, NaiveDate::from_ymd_opt(2023, 1, 31).unwrap().and_hms_opt(23, 0, 0));
+        assert_eq!(st.opening_balance.as_ref().unwrap().amount, Money::new(100000, 2));
+        assert_eq!(st.closing_balance.as_ref().unwrap().amount, Money::new(145000, 2));
+        assert_eq!(st.available_balances[0].amount, Money::new(140000, 2));
+        assert_eq!(st.summaries.len(), 2);
+        assert_eq!(st.summaries[0].type_code, "100");
+        assert_eq!(st.summaries[0].item_count, Some(2));
+
+        let wire = &st.entries[0];
+        assert_eq!(wire.amount, Money::new(50000, 2));
+        assert_eq!(wire.gvc.as_deref(), Some("195"));
+        assert_eq!(wire.bank_reference.as_deref(), Some("BR1"));
+        assert_eq!(wire.customer_reference.as_deref(), Some("CR1"));
+        assert_eq!(wire.description, "Incoming wire, ACME Corp");
+
+        let ach = &st.entries[1];
+        assert_eq!(ach.value_date, NaiveDate::from_ymd_opt(2023, 2, 1).unwrap());
+        assert_eq!(ach.booking_date, NaiveDate::from_ymd_opt(2023, 1, 31));
+        assert_eq!(ach.bank_reference.as_deref(), Some("BR2"));
+        assert_eq!(ach.customer_reference, None);
+
+        let check = &st.entries[2];
+        assert_eq!(check.amount, Money::new(-20000, 2));
+        assert_eq!(check.description, "Check paid");
+    }
+}
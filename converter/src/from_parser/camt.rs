@@ -0,0 +1,465 @@
+//! Разбор выписок ISO 20022 (CAMT) из дерева [`XmlElement`].
+
+use chrono::{DateTime, NaiveDate, NaiveDateTime};
+use parser::{ParseError, XmlElement};
+
+use crate::{
+    error::ConvertError,
+    model::{Balance, BalanceKind, Counterparty, Statement, Transaction},
+    money::{Money, currency_exponent, overflow},
+    options::CamtVersion,
+};
+
+/// Сообщение Bank-to-Customer Cash Management: общая структура записей `Ntry`,
+/// разные корневой элемент и блок счета.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub(crate) enum CamtMessage {
+    /// camt.052 — внутридневной отчет по счету.
+    Report,
+    /// camt.053 — выписка.
+    Statement,
+    /// camt.054 — уведомления о дебете и кредите.
+    Notification,
+}
+
+impl CamtMessage {
+    /// Имя сообщения в пространстве имен: `camt.053`.
+    pub(crate) fn name(self) -> &'static str {
+        match self {
+            CamtMessage::Report => "camt.052",
+            CamtMessage::Statement => "camt.053",
+            CamtMessage::Notification => "camt.054",
+        }
+    }
+
+    /// Элемент под `Document`.
+    pub(crate) fn root(self) -> &'static str {
+        match self {
+            CamtMessage::Report => "BkToCstmrAcctRpt",
+            CamtMessage::Statement => "BkToCstmrStmt",
+            CamtMessage::Notification => "BkToCstmrDbtCdtNtfctn",
+        }
+    }
+
+    /// Блок одного счета: `Rpt`, `Stmt`, `Ntfctn`.
+    pub(crate) fn block(self) -> &'static str {
+        match self {
+            CamtMessage::Report => "Rpt",
+            CamtMessage::Statement => "Stmt",
+            CamtMessage::Notification => "Ntfctn",
+        }
+    }
+}
+
+fn missing(what: &str) -> ConvertError {
+    ParseError::InvalidFormat(format!("CAMT: нет обязательного элемента {}", what)).into()
+}
+
+/// Версия документа по `xmlns` (в том числе с префиксом) корневого элемента.
+///
+/// Документ без пространства имен читается как 001.02.
+pub(crate) fn detect_version(document: &XmlElement, message: &str) -> Result<CamtVersion, ConvertError> {
+    let namespaces = document
+        .attributes
+        .iter()
+        .filter(|(key, _)| key == "xmlns" || key.starts_with("xmlns:"))
+        .map(|(_, value)| value.as_str())
+        .filter(|ns| ns.starts_with("urn:iso:std:iso:20022:tech:xsd:"));
+    let mut unsupported = None;
+    for namespace in namespaces {
+        match CamtVersion::from_namespace(namespace) {
+            Some((found, version)) if found == message => return Ok(version),
+            _ => unsupported = Some(namespace),
+        }
+    }
+    match unsupported {
+        Some(namespace) => {
+            Err(ParseError::InvalidFormat(format!("{}: неподдерживаемое пространство имен {}", message, namespace)).into())
+        }
+        None => Ok(CamtVersion::default()),
+    }
+}
+
+/// Статус записи: код `Sts` до 001.08, `Sts/Cd` начиная с нее.
+///
+/// Банки нередко путают формы, поэтому при отсутствии ожидаемой читается другая.
+fn entry_status(ntry: &XmlElement, version: CamtVersion) -> Option<&str> {
+    let code = ntry.find_text(&["Sts", "Cd"]).or_else(|| ntry.find_text(&["Sts", "Prtry"]));
+    let plain = ntry.find_text(&["Sts"]);
+    if version.structured_status() {
+        code.or(plain)
+    } else {
+        plain.or(code)
+    }
+}
+
+/// Дата из `Dt` или `DtTm` (`<BookgDt><Dt>2023-01-01</Dt></BookgDt>`).
+fn parse_date_choice(el: &XmlElement) -> Result<Option<NaiveDate>, ConvertError> {
+    if let Some(text) = el.find_text(&["Dt"]) {
+        return NaiveDate::parse_from_str(text, "%Y-%m-%d")
+            .map(Some)
+            .map_err(|_| ConvertError::InvalidDate(text.to_string()));
+    }
+    el.find_text(&["DtTm"])
+        .map(|text| parse_date_time(text).map(|t| t.date()))
+        .transpose()
+}
+
+/// ISO-время `2023-01-01T10:00:00`, с долями секунды и/или смещением.
+pub(crate) fn parse_date_time(text: &str) -> Result<NaiveDateTime, ConvertError> {
+    DateTime::parse_from_rfc3339(text)
+        .map(|t| t.naive_local())
+        .or_else(|_| NaiveDateTime::parse_from_str(text, "%Y-%m-%dT%H:%M:%S%.f"))
+        .map_err(|_| ConvertError::InvalidDate(text.to_string()))
+}
+
+/// Сумма `<Amt Ccy="EUR">1.00</Amt>` со знаком по `CdtDbtInd`.
+fn parse_signed_amount(el: &XmlElement) -> Result<(Money, String), ConvertError> {
+    let amt = el.child("Amt").ok_or_else(|| missing("Amt"))?;
+    let currency = amt.attribute("Ccy").ok_or_else(|| missing("Amt@Ccy"))?.to_string();
+    let amount = Money::parse(&amt.text, currency_exponent(&currency))?;
+    match el.find_text(&["CdtDbtInd"]) {
+        Some("CRDT") => Ok((amount, currency)),
+        Some("DBIT") => Ok((amount.checked_neg().ok_or_else(overflow)?, currency)),
+        Some(other) => Err(ParseError::InvalidFormat(format!("CAMT: неизвестный CdtDbtInd {}", other)).into()),
+        None => Err(missing("CdtDbtInd")),
+    }
+}
+
+fn parse_balance(bal: &XmlElement) -> Result<Option<Balance>, ConvertError> {
+    let kind = match bal.find_text(&["Tp", "CdOrPrtry", "Cd"]) {
+        Some("OPBD") => BalanceKind::Opening,
+        Some("PRCD") => BalanceKind::InterimOpening,
+        Some("CLBD") => BalanceKind::Closing,
+        Some("ITBD") => BalanceKind::InterimClosing,
+        Some("CLAV") => BalanceKind::ClosingAvailable,
+        Some("FWAV") => BalanceKind::ForwardAvailable,
+        // ITAV, INFO и прочие в модели не представлены.
+        _ => return Ok(None),
+    };
+    let (amount, currency) = parse_signed_amount(bal)?;
+    let date = bal
+        .child("Dt")
+        .map(parse_date_choice)
+        .transpose()?
+        .flatten()
+        .ok_or_else(|| missing("Bal/Dt"))?;
+    Ok(Some(Balance {
+        kind,
+        amount,
+        currency,
+        date,
+    }))
+}
+
+/// Все `Ustrd` записи через пробел, иначе `AddtlTxInf` или `AddtlNtryInf`.
+fn entry_description(ntry: &XmlElement) -> String {
+    let details: Vec<&XmlElement> = ntry.children("NtryDtls").flat_map(|d| d.children("TxDtls")).collect();
+    let unstructured: Vec<&str> = details
+        .iter()
+        .filter_map(|tx| tx.child("RmtInf"))
+        .flat_map(|rmt| rmt.children("Ustrd"))
+        .map(|u| u.text.as_str())
+        .filter(|t| !t.is_empty())
+        .collect();
+    if !unstructured.is_empty() {
+        return unstructured.join(" ");
+    }
+    details
+        .iter()
+        .find_map(|tx| tx.find_text(&["AddtlTxInf"]))
+        .or_else(|| ntry.find_text(&["AddtlNtryInf"]))
+        .unwrap_or_default()
+        .to_string()
+}
+
+/// Контрагент из `RltdPties`/`RltdAgts`: плательщик для кредита, получатель для дебета.
+fn entry_counterparty(tx: &XmlElement, debit: bool) -> Option<Counterparty> {
+    let (party, account, agent) = if debit {
+        ("Cdtr", "CdtrAcct", "CdtrAgt")
+    } else {
+        ("Dbtr", "DbtrAcct", "DbtrAgt")
+    };
+    let parties = tx.child("RltdPties");
+    let text = |path: &[&str]| parties.and_then(|p| p.find_text(path)).map(str::to_string);
+    let bic = tx.find(&["RltdAgts", agent, "FinInstnId"]);
+    let counterparty = Counterparty {
+        name: text(&[party, "Pty", "Nm"]).or_else(|| text(&[party, "Nm"])),
+        account: text(&[account, "Id", "IBAN"]).or_else(|| text(&[account, "Id", "Othr", "Id"])),
+        bic: bic
+            .and_then(|f| f.find_text(&["BICFI"]).or_else(|| f.find_text(&["BIC"])))
+            .map(str::to_string),
+        ..Default::default()
+    };
+    Some(counterparty).filter(|c| !c.is_empty())
+}
+
+fn parse_entry(ntry: &XmlElement, statement: &Statement) -> Result<Transaction, ConvertError> {
+    let (amount, currency) = parse_signed_amount(ntry)?;
+    let booking_date = ntry.child("BookgDt").map(parse_date_choice).transpose()?.flatten();
+    let value_date = ntry.child("ValDt").map(parse_date_choice).transpose()?.flatten();
+    // Пакетная запись может содержать несколько TxDtls: референсы берем из первой.
+    let refs = ntry.find(&["NtryDtls", "TxDtls", "Refs"]);
+    let customer_reference = refs
+        .and_then(|r| r.find_text(&["EndToEndId"]).or_else(|| r.find_text(&["InstrId"])))
+        .filter(|id| *id != "NOTPROVIDED");
+    let bank_reference = ntry
+        .find_text(&["AcctSvcrRef"])
+        .or_else(|| refs.and_then(|r| r.find_text(&["AcctSvcrRef"])));
+    let transaction_type = ntry
+        .find(&["BkTxCd", "Prtry"])
+        .filter(|prtry| prtry.find_text(&["Issr"]) == Some("SWIFT"))
+        .and_then(|prtry| prtry.find_text(&["Cd"]));
+
+    Ok(Transaction {
+        reference: statement.id.clone(),
+        account: statement.account.clone(),
+        amount,
+        currency,
+        value_date: value_date.or(booking_date).ok_or_else(|| missing("Ntry/ValDt или Ntry/BookgDt"))?,
+        booking_date,
+        customer_reference: customer_reference.map(str::to_string),
+        bank_reference: bank_reference.map(str::to_string),
+        reversal: ntry.find_text(&["RvslInd"]).is_some_and(|v| v == "true" || v == "1"),
+        transaction_type: transaction_type.map(str::to_string),
+        counterparty: ntry
+            .find(&["NtryDtls", "TxDtls"])
+            .and_then(|tx| entry_counterparty(tx, amount.is_negative())),
+        description: entry_description(ntry),
+        ..Default::default()
+    })
+}
+
+/// Разбирает один `Stmt` (или аналогичный блок `Rpt`/`Ntfctn`).
+///
+/// Записи в статусе, отличном от `BOOK` (`PDNG`, `INFO`), не входят в обороты
+/// и пропускаются — иначе входящее сальдо плюс оборот не сойдется с исходящим.
+pub(crate) fn parse_statement(
+    stmt: &XmlElement,
+    group_created_at: Option<NaiveDateTime>,
+    version: CamtVersion,
+) -> Result<Statement, ConvertError> {
+    let mut statement = Statement {
+        id: stmt
+            .find_text(&["Id"])
+            .ok_or_else(|| missing(&format!("{}/Id", stmt.name)))?
+            .to_string(),
+        sequence_number: stmt
+            .find_text(&["ElctrncSeqNb"])
+            .or_else(|| stmt.find_text(&["LglSeqNb"]))
+            .map(str::to_string),
+        account: stmt
+            .find_text(&["Acct", "Id", "IBAN"])
+            .or_else(|| stmt.find_text(&["Acct", "Id", "Othr", "Id"]))
+            .ok_or_else(|| missing("Acct/Id"))?
+            .to_string(),
+        currency: stmt.find_text(&["Acct", "Ccy"]).unwrap_or_default().to_string(),
+        created_at: stmt.find_text(&["CreDtTm"]).map(parse_date_time).transpose()?.or(group_created_at),
+        ..Default::default()
+    };
+
+    for bal in stmt.children("Bal") {
+        let Some(balance) = parse_balance(bal)? else { continue };
+        if statement.currency.is_empty() {
+            statement.currency = balance.currency.clone();
+        }
+        match balance.kind {
+            BalanceKind::Opening | BalanceKind::InterimOpening => statement.opening_balance = Some(balance),
+            BalanceKind::Closing | BalanceKind::InterimClosing => statement.closing_balance = Some(balance),
+            BalanceKind::ClosingAvailable | BalanceKind::ForwardAvailable => statement.available_balances.push(balance),
+        }
+    }
+
+    for ntry in stmt.children("Ntry") {
+        if entry_status(ntry, version).is_some_and(|status| status != "BOOK") {
+            continue;
+        }
+        let entry = parse_entry(ntry, &statement)?;
+        statement.entries.push(entry);
+    }
+    if statement.currency.is_empty() {
+        statement.currency = statement.entries.first().map(|tx| tx.currency.clone()).unwrap_or_default();
+    }
+    Ok(statement)
+}
+
+/// Выписки всех блоков счета документа.
+pub(crate) fn statements(document: &XmlElement, message: CamtMessage) -> Result<Vec<Statement>, ConvertError> {
+    let group = document.child(message.root()).ok_or_else(|| missing(message.root()))?;
+    let version = detect_version(document, message.name())?;
+    let created_at = group.find_text(&["GrpHdr", "CreDtTm"]).map(parse_date_time).transpose()?;
+    group
+        .children(message.block())
+        .map(|block| parse_statement(block, created_at, version))
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use parser::{Camt052Parser, Camt053Parser, Camt054Parser, Parser};
+
+    use crate::{FromParser, options::ConvertOptions};
+
+    use super::*;
+
+    const STATEMENT: &str = r#"<?xml version="1.0" encoding="UTF-8"?>
+<Document xmlns="urn:iso:std:iso:20022:tech:xsd:camt.053.001.02">
+  <BkToCstmrStmt>
+    <GrpHdr><MsgId>MSG1</MsgId><CreDtTm>2023-01-03T08:15:00+01:00</CreDtTm></GrpHdr>
+    <Stmt>
+      <Id>STMT-2023-01</Id>
+      <ElctrncSeqNb>12</ElctrncSeqNb>
+      <Acct><Id><IBAN>DE89370400440532013000</IBAN></Id><Ccy>EUR</Ccy></Acct>
+      <Bal>
+        <Tp><CdOrPrtry><Cd>OPBD</Cd></CdOrPrtry></Tp>
+        <Amt Ccy="EUR">1000.00</Amt><CdtDbtInd>CRDT</CdtDbtInd>
+        <Dt><Dt>2023-01-01</Dt></Dt>
+      </Bal>
+      <Bal>
+        <Tp><CdOrPrtry><Cd>CLBD</Cd></CdOrPrtry></Tp>
+        <Amt Ccy="EUR">899.90</Amt><CdtDbtInd>CRDT</CdtDbtInd>
+        <Dt><Dt>2023-01-02</Dt></Dt>
+      </Bal>
+      <Bal>
+        <Tp><CdOrPrtry><Cd>ITAV</Cd></CdOrPrtry></Tp>
+        <Amt Ccy="EUR">899.90</Amt><CdtDbtInd>CRDT</CdtDbtInd>
+        <Dt><Dt>2023-01-02</Dt></Dt>
+      </Bal>
+      <Ntry>
+        <Amt Ccy="EUR">100.10</Amt>
+        <CdtDbtInd>DBIT</CdtDbtInd>
+        <Sts>BOOK</Sts>
+        <BookgDt><Dt>2023-01-02</Dt></BookgDt>
+        <ValDt><Dt>2023-01-01</Dt></ValDt>
+        <AcctSvcrRef>BANKREF1</AcctSvcrRef>
+        <NtryDtls><TxDtls>
+          <Refs><EndToEndId>E2E-1</EndToEndId></Refs>
+          <RmtInf><Ustrd>Invoice 42</Ustrd><Ustrd>Tom &amp; Jerry</Ustrd></RmtInf>
+        </TxDtls></NtryDtls>
+      </Ntry>
+      <Ntry>
+        <Amt Ccy="EUR">0.00</Amt>
+        <CdtDbtInd>CRDT</CdtDbtInd>
+        <BookgDt><DtTm>2023-01-02T12:00:00</DtTm></BookgDt>
+        <AddtlNtryInf>Fee waived</AddtlNtryInf>
+      </Ntry>
+    </Stmt>
+  </BkToCstmrStmt>
+</Document>"#;
+
+    #[test]
+    fn camt053_statement_is_mapped() {
+        let p = Camt053Parser::parse(STATEMENT.as_bytes()).unwrap();
+        let statements = p.to_statements(&ConvertOptions::default()).unwrap();
+        assert_eq!(statements.len(), 1);
+        let st = &statements[0];
+        assert_eq!(st.id, "STMT-2023-01");
+        assert_eq!(st.sequence_number.as_deref(), Some("12"));
+        assert_eq!(st.account, "DE89370400440532013000");
+        assert_eq!(st.currency, "EUR");
+        assert_eq!(st.created_at.unwrap().to_string(), "2023-01-03 08:15:00");
+        assert_eq!(st.opening_balance.as_ref().unwrap().amount, Money::new(100000, 2));
+        assert_eq!(st.closing_balance.as_ref().unwrap().amount, Money::new(89990, 2));
+        assert!(st.available_balances.is_empty(), "ITAV не поддерживается моделью");
+        assert_eq!(
+            st.opening_balance.as_ref().unwrap().amount.checked_add(st.turnover().unwrap()),
+            Some(st.closing_balance.as_ref().unwrap().amount)
+        );
+    }
+
+    #[test]
+    fn camt053_entries_are_mapped() {
+        let p = Camt053Parser::parse(STATEMENT.as_bytes()).unwrap();
+        let txs = p.to_transactions(&ConvertOptions::default()).unwrap();
+        assert_eq!(txs.len(), 2);
+        assert_eq!(txs[0].amount, Money::new(-10010, 2));
+        assert_eq!(txs[0].value_date, NaiveDate::from_ymd_opt(2023, 1, 1).unwrap());
+        assert_eq!(txs[0].booking_date, NaiveDate::from_ymd_opt(2023, 1, 2));
+        assert_eq!(txs[0].customer_reference.as_deref(), Some("E2E-1"));
+        assert_eq!(txs[0].bank_reference.as_deref(), Some("BANKREF1"));
+        assert_eq!(txs[0].description, "Invoice 42 Tom & Jerry");
+        assert_eq!(txs[1].value_date, NaiveDate::from_ymd_opt(2023, 1, 2).unwrap());
+        assert_eq!(txs[1].description, "Fee waived");
+    }
+
+    #[test]
+    fn camt053_invalid_entry_is_error() {
+        let broken = STATEMENT.replace("<CdtDbtInd>DBIT</CdtDbtInd>", "");
+        let p = Camt053Parser::parse(broken.as_bytes()).unwrap();
+        assert!(p.to_statements(&ConvertOptions::default()).is_err());
+
+        let broken = STATEMENT.replace("2023-01-02T12:00:00", "2023-02-30T12:00:00");
+        let p = Camt053Parser::parse(broken.as_bytes()).unwrap();
+        assert!(matches!(
+            p.to_statements(&ConvertOptions::default()),
+            Err(ConvertError::InvalidDate(_))
+        ));
+    }
+
+    #[test]
+    fn camt053_version_is_detected_and_status_mapped() {
+        let v04 = STATEMENT.replace("camt.053.001.02", "camt.053.001.04").replace(
+            "<CdtDbtInd>CRDT</CdtDbtInd>\n        <BookgDt>",
+            "<CdtDbtInd>CRDT</CdtDbtInd><Sts>PDNG</Sts><BookgDt>",
+        );
+        let p = Camt053Parser::parse(v04.as_bytes()).unwrap();
+        assert_eq!(detect_version(&p.document, "camt.053").unwrap(), CamtVersion::V04);
+        assert_eq!(
+            p.to_transactions(&ConvertOptions::default()).unwrap().len(),
+            1,
+            "PDNG не входит в выписку"
+        );
+
+        let v08 = STATEMENT
+            .replace("camt.053.001.02", "camt.053.001.08")
+            .replace("<Sts>BOOK</Sts>", "<Sts><Cd>BOOK</Cd></Sts>")
+            .replace(
+                "<CdtDbtInd>CRDT</CdtDbtInd>\n        <BookgDt>",
+                "<CdtDbtInd>CRDT</CdtDbtInd><Sts><Cd>INFO</Cd></Sts><BookgDt>",
+            );
+        let p = Camt053Parser::parse(v08.as_bytes()).unwrap();
+        assert_eq!(detect_version(&p.document, "camt.053").unwrap(), CamtVersion::V08);
+        let txs = p.to_transactions(&ConvertOptions::default()).unwrap();
+        assert_eq!(txs.len(), 1);
+        assert_eq!(txs[0].customer_reference.as_deref(), Some("E2E-1"));
+
+        let prefixed = r#"<c:Document xmlns:c="urn:iso:std:iso:20022:tech:xsd:camt.053.001.10"><c:BkToCstmrStmt/></c:Document>"#;
+        let p = Camt053Parser::parse(prefixed.as_bytes()).unwrap();
+        assert_eq!(detect_version(&p.document, "camt.053").unwrap(), CamtVersion::V10);
+
+        let unknown = STATEMENT.replace("camt.053.001.02", "camt.053.001.99");
+        let p = Camt053Parser::parse(unknown.as_bytes()).unwrap();
+        assert!(p.to_statements(&ConvertOptions::default()).is_err());
+    }
+
+    #[test]
+    fn camt052_report_and_camt054_notification_share_entry_mapping() {
+        let expected = Camt053Parser::parse(STATEMENT.as_bytes())
+            .unwrap()
+            .to_transactions(&ConvertOptions::default())
+            .unwrap();
+
+        let report = STATEMENT
+            .replace("camt.053.001.02", "camt.052.001.02")
+            .replace("BkToCstmrStmt>", "BkToCstmrAcctRpt>")
+            .replace("Stmt>", "Rpt>")
+            .replace("<Cd>CLBD</Cd>", "<Cd>ITBD</Cd>");
+        let p = Camt052Parser::parse(report.as_bytes()).unwrap();
+        let statements = p.to_statements(&ConvertOptions::default()).unwrap();
+        assert_eq!(statements[0].id, "STMT-2023-01");
+        assert_eq!(statements[0].closing_balance.as_ref().unwrap().kind, BalanceKind::InterimClosing);
+        assert_eq!(statements[0].entries, expected);
+
+        let notification = STATEMENT
+            .replace("camt.053.001.02", "camt.054.001.02")
+            .replace("BkToCstmrStmt>", "BkToCstmrDbtCdtNtfctn>")
+            .replace("Stmt>", "Ntfctn>");
+        let p = Camt054Parser::parse(notification.as_bytes()).unwrap();
+        assert_eq!(p.to_transactions(&ConvertOptions::default()).unwrap(), expected);
+        assert_eq!(detect_version(&p.document, "camt.054").unwrap(), CamtVersion::V02);
+
+        // Пространство имен другого сообщения — ошибка.
+        let p = Camt054Parser::parse(notification.replace("camt.054", "camt.053").as_bytes()).unwrap();
+        assert!(p.to_statements(&ConvertOptions::default()).is_err());
+    }
+}
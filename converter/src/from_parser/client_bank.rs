@@ -0,0 +1,248 @@
+//! Разбор выписки 1С `1CClientBankExchange` в [`Statement`].
+
+use chrono::{NaiveDate, NaiveTime};
+use parser::{ClientBank1CParser, ClientBank1CSection, ParseError};
+
+use crate::{
+    error::ConvertError,
+    model::{Balance, BalanceKind, Counterparty, Statement, Transaction},
+    money::{Money, overflow},
+};
+
+/// Формат обмена 1С — только рубли.
+pub(crate) const CURRENCY: &str = "RUB";
+
+fn parse_date(value: &str) -> Result<NaiveDate, ConvertError> {
+    NaiveDate::parse_from_str(value, "%d.%m.%Y").map_err(|e| ConvertError::InvalidDate(format!("{}: {}", value, e)))
+}
+
+fn date_field(section: &ClientBank1CSection, key: &str) -> Result<Option<NaiveDate>, ConvertError> {
+    section.get(key).map(parse_date).transpose()
+}
+
+fn balance(
+    section: &ClientBank1CSection,
+    key: &str,
+    kind: BalanceKind,
+    date: Option<NaiveDate>,
+) -> Result<Option<Balance>, ConvertError> {
+    let (Some(amount), Some(date)) = (section.get(key), date) else {
+        return Ok(None);
+    };
+    Ok(Some(Balance {
+        kind,
+        amount: Money::parse(amount, 2)?,
+        currency: CURRENCY.to_string(),
+        date,
+    }))
+}
+
+/// Реквизиты стороны документа: `Плательщик*` или `Получатель*`.
+fn party(doc: &ClientBank1CSection, side: &str) -> Counterparty {
+    let field = |suffix: &str| doc.get(&format!("{}{}", side, suffix)).map(str::to_string);
+    Counterparty {
+        // `Плательщик` может быть вида "ИНН 7701234567 ООО Ромашка", `Плательщик1` — только наименование.
+        name: field("1").or_else(|| field("")),
+        account: field("Счет").or_else(|| field("РасчСчет")),
+        bank_code: field("БИК"),
+        inn: field("ИНН"),
+        kpp: field("КПП"),
+        ..Default::default()
+    }
+}
+
+/// Назначение платежа: `НазначениеПлатежа` или строки `НазначениеПлатежа1`..`6` старых версий.
+fn purpose(doc: &ClientBank1CSection) -> String {
+    match doc.get("НазначениеПлатежа") {
+        Some(purpose) => purpose.to_string(),
+        None => (1..=6)
+            .filter_map(|n| doc.get(&format!("НазначениеПлатежа{}", n)))
+            .collect::<Vec<_>>()
+            .join(" "),
+    }
+}
+
+fn parse_document(doc: &ClientBank1CSection, statement: &Statement, debit: bool) -> Result<Transaction, ConvertError> {
+    let amount = Money::parse(doc.get("Сумма").ok_or_else(|| missing("Сумма"))?, 2)?;
+    let (date_key, other_side) = if debit {
+        ("ДатаСписано", "Получатель")
+    } else {
+        ("ДатаПоступило", "Плательщик")
+    };
+    let value_date = match date_field(doc, date_key)? {
+        Some(date) => date,
+        None => date_field(doc, "Дата")?.ok_or_else(|| missing("Дата"))?,
+    };
+    let counterparty = party(doc, other_side);
+    Ok(Transaction {
+        reference: statement.id.clone(),
+        account: statement.account.clone(),
+        amount: if debit {
+            amount.checked_neg().ok_or_else(overflow)?
+        } else {
+            amount
+        },
+        currency: CURRENCY.to_string(),
+        value_date,
+        customer_reference: doc.get("Номер").map(str::to_string),
+        counterparty: Some(counterparty).filter(|c| !c.is_empty()),
+        description: purpose(doc),
+        ..Default::default()
+    })
+}
+
+fn missing(key: &str) -> ConvertError {
+    ParseError::InvalidFormat(format!("1CClientBankExchange: нет обязательного поля {}", key)).into()
+}
+
+/// Выписки по счетам из `СекцияРасчСчет` (или `РасчСчет` заголовка) с документами,
+/// где счет — плательщик (списание) или получатель (поступление).
+pub(crate) fn statements(parser: &ClientBank1CParser) -> Result<Vec<Statement>, ConvertError> {
+    let header = &parser.header;
+    let created_at = match date_field(header, "ДатаСоздания")? {
+        Some(date) => {
+            let time = header
+                .get("ВремяСоздания")
+                .and_then(|t| NaiveTime::parse_from_str(t, "%H:%M:%S").ok())
+                .unwrap_or_default();
+            Some(date.and_time(time))
+        }
+        None => None,
+    };
+
+    let header_only: Vec<ClientBank1CSection>;
+    let sections: &[ClientBank1CSection] = if parser.accounts.is_empty() {
+        header_only = header
+            .get_all("РасчСчет")
+            .map(|account| ClientBank1CSection {
+                kind: "РасчСчет".to_string(),
+                fields: vec![("РасчСчет".to_string(), account.to_string())],
+            })
+            .collect();
+        &header_only
+    } else {
+        &parser.accounts
+    };
+    if sections.is_empty() {
+        return Err(missing("РасчСчет"));
+    }
+
+    let mut statements = Vec::new();
+    for section in sections {
+        let account = section.get("РасчСчет").ok_or_else(|| missing("РасчСчет"))?;
+        let start = date_field(section, "ДатаНачала")?.or(date_field(header, "ДатаНачала")?);
+        let end = date_field(section, "ДатаКонца")?.or(date_field(header, "ДатаКонца")?);
+        let mut statement = Statement {
+            id: start.map_or_else(|| "1C".to_string(), |d| format!("1C{}", d.format("%Y%m%d"))),
+            account: account.to_string(),
+            currency: CURRENCY.to_string(),
+            opening_balance: balance(section, "НачальныйОстаток", BalanceKind::Opening, start)?,
+            closing_balance: balance(section, "КонечныйОстаток", BalanceKind::Closing, end)?,
+            created_at,
+            ..Default::default()
+        };
+        for doc in &parser.documents {
+            // Перевод между своими счетами попадает в обе выписки.
+            if doc.get("ПлательщикСчет") == Some(account) {
+                let tx = parse_document(doc, &statement, true)?;
+                statement.entries.push(tx);
+            }
+            if doc.get("ПолучательСчет") == Some(account) {
+                let tx = parse_document(doc, &statement, false)?;
+                statement.entries.push(tx);
+            }
+        }
+        statements.push(statement);
+    }
+    Ok(statements)
+}
+
+#[cfg(test)]
+mod tests {
+    use parser::Parser;
+
+    use super::*;
+
+    const FILE: &str = "1CClientBankExchange
+ВерсияФормата=1.03
+Кодировка=Windows
+ДатаСоздания=01.02.2023
+ВремяСоздания=10:15:00
+ДатаНачала=01.01.2023
+ДатаКонца=31.01.2023
+РасчСчет=40702810900000000001
+СекцияРасчСчет
+ДатаНачала=01.01.2023
+ДатаКонца=31.01.2023
+РасчСчет=40702810900000000001
+НачальныйОстаток=1000.00
+КонечныйОстаток=1300.00
+КонецРасчСчет
+СекцияДокумент=Платежное поручение
+Номер=15
+Дата=10.01.2023
+Сумма=200.00
+ПлательщикСчет=40702810900000000001
+ДатаСписано=11.01.2023
+Плательщик=ООО \"Ромашка\"
+ПолучательСчет=40702810100000000002
+Получатель=ИНН 7702345678 ООО \"Лютик\"
+Получатель1=ООО \"Лютик\"
+ПолучательИНН=7702345678
+ПолучательКПП=770201001
+ПолучательБИК=044525225
+НазначениеПлатежа=Оплата по счету 7
+КонецДокумента
+СекцияДокумент=Платежное поручение
+Номер=3
+Дата=20.01.2023
+Сумма=500
+ПлательщикСчет=40702810500000000003
+Плательщик=ИП Иванов
+ПлательщикИНН=770300000001
+ПолучательСчет=40702810900000000001
+ДатаПоступило=20.01.2023
+НазначениеПлатежа1=Возврат
+НазначениеПлатежа2=займа
+КонецДокумента
+КонецФайла
+";
+
+    #[test]
+    fn statement_with_debit_and_credit() {
+        let parser = ClientBank1CParser::parse(FILE.as_bytes()).unwrap();
+        let statements = statements(&parser).unwrap();
+        assert_eq!(statements.len(), 1);
+        let st = &statements[0];
+        assert_eq!(st.account, "40702810900000000001");
+        assert_eq!(st.opening_balance.as_ref().unwrap().amount, Money::new(100000, 2));
+        assert_eq!(
+            st.closing_balance.as_ref().unwrap().date,
+            NaiveDate::from_ymd_opt(2023, 1, 31).unwrap()
+        );
+        assert_eq!(st.created_at, NaiveDate::from_ymd_opt(2023, 2, 1).unwrap().and_hms_opt(10, 15, 0));
+
+        let debit = &st.entries[0];
+        assert_eq!(debit.amount, Money::new(-20000, 2));
+        assert_eq!(debit.value_date, NaiveDate::from_ymd_opt(2023, 1, 11).unwrap());
+        assert_eq!(debit.customer_reference.as_deref(), Some("15"));
+        assert_eq!(debit.description, "Оплата по счету 7");
+        let payee = debit.counterparty.as_ref().unwrap();
+        assert_eq!(payee.name.as_deref(), Some("ООО \"Лютик\""));
+        assert_eq!(payee.account.as_deref(), Some("40702810100000000002"));
+        assert_eq!(payee.inn.as_deref(), Some("7702345678"));
+        assert_eq!(payee.kpp.as_deref(), Some("770201001"));
+        assert_eq!(payee.bank_code.as_deref(), Some("044525225"));
+
+        let credit = &st.entries[1];
+        assert_eq!(credit.amount, Money::new(50000, 2));
+        assert_eq!(credit.description, "Возврат займа");
+        assert_eq!(credit.counterparty.as_ref().unwrap().name.as_deref(), Some("ИП Иванов"));
+    }
+
+    #[test]
+    fn missing_account_is_error() {
+        let parser = ClientBank1CParser::parse("1CClientBankExchange\nВерсияФормата=1.03\nКонецФайла\n".as_bytes()).unwrap();
+        assert!(matches!(statements(&parser), Err(ConvertError::Parse(_))));
+    }
+}
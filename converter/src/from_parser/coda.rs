@@ -0,0 +1,207 @@
+//! Разбор CODA в [`Statement`]: выписка на каждый блок `0`…`9`.
+
+use chrono::NaiveDate;
+use parser::{CodaMovement, CodaParser, CodaRecord, CodaStatement};
+
+use crate::{
+    date::parse_yymmdd,
+    error::ConvertError,
+    model::{Balance, BalanceKind, Counterparty, Statement, Transaction},
+    money::{Money, currency_exponent, overflow},
+    options::ConvertOptions,
+};
+
+/// Суммы CODA — 15 цифр, из них 3 после запятой.
+pub(crate) const AMOUNT_EXPONENT: u8 = 3;
+
+fn missing(what: &str) -> ConvertError {
+    parser::ParseError::InvalidFormat(format!("CODA: нет обязательного поля {}", what)).into()
+}
+
+/// Дата `DDMMYY`; `000000` — даты нет.
+fn date(record: &CodaRecord, start: usize, options: &ConvertOptions) -> Result<Option<NaiveDate>, ConvertError> {
+    let raw = record.raw(start, 6);
+    if raw.trim_matches(['0', ' ']).is_empty() {
+        return Ok(None);
+    }
+    let (day, rest) = raw.split_at(2);
+    let (month, year) = rest.split_at(2);
+    parse_yymmdd(&format!("{}{}{}", year, month, day), options.century_pivot).map(Some)
+}
+
+/// Сумма с признаком `0` — кредит, `1` — дебет в позиции `sign`, за ней 15 цифр.
+fn amount(record: &CodaRecord, sign: usize, currency: &str) -> Result<Money, ConvertError> {
+    let digits = record.raw(sign + 1, 15);
+    let value: i64 = digits.trim().parse().map_err(|_| ConvertError::InvalidAmount(digits.clone()))?;
+    let money = Money::new(value, AMOUNT_EXPONENT)
+        .rescale(currency_exponent(currency))
+        .ok_or(ConvertError::InvalidAmount(digits))?;
+    if record.raw(sign, 1) == "1" {
+        money.checked_neg().ok_or_else(overflow)
+    } else {
+        Ok(money)
+    }
+}
+
+/// Структурированное сообщение `101`/`102`: `+++123/4567/89012+++`.
+fn structured_communication(record: &CodaRecord) -> Option<String> {
+    let digits = record.raw(65, 12);
+    let known = matches!(record.raw(62, 3).as_str(), "101" | "102");
+    (known && digits.bytes().all(|b| b.is_ascii_digit())).then(|| format!("+++{}/{}/{}+++", &digits[..3], &digits[3..7], &digits[7..]))
+}
+
+fn parse_movement(movement: &CodaMovement, statement: &Statement, options: &ConvertOptions) -> Result<Transaction, ConvertError> {
+    let record = &movement.record;
+    let part2 = movement.detail("22");
+    let part3 = movement.detail("23");
+    let booking_date = date(record, 115, options)?;
+    let value_date = date(record, 47, options)?.or(booking_date).ok_or_else(|| missing("2.1: дата"))?;
+    let structured = match record.raw(61, 1).as_str() {
+        "1" => structured_communication(record),
+        _ => None,
+    };
+    // Свободное сообщение продолжается в 2.2 и 2.3 без разделителей.
+    let free = [Some(record.raw(62, 53)), part2.map(|r| r.raw(10, 53)), part3.map(|r| r.raw(82, 43))]
+        .into_iter()
+        .flatten()
+        .collect::<String>()
+        .trim()
+        .to_string();
+    let counterparty = Counterparty {
+        name: part3.and_then(|r| r.field(47, 35)),
+        account: part3.and_then(|r| r.field(10, 34)),
+        bic: part2.and_then(|r| r.field(98, 11)),
+        ..Default::default()
+    };
+    Ok(Transaction {
+        reference: statement.id.clone(),
+        account: statement.account.clone(),
+        amount: amount(record, 31, &statement.currency)?,
+        currency: statement.currency.clone(),
+        value_date,
+        booking_date: booking_date.filter(|d| *d != value_date),
+        customer_reference: part2.and_then(|r| r.field(63, 35)),
+        bank_reference: record.field(10, 21),
+        counterparty: Some(counterparty).filter(|c| !c.is_empty()),
+        gvc: record.field(53, 8),
+        description: structured.clone().unwrap_or(free),
+        remittance_information: structured,
+        ..Default::default()
+    })
+}
+
+/// Счет и валюта записи `1`/`8` начиная с `start`: номер BBAN (структура `0`) или IBAN/иностранный.
+fn account(record: &CodaRecord, structure: &str, start: usize) -> (Option<String>, Option<String>) {
+    match structure {
+        "0" => (record.field(start, 12), record.field(start + 13, 3)),
+        _ => (record.field(start, 34), record.field(start + 34, 3)),
+    }
+}
+
+fn parse_statement(coda: &CodaStatement, options: &ConvertOptions) -> Result<Statement, ConvertError> {
+    let old = &coda.old_balance;
+    let (number, currency) = account(old, &old.raw(1, 1), 5);
+    let currency = currency.ok_or_else(|| missing("1: валюта"))?;
+    let mut statement = Statement {
+        id: coda.header.field(24, 10).unwrap_or_else(|| "CODA".to_string()),
+        sequence_number: old.field(2, 3),
+        account: number.ok_or_else(|| missing("1: счет"))?,
+        currency: currency.clone(),
+        sender_bic: coda.header.field(60, 11),
+        created_at: date(&coda.header, 5, options)?.map(|d| d.and_time(Default::default())),
+        ..Default::default()
+    };
+    statement.opening_balance = Some(Balance {
+        kind: BalanceKind::Opening,
+        amount: amount(old, 42, &currency)?,
+        currency: currency.clone(),
+        date: date(old, 58, options)?.ok_or_else(|| missing("1: дата сальдо"))?,
+    });
+    let new = &coda.new_balance;
+    statement.closing_balance = Some(Balance {
+        kind: BalanceKind::Closing,
+        amount: amount(new, 41, &currency)?,
+        currency,
+        date: date(new, 57, options)?.ok_or_else(|| missing("8: дата сальдо"))?,
+    });
+    // Детали глобализованного движения (номер детали не `0000`) уже входят в его сумму.
+    for movement in coda.movements.iter().filter(|m| m.record.raw(6, 4) == "0000") {
+        let tx = parse_movement(movement, &statement, options)?;
+        statement.entries.push(tx);
+    }
+    Ok(statement)
+}
+
+/// Выписки всех блоков файла.
+pub(crate) fn statements(parser: &CodaParser, options: &ConvertOptions) -> Result<Vec<Statement>, ConvertError> {
+    parser.statements.iter().map(|st| parse_statement(st, options)).collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use parser::Parser;
+
+    use super::*;
+
+    fn line(text: &str) -> String {
+        format!("{:<128}", text)
+    }
+
+    fn file() -> String {
+        [
+            line("0000005012372505        FILE000042ACME NV                   GEBABEBB   00000000000 00000"),
+            line(&format!("12042{:<34}EUR0{:015}311222ACME NV", "BE68539007547034", 1_000_000)),
+            line(&format!(
+                "2100010000{:<21}1{:015}030123001010001101123456789012{:<38}04012304201 0",
+                "BANKREF1", 50_000, ""
+            )),
+            line(&format!("2200010000{:<53}{:<35}GKCCBEBB", "", "E2E-1")),
+            line(&format!("2300010000{:<34}EUR{:<35}", "BE71096123456769", "Supplier BVBA")),
+            line(&format!(
+                "2100020000{:<21}0{:015}040123001500000{:<53}04012304200 0",
+                "", 1_234_560, "Refund of order 77"
+            )),
+            line(&format!(
+                "2100020001{:<21}0{:015}040123001500000{:<53}04012304200 0",
+                "", 1_234_560, "detail"
+            )),
+            line(&format!("8042{:<34}EUR0{:015}040123", "BE68539007547034", 2_184_560)),
+            line(&format!("9{:15}{:06}{:015}{:015}", "", 7, 50_000, 2_469_120)),
+        ]
+        .join("\r\n")
+    }
+
+    #[test]
+    fn balances_movements_and_communication() {
+        let parser = CodaParser::parse(file().as_bytes()).unwrap();
+        let statements = statements(&parser, &ConvertOptions::default()).unwrap();
+        let st = &statements[0];
+        assert_eq!(
+            (st.id.as_str(), st.account.as_str(), st.currency.as_str()),
+            ("FILE000042", "BE68539007547034", "EUR")
+        );
+        assert_eq!(st.sequence_number.as_deref(), Some("042"));
+        assert_eq!(st.sender_bic.as_deref(), Some("GEBABEBB"));
+        assert_eq!(st.opening_balance.as_ref().unwrap().amount, Money::new(100000, 2));
+        assert_eq!(st.closing_balance.as_ref().unwrap().amount, Money::new(218456, 2));
+        assert_eq!(st.entries.len(), 2, "деталь глобализации не считается отдельной записью");
+
+        let payment = &st.entries[0];
+        assert_eq!(payment.amount, Money::new(-5000, 2));
+        assert_eq!(payment.value_date, NaiveDate::from_ymd_opt(2023, 1, 3).unwrap());
+        assert_eq!(payment.booking_date, NaiveDate::from_ymd_opt(2023, 1, 4));
+        assert_eq!(payment.gvc.as_deref(), Some("00101000"));
+        assert_eq!(payment.remittance_information.as_deref(), Some("+++123/4567/89012+++"));
+        assert_eq!(payment.bank_reference.as_deref(), Some("BANKREF1"));
+        assert_eq!(payment.customer_reference.as_deref(), Some("E2E-1"));
+        let counterparty = payment.counterparty.as_ref().unwrap();
+        assert_eq!(counterparty.account.as_deref(), Some("BE71096123456769"));
+        assert_eq!(counterparty.bic.as_deref(), Some("GKCCBEBB"));
+        assert_eq!(counterparty.name.as_deref(), Some("Supplier BVBA"));
+
+        let refund = &st.entries[1];
+        assert_eq!(refund.amount, Money::new(1234560 / 10, 2));
+        assert_eq!(refund.description, "Refund of order 77");
+        assert_eq!(refund.counterparty, None);
+    }
+}
@@ -0,0 +1,149 @@
+//! Разбор подтверждений дебета MT900 и кредита MT910: выписка с одной записью на сообщение.
+
+use chrono::NaiveDate;
+use parser::{Mt940Message, ParseError};
+
+use crate::{
+    date::parse_yymmdd,
+    error::ConvertError,
+    from_parser::parse_mt942_date_time,
+    model::{Counterparty, Statement, Transaction},
+    money::{Money, currency_exponent, overflow},
+    options::ConvertOptions,
+};
+
+fn field<'a>(message: &'a Mt940Message, tags: &[&str]) -> Option<&'a str> {
+    message
+        .records
+        .iter()
+        .find(|r| tags.contains(&r.tag.as_str()))
+        .map(|r| r.value.as_str())
+}
+
+/// Дата валютирования, валюта и сумма `:32A:230102USD233530,`.
+fn parse_value_date_amount(value: &str, options: &ConvertOptions) -> Result<(NaiveDate, String, Money), ConvertError> {
+    let invalid = || ParseError::InvalidFormat(format!("Invalid :32A: {}", value));
+    let date = parse_yymmdd(value.get(..6).ok_or_else(invalid)?, options.century_pivot)?;
+    let currency = value.get(6..9).ok_or_else(invalid)?;
+    let amount = Money::parse(value.get(9..).ok_or_else(invalid)?, currency_exponent(currency))?;
+    Ok((date, currency.to_string(), amount))
+}
+
+/// Плательщик MT910 из `:50a:` (`/счет`, наименование или BIC) и банк из `:52A:`.
+///
+/// В `:50F:` наименование — строка `1/...`, в `:50K:` — первая строка после счета.
+fn ordering_party(message: &Mt940Message) -> Option<Counterparty> {
+    let mut counterparty = Counterparty::default();
+    if let Some(record) = message.records.iter().find(|r| r.tag.starts_with("50")) {
+        let mut lines = record.value.lines().peekable();
+        if let Some(account) = lines.peek().and_then(|l| l.strip_prefix('/')) {
+            counterparty.account = Some(account.to_string());
+            lines.next();
+        }
+        match record.tag.as_str() {
+            "50A" => counterparty.bic = lines.next().map(str::to_string),
+            "50F" => counterparty.name = lines.find_map(|l| l.strip_prefix("1/")).map(str::to_string),
+            _ => counterparty.name = lines.next().map(str::to_string),
+        }
+    }
+    if counterparty.bic.is_none() {
+        // `:52A:` — BIC, которому может предшествовать строка `/идентификатор`.
+        counterparty.bic = field(message, &["52A"])
+            .and_then(|v| v.lines().find(|l| !l.starts_with('/')))
+            .map(str::to_string);
+    }
+    Some(counterparty).filter(|c| !c.is_empty())
+}
+
+fn parse_message(message: &Mt940Message, debit: bool, options: &ConvertOptions) -> Result<Statement, ConvertError> {
+    let missing = |tag: &str| ConvertError::from(ParseError::InvalidFormat(format!("Нет обязательного поля :{}:", tag)));
+    let id = field(message, &["20"]).ok_or_else(|| missing("20"))?.to_string();
+    // `:25P:` — счет и BIC владельца на второй строке.
+    let account = field(message, &["25", "25P"])
+        .and_then(|v| v.lines().next())
+        .ok_or_else(|| missing("25"))?
+        .to_string();
+    let (value_date, currency, amount) = parse_value_date_amount(field(message, &["32A"]).ok_or_else(|| missing("32A"))?, options)?;
+    let tx = Transaction {
+        reference: id.clone(),
+        account: account.clone(),
+        amount: if debit {
+            amount.checked_neg().ok_or_else(overflow)?
+        } else {
+            amount
+        },
+        currency: currency.clone(),
+        value_date,
+        customer_reference: field(message, &["21"]).filter(|r| *r != "NONREF").map(str::to_string),
+        bank_reference: Some(id.clone()),
+        counterparty: if debit { None } else { ordering_party(message) },
+        description: field(message, &["72"]).unwrap_or_default().to_string(),
+        ..Default::default()
+    };
+    Ok(Statement {
+        id,
+        account,
+        currency,
+        sender_bic: message.header.sender_bic(),
+        receiver_bic: message.header.receiver_bic(),
+        created_at: field(message, &["13D"]).map(|v| parse_mt942_date_time(v, options)).transpose()?,
+        entries: vec![tx],
+        ..Default::default()
+    })
+}
+
+/// Выписка на каждое сообщение; `debit` — MT900, иначе MT910.
+pub(crate) fn statements(messages: &[Mt940Message], debit: bool, options: &ConvertOptions) -> Result<Vec<Statement>, ConvertError> {
+    messages.iter().map(|message| parse_message(message, debit, options)).collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use parser::{Mt900Parser, Mt910Parser, Parser};
+
+    use super::*;
+    use crate::FromParser;
+
+    #[test]
+    fn debit_confirmation() {
+        let mt900 =
+            ":20:C11126A1378\n:21:5482ABC\n:25P:9-9876543\nBANKDEFF\n:13D:2301021030+0100\n:32A:230102USD233530,\n:72:/BNF/FEES\n";
+        let parser = Mt900Parser::parse(mt900.as_bytes()).unwrap();
+        let statements = parser.to_statements(&ConvertOptions::default()).unwrap();
+        let st = &statements[0];
+        assert_eq!(
+            (st.id.as_str(), st.account.as_str(), st.currency.as_str()),
+            ("C11126A1378", "9-9876543", "USD")
+        );
+        assert_eq!(st.created_at, NaiveDate::from_ymd_opt(2023, 1, 2).unwrap().and_hms_opt(10, 30, 0));
+        let tx = &st.entries[0];
+        assert_eq!(tx.amount, Money::new(-23353000, 2));
+        assert_eq!(tx.value_date, NaiveDate::from_ymd_opt(2023, 1, 2).unwrap());
+        assert_eq!(tx.customer_reference.as_deref(), Some("5482ABC"));
+        assert_eq!(tx.bank_reference.as_deref(), Some("C11126A1378"));
+        assert_eq!(tx.description, "/BNF/FEES");
+    }
+
+    #[test]
+    fn credit_confirmation_with_ordering_party() {
+        let mt910 = ":20:C11126C9224\n:21:494936/DEV\n:25:6-9412771\n:32A:230103EUR1000,00\n\
+                     :50K:/123-456-789\nJOHN SMITH\nLONDON\n:52A:BKAUATWW\n";
+        let parser = Mt910Parser::parse(mt910.as_bytes()).unwrap();
+        let txs = parser.to_transactions(&ConvertOptions::default()).unwrap();
+        assert_eq!(txs[0].amount, Money::new(100000, 2));
+        let counterparty = txs[0].counterparty.as_ref().unwrap();
+        assert_eq!(counterparty.name.as_deref(), Some("JOHN SMITH"));
+        assert_eq!(counterparty.account.as_deref(), Some("123-456-789"));
+        assert_eq!(counterparty.bic.as_deref(), Some("BKAUATWW"));
+
+        let mt910 = mt910.replace(
+            ":50K:/123-456-789\nJOHN SMITH\nLONDON\n",
+            ":50F:/DE89370400440532013000\n1/ACME GMBH\n2/BERLIN\n",
+        );
+        let txs = Mt910Parser::parse(mt910.as_bytes())
+            .unwrap()
+            .to_transactions(&ConvertOptions::default())
+            .unwrap();
+        assert_eq!(txs[0].counterparty.as_ref().unwrap().name.as_deref(), Some("ACME GMBH"));
+    }
+}
@@ -0,0 +1,68 @@
+//! Чтение JSON и JSON Lines по схеме [`crate::schema`].
+
+use parser::{JsonLinesParser, JsonParser, ParseError};
+use serde::Deserialize;
+
+use crate::{
+    error::ConvertError,
+    model::Statement,
+    schema::{JsonDocument, JsonLine, check_version},
+};
+
+fn invalid(format: &str, error: serde_json::Error) -> ConvertError {
+    ParseError::InvalidFormat(format!("{} не соответствует схеме: {}", format, error)).into()
+}
+
+/// Выписки документа JSON.
+pub(crate) fn statements(parser: &JsonParser) -> Result<Vec<Statement>, ConvertError> {
+    let document = JsonDocument::deserialize(&parser.document).map_err(|e| invalid("JSON", e))?;
+    check_version(document.schema_version)?;
+    Ok(document.statements)
+}
+
+/// Выписки из строк JSON Lines: подряд идущие транзакции одной выписки объединяются.
+pub(crate) fn line_statements(parser: &JsonLinesParser) -> Result<Vec<Statement>, ConvertError> {
+    let mut statements: Vec<Statement> = Vec::new();
+    for (index, value) in parser.lines.iter().enumerate() {
+        let line = JsonLine::deserialize(value).map_err(|e| invalid(&format!("JSON Lines, запись {}", index + 1), e))?;
+        check_version(line.schema_version)?;
+        let tx = line.transaction;
+        match statements.last_mut() {
+            Some(st) if st.id == tx.reference && st.account == tx.account && st.currency == tx.currency => st.entries.push(tx),
+            _ => statements.push(Statement::from_entries(vec![tx])),
+        }
+    }
+    Ok(statements)
+}
+
+#[cfg(test)]
+mod tests {
+    use parser::Parser;
+
+    use super::*;
+    use crate::money::Money;
+
+    #[test]
+    fn lines_are_grouped_and_versions_checked() {
+        let lines = [
+            r#"{"schema_version":1,"reference":"S1","account":"A","amount":"-5.00","currency":"EUR","value_date":"2023-01-02","description":"Fee"}"#,
+            r#"{"schema_version":1,"reference":"S1","account":"A","amount":"10","currency":"EUR","value_date":"2023-01-03","description":"","reversal":true}"#,
+            r#"{"schema_version":1,"reference":"S2","account":"A","amount":"1.5","currency":"EUR","value_date":"2023-01-03","description":""}"#,
+        ];
+        let parser = JsonLinesParser::parse(lines.join("\n").as_bytes()).unwrap();
+        let statements = line_statements(&parser).unwrap();
+        assert_eq!(statements.len(), 2);
+        assert_eq!(statements[0].entries.len(), 2);
+        assert_eq!(statements[0].entries[0].amount, Money::new(-500, 2));
+        assert_eq!(statements[0].entries[1].amount.exponent(), 0);
+        assert!(statements[0].entries[1].reversal);
+        assert_eq!(statements[1].id, "S2");
+
+        let future = lines[0].replace(r#""schema_version":1"#, r#""schema_version":2"#);
+        let parser = JsonLinesParser::parse(future.as_bytes()).unwrap();
+        assert!(line_statements(&parser).is_err());
+        let float = lines[0].replace(r#""-5.00""#, "-5.0");
+        let parser = JsonLinesParser::parse(float.as_bytes()).unwrap();
+        assert!(line_statements(&parser).unwrap_err().to_string().contains("запись 1"));
+    }
+}
@@ -0,0 +1,201 @@
+//! Разбор OFX (`STMTRS`, `CCSTMTRS`) в [`Statement`].
+
+use parser::{OfxParser, XmlElement};
+
+use crate::{
+    date::parse_ofx_date_time,
+    error::ConvertError,
+    model::{Balance, BalanceKind, Counterparty, Statement, Transaction},
+    money::{Money, currency_exponent},
+};
+
+/// Соответствие `TRNTYPE` кодам SWIFT в [`Transaction::transaction_type`].
+///
+/// При записи берется первая пара с нужным кодом; `CREDIT`/`DEBIT` и прочие
+/// типы без аналога в SWIFT кода не получают.
+pub(crate) const TRANSACTION_TYPES: [(&str, &str); 8] = [
+    ("XFER", "NTRF"),
+    ("DIRECTDEP", "NTRF"),
+    ("FEE", "NCHG"),
+    ("SRVCHG", "NCHG"),
+    ("INT", "NINT"),
+    ("DIV", "NDIV"),
+    ("CHECK", "NCHK"),
+    ("DIRECTDEBIT", "NDDT"),
+];
+
+fn missing(what: &str) -> ConvertError {
+    parser::ParseError::InvalidFormat(format!("OFX: нет обязательного элемента {}", what)).into()
+}
+
+fn parse_amount(text: &str, currency: &str) -> Result<Money, ConvertError> {
+    Money::parse(text, currency_exponent(currency))
+}
+
+fn parse_balance(el: &XmlElement, kind: BalanceKind, currency: &str) -> Result<Balance, ConvertError> {
+    let amount = el.find_text(&["BALAMT"]).ok_or_else(|| missing("BALAMT"))?;
+    let date = el.find_text(&["DTASOF"]).ok_or_else(|| missing("DTASOF"))?;
+    Ok(Balance {
+        kind,
+        amount: parse_amount(amount, currency)?,
+        currency: currency.to_string(),
+        date: parse_ofx_date_time(date)?.date(),
+    })
+}
+
+fn parse_transaction(trn: &XmlElement, statement: &Statement) -> Result<Transaction, ConvertError> {
+    let amount = trn.find_text(&["TRNAMT"]).ok_or_else(|| missing("TRNAMT"))?;
+    let posted = trn.find_text(&["DTPOSTED"]).ok_or_else(|| missing("DTPOSTED"))?;
+    let transaction_type = trn.find_text(&["TRNTYPE"]).and_then(|trntype| {
+        TRANSACTION_TYPES
+            .iter()
+            .find(|(ofx, _)| *ofx == trntype)
+            .map(|(_, swift)| swift.to_string())
+    });
+    let name = trn.find_text(&["NAME"]).or_else(|| trn.find_text(&["PAYEE", "NAME"]));
+    let to = trn.child("BANKACCTTO").or_else(|| trn.child("CCACCTTO"));
+    let counterparty = Counterparty {
+        name: name.map(str::to_string),
+        account: to.and_then(|el| el.find_text(&["ACCTID"])).map(str::to_string),
+        bank_code: to.and_then(|el| el.find_text(&["BANKID"])).map(str::to_string),
+        ..Default::default()
+    };
+    let memo = trn.find_text(&["MEMO"]);
+
+    Ok(Transaction {
+        reference: statement.id.clone(),
+        account: statement.account.clone(),
+        amount: parse_amount(amount, &statement.currency)?,
+        currency: statement.currency.clone(),
+        value_date: parse_ofx_date_time(posted)?.date(),
+        customer_reference: trn
+            .find_text(&["CHECKNUM"])
+            .or_else(|| trn.find_text(&["REFNUM"]))
+            .map(str::to_string),
+        bank_reference: trn.find_text(&["FITID"]).map(str::to_string),
+        transaction_type,
+        counterparty: Some(counterparty).filter(|c| !c.is_empty()),
+        description: memo.or(name).unwrap_or_default().to_string(),
+        ..Default::default()
+    })
+}
+
+/// Выписка из `STMTTRNRS`/`CCSTMTTRNRS`.
+fn parse_statement(
+    trnrs: &XmlElement,
+    stmtrs: &XmlElement,
+    created_at: Option<chrono::NaiveDateTime>,
+) -> Result<Statement, ConvertError> {
+    let currency = stmtrs.find_text(&["CURDEF"]).ok_or_else(|| missing("CURDEF"))?;
+    let account = stmtrs
+        .find_text(&["BANKACCTFROM", "ACCTID"])
+        .or_else(|| stmtrs.find_text(&["CCACCTFROM", "ACCTID"]))
+        .ok_or_else(|| missing("ACCTID"))?;
+    let mut statement = Statement {
+        id: trnrs.find_text(&["TRNUID"]).unwrap_or_default().to_string(),
+        account: account.to_string(),
+        currency: currency.to_string(),
+        created_at,
+        ..Default::default()
+    };
+    if let Some(ledger) = stmtrs.child("LEDGERBAL") {
+        statement.closing_balance = Some(parse_balance(ledger, BalanceKind::Closing, currency)?);
+    }
+    if let Some(available) = stmtrs.child("AVAILBAL") {
+        statement
+            .available_balances
+            .push(parse_balance(available, BalanceKind::ClosingAvailable, currency)?);
+    }
+    if let Some(list) = stmtrs.child("BANKTRANLIST") {
+        for trn in list.children("STMTTRN") {
+            let tx = parse_transaction(trn, &statement)?;
+            statement.entries.push(tx);
+        }
+    }
+    Ok(statement)
+}
+
+/// Выписки по банковским счетам и картам в порядке следования.
+pub(crate) fn statements(parser: &OfxParser) -> Result<Vec<Statement>, ConvertError> {
+    let ofx = &parser.document;
+    let created_at = ofx
+        .find_text(&["SIGNONMSGSRSV1", "SONRS", "DTSERVER"])
+        .map(parse_ofx_date_time)
+        .transpose()?;
+    let bank = ofx.children("BANKMSGSRSV1").flat_map(|msgs| msgs.children("STMTTRNRS"));
+    let card = ofx.children("CREDITCARDMSGSRSV1").flat_map(|msgs| msgs.children("CCSTMTTRNRS"));
+    bank.filter_map(|trnrs| trnrs.child("STMTRS").map(|rs| (trnrs, rs)))
+        .chain(card.filter_map(|trnrs| trnrs.child("CCSTMTRS").map(|rs| (trnrs, rs))))
+        .map(|(trnrs, stmtrs)| parse_statement(trnrs, stmtrs, created_at))
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use chrono::NaiveDate;
+    use parser::Parser;
+
+    use super::*;
+
+    const SGML: &str = "OFXHEADER:100
+DATA:OFXSGML
+VERSION:102
+ENCODING:USASCII
+CHARSET:1252
+
+<OFX>
+<SIGNONMSGSRSV1><SONRS><STATUS><CODE>0<SEVERITY>INFO</STATUS><DTSERVER>20230201120000[-5:EST]<LANGUAGE>ENG</SONRS></SIGNONMSGSRSV1>
+<BANKMSGSRSV1><STMTTRNRS><TRNUID>1001<STATUS><CODE>0<SEVERITY>INFO</STATUS>
+<STMTRS><CURDEF>USD<BANKACCTFROM><BANKID>121000248<ACCTID>123456789<ACCTTYPE>CHECKING</BANKACCTFROM>
+<BANKTRANLIST><DTSTART>20230101<DTEND>20230131
+<STMTTRN><TRNTYPE>DEBIT<DTPOSTED>20230105<TRNAMT>-42.50<FITID>F1<NAME>Coffee Shop<MEMO>Card 1234</STMTTRN>
+<STMTTRN><TRNTYPE>XFER<DTPOSTED>20230110120000<TRNAMT>1000.00<FITID>F2<CHECKNUM>77<NAME>Employer
+<BANKACCTTO><BANKID>026009593<ACCTID>555<ACCTTYPE>CHECKING</BANKACCTTO></STMTTRN>
+</BANKTRANLIST>
+<LEDGERBAL><BALAMT>2457.50<DTASOF>20230131</LEDGERBAL>
+<AVAILBAL><BALAMT>2400.00<DTASOF>20230131</AVAILBAL>
+</STMTRS></STMTTRNRS></BANKMSGSRSV1>
+</OFX>
+";
+
+    #[test]
+    fn bank_statement_from_sgml() {
+        let parser = OfxParser::parse(SGML.as_bytes()).unwrap();
+        let statements = statements(&parser).unwrap();
+        assert_eq!(statements.len(), 1);
+        let st = &statements[0];
+        assert_eq!(
+            (st.id.as_str(), st.account.as_str(), st.currency.as_str()),
+            ("1001", "123456789", "USD")
+        );
+        assert_eq!(st.created_at, NaiveDate::from_ymd_opt(2023, 2, 1).unwrap().and_hms_opt(12, 0, 0));
+        let closing = st.closing_balance.as_ref().unwrap();
+        assert_eq!(closing.amount, Money::new(245750, 2));
+        assert_eq!(st.available_balances[0].kind, BalanceKind::ClosingAvailable);
+
+        let card = &st.entries[0];
+        assert_eq!(card.amount, Money::new(-4250, 2));
+        assert_eq!(card.bank_reference.as_deref(), Some("F1"));
+        assert_eq!(card.transaction_type, None);
+        assert_eq!(card.description, "Card 1234");
+        assert_eq!(card.counterparty.as_ref().unwrap().name.as_deref(), Some("Coffee Shop"));
+
+        let salary = &st.entries[1];
+        assert_eq!(salary.value_date, NaiveDate::from_ymd_opt(2023, 1, 10).unwrap());
+        assert_eq!(salary.transaction_type.as_deref(), Some("NTRF"));
+        assert_eq!(salary.customer_reference.as_deref(), Some("77"));
+        assert_eq!(salary.description, "Employer");
+        let payer = salary.counterparty.as_ref().unwrap();
+        assert_eq!(
+            (payer.account.as_deref(), payer.bank_code.as_deref()),
+            (Some("555"), Some("026009593"))
+        );
+    }
+
+    #[test]
+    fn missing_amount_is_error() {
+        let ofx = SGML.replace("<TRNAMT>-42.50", "");
+        let parser = OfxParser::parse(ofx.as_bytes()).unwrap();
+        assert!(matches!(statements(&parser), Err(ConvertError::Parse(_))));
+    }
+}
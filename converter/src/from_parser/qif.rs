@@ -0,0 +1,193 @@
+//! Разбор QIF (`!Type:Bank`, `CCard`, `Cash`, `Oth A`, `Oth L`) в [`Statement`].
+
+use parser::{QifParser, QifRecord, QifSection};
+
+use crate::{
+    date::parse_qif_date,
+    error::ConvertError,
+    model::{Counterparty, Split, Statement, Transaction},
+    money::{Money, currency_exponent},
+    options::ConvertOptions,
+};
+
+/// Типы разделов с операциями по счету; `Cat`, `Class`, `Memorized`, `Invst` пропускаются.
+const ACCOUNT_TYPES: [&str; 5] = ["Bank", "CCard", "Cash", "Oth A", "Oth L"];
+
+/// Сумма QIF: `-1,234.56`, `1.234,56`, `10`.
+///
+/// Разделитель дробной части — последний `,` или `.`, если после него не три цифры
+/// или встречаются оба разделителя; остальные считаются разделителями тысяч.
+fn parse_amount(text: &str, exponent: u8) -> Result<Money, ConvertError> {
+    let text: String = text.chars().filter(|c| !c.is_whitespace()).collect();
+    let decimal = text.rfind([',', '.']).filter(|&pos| {
+        let both = text.contains(',') && text.contains('.');
+        let repeated = text.matches(&text[pos..=pos]).count() > 1;
+        both || (!repeated && text.len() - pos - 1 != 3)
+    });
+    let normalized: String = text
+        .char_indices()
+        .filter(|(pos, c)| !matches!(c, ',' | '.') || Some(*pos) == decimal)
+        .map(|(_, c)| c)
+        .collect();
+    Money::parse(&normalized, exponent).map_err(|_| ConvertError::InvalidAmount(text.clone()))
+}
+
+fn parse_splits(record: &QifRecord, exponent: u8) -> Result<Vec<Split>, ConvertError> {
+    let mut splits: Vec<Split> = Vec::new();
+    let mut amount_seen = false;
+    for (code, value) in &record.fields {
+        let value = value.trim();
+        // Новая часть начинается с `S`, а при его отсутствии — с `E`/`$` после заполненной части.
+        let starts_new = match code {
+            'S' => true,
+            'E' => splits.last().is_none_or(|s| s.memo.is_some() || amount_seen),
+            '$' => splits.last().is_none_or(|_| amount_seen),
+            _ => continue,
+        };
+        if starts_new {
+            splits.push(Split {
+                category: None,
+                memo: None,
+                amount: Money::zero(exponent),
+            });
+            amount_seen = false;
+        }
+        let split = splits.last_mut().expect("часть только что добавлена");
+        match code {
+            'S' => split.category = Some(value.to_string()).filter(|v| !v.is_empty()),
+            'E' => split.memo = Some(value.to_string()).filter(|v| !v.is_empty()),
+            _ => {
+                split.amount = parse_amount(value, exponent)?;
+                amount_seen = true;
+            }
+        }
+    }
+    Ok(splits)
+}
+
+fn parse_record(record: &QifRecord, statement: &Statement, options: &ConvertOptions) -> Result<Transaction, ConvertError> {
+    let missing = |code: &str| ConvertError::Parse(parser::ParseError::InvalidFormat(format!("QIF: нет поля {}", code)));
+    let exponent = currency_exponent(&statement.currency);
+    let date = record.get('D').ok_or_else(|| missing("D"))?;
+    let amount = record.get('T').or_else(|| record.get('U')).ok_or_else(|| missing("T"))?;
+    let payee = record.get('P');
+    Ok(Transaction {
+        reference: statement.id.clone(),
+        account: statement.account.clone(),
+        amount: parse_amount(amount, exponent)?,
+        currency: statement.currency.clone(),
+        value_date: parse_qif_date(date, options.qif_date_format, options.century_pivot)?,
+        customer_reference: record.get('N').map(str::to_string),
+        counterparty: payee.map(|name| Counterparty {
+            name: Some(name.to_string()),
+            ..Default::default()
+        }),
+        category: record.get('L').map(str::to_string),
+        splits: parse_splits(record, exponent)?,
+        description: record.get('M').or(payee).unwrap_or_default().to_string(),
+        ..Default::default()
+    })
+}
+
+fn parse_section(section: &QifSection, options: &ConvertOptions) -> Result<Statement, ConvertError> {
+    let account = section.account.as_ref().and_then(|a| a.get('N')).unwrap_or_default();
+    let mut statement = Statement {
+        id: if account.is_empty() {
+            "QIF".to_string()
+        } else {
+            account.to_string()
+        },
+        account: account.to_string(),
+        currency: options.qif_currency.clone(),
+        ..Default::default()
+    };
+    for record in &section.records {
+        let tx = parse_record(record, &statement, options)?;
+        statement.entries.push(tx);
+    }
+    Ok(statement)
+}
+
+/// Выписка на каждый раздел с операциями по счету.
+pub(crate) fn statements(parser: &QifParser, options: &ConvertOptions) -> Result<Vec<Statement>, ConvertError> {
+    parser
+        .sections
+        .iter()
+        .filter(|section| ACCOUNT_TYPES.iter().any(|kind| kind.eq_ignore_ascii_case(&section.kind)))
+        .map(|section| parse_section(section, options))
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use chrono::NaiveDate;
+    use parser::Parser;
+
+    use super::*;
+    use crate::options::QifDateFormat;
+
+    const QIF: &str = "!Type:Cat
+NFood
+^
+!Account
+NChecking
+TBank
+^
+!Type:Bank
+D31/01/2023
+T-1.234,50
+N1001
+PLandlord
+MRent January
+LHousing
+^
+D5/1'23
+T-42.50
+PCafe
+SFood
+EBreakfast
+$-40.00
+SFees
+$-2.50
+^
+";
+
+    #[test]
+    fn bank_section_with_splits() {
+        let options = ConvertOptions {
+            qif_date_format: QifDateFormat::European,
+            qif_currency: "EUR".to_string(),
+            ..Default::default()
+        };
+        let parser = QifParser::parse(QIF.as_bytes()).unwrap();
+        let statements = statements(&parser, &options).unwrap();
+        assert_eq!(statements.len(), 1);
+        let st = &statements[0];
+        assert_eq!((st.account.as_str(), st.currency.as_str()), ("Checking", "EUR"));
+
+        let rent = &st.entries[0];
+        assert_eq!(rent.amount, Money::new(-123450, 2));
+        assert_eq!(rent.value_date, NaiveDate::from_ymd_opt(2023, 1, 31).unwrap());
+        assert_eq!(rent.customer_reference.as_deref(), Some("1001"));
+        assert_eq!(rent.description, "Rent January");
+        assert_eq!(rent.category.as_deref(), Some("Housing"));
+        assert!(rent.splits.is_empty());
+
+        let cafe = &st.entries[1];
+        assert_eq!(cafe.value_date, NaiveDate::from_ymd_opt(2023, 1, 5).unwrap());
+        assert_eq!(cafe.description, "Cafe");
+        assert_eq!(cafe.splits.len(), 2);
+        assert_eq!(cafe.splits[0].memo.as_deref(), Some("Breakfast"));
+        assert_eq!(cafe.splits[1].category.as_deref(), Some("Fees"));
+        assert_eq!(cafe.splits[1].amount, Money::new(-250, 2));
+    }
+
+    #[test]
+    fn amount_separators() {
+        assert_eq!(parse_amount("1,234.56", 2).unwrap(), Money::new(123456, 2));
+        assert_eq!(parse_amount("1.234", 2).unwrap(), Money::new(123400, 2));
+        assert_eq!(parse_amount("-12,5", 2).unwrap(), Money::new(-1250, 2));
+        assert_eq!(parse_amount("1,234,567", 2).unwrap(), Money::new(123456700, 2));
+        assert!(parse_amount("12a", 2).is_err());
+    }
+}
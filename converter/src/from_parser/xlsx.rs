@@ -0,0 +1,174 @@
+//! Чтение листа xlsx через сопоставление колонок CSV ([`crate::CsvMapping`]).
+//!
+//! Числовые ячейки переводятся в текст с десятичным разделителем диалекта
+//! (шум двоичной дроби округляется до знаков валюты строки), ячейки-даты —
+//! в текст по [`crate::CsvMapping::date_format`], так что профиль CSV подходит
+//! к листу без изменений.
+
+use std::fmt::Write;
+
+use chrono::{NaiveDate, NaiveDateTime};
+use parser::{CsvRow, XlsxCell, XlsxParser};
+
+use crate::{error::ConvertError, model::Statement, money::currency_exponent, options::ConvertOptions};
+
+/// Дата ISO из ячейки в формате профиля; если формат к ней не подходит — как есть.
+fn date_text(iso: &str, format: &str) -> String {
+    let parsed = NaiveDateTime::parse_from_str(iso, "%Y-%m-%dT%H:%M:%S%.f")
+        .or_else(|_| NaiveDate::parse_from_str(iso, "%Y-%m-%d").map(|d| d.and_time(Default::default())));
+    let mut text = String::new();
+    match parsed {
+        Ok(value) if write!(text, "{}", value.format(format)).is_ok() => text,
+        _ => iso.to_string(),
+    }
+}
+
+/// Число из ячейки: `0.1 + 0.2` из формулы дает 0.30000000000000004, и без округления
+/// [`crate::Money::parse`] отклонит его как потерю точности.
+///
+/// Округляется до `exponent` знаков, только если сдвиг не больше погрешности f64
+/// (несколько ulp). Настоящие лишние знаки (`1.2345` для USD) остаются и дают ошибку суммы.
+fn number_text(value: f64, exponent: u8) -> String {
+    let units = value * 10f64.powi(exponent.into());
+    let tolerance = units.abs().max(1.0) * f64::EPSILON * 1024.0;
+    if (units - units.round()).abs() > tolerance {
+        // `Display` у f64 — кратчайшая запись без экспоненты: 1234.5, -0.07.
+        return value.to_string();
+    }
+    let text = format!("{:.*}", usize::from(exponent), units.round() / 10f64.powi(exponent.into()));
+    if text.contains('.') {
+        text.trim_end_matches('0').trim_end_matches('.').to_string()
+    } else {
+        text
+    }
+}
+
+fn cell_text(cell: &XlsxCell, date_format: &str, decimal: char, exponent: u8) -> String {
+    match cell {
+        XlsxCell::Empty => String::new(),
+        XlsxCell::Text(text) => text.clone(),
+        XlsxCell::Number(value) => number_text(*value, exponent).replace('.', &decimal.to_string()),
+        XlsxCell::Date(iso) => date_text(iso, date_format),
+    }
+}
+
+/// Одна выписка из строк листа.
+///
+/// Заголовок ищется по колонкам профиля, заданным по имени: строки над таблицей
+/// (название отчета, период) пропускаются без `skip_rows`. Пустые строки не читаются.
+pub(crate) fn statements(parser: &XlsxParser, options: &ConvertOptions) -> Result<Vec<Statement>, ConvertError> {
+    let mut mapping = options.csv_mapping.clone();
+    let mut dialect = options.csv_dialect.clone();
+    let decimal = if dialect.decimal_comma { ',' } else { '.' };
+    let text_row = |cells: &[XlsxCell], exponent: u8| CsvRow {
+        row: cells
+            .iter()
+            .map(|c| cell_text(c, &mapping.date_format, decimal, exponent))
+            .collect(),
+    };
+    let default_exponent = currency_exponent(&mapping.default_currency);
+    let mut rows: Vec<CsvRow> = parser.rows.iter().map(|cells| text_row(cells, default_exponent)).collect();
+    let start = match mapping.header_row(&rows) {
+        Some(header) => {
+            dialect.has_header = true;
+            header
+        }
+        None => mapping.skip_rows,
+    };
+    // Колонка валюты известна только после заголовка: числа строк с другой валютой округляются заново.
+    let header = rows.get(start).filter(|_| dialect.has_header);
+    if let Some(column) = mapping.currency_column(header) {
+        for (row, cells) in rows.iter_mut().zip(&parser.rows) {
+            let currency = row.row.get(column).map(|c| c.trim()).filter(|c| !c.is_empty());
+            let exponent = currency.map_or(default_exponent, currency_exponent);
+            if exponent != default_exponent {
+                *row = text_row(cells, exponent);
+            }
+        }
+    }
+    mapping.skip_rows = 0;
+    let rows: Vec<CsvRow> = rows
+        .into_iter()
+        .skip(start)
+        .filter(|r| r.row.iter().any(|c| !c.trim().is_empty()))
+        .collect();
+    Ok(vec![Statement::from_entries(mapping.transactions(&rows, &dialect)?)])
+}
+
+#[cfg(test)]
+mod tests {
+    use rust_xlsxwriter::{ExcelDateTime, Workbook};
+
+    use super::*;
+    use crate::{CsvMapping, money::Money};
+
+    #[test]
+    fn cells_follow_profile_formats() {
+        assert_eq!(cell_text(&XlsxCell::Number(-1234.5), "%Y-%m-%d", ',', 2), "-1234,5");
+        assert_eq!(cell_text(&XlsxCell::Number(40702810.0), "%Y-%m-%d", '.', 2), "40702810");
+        assert_eq!(
+            cell_text(&XlsxCell::Date("2023-01-02".to_string()), "%d.%m.%Y", '.', 2),
+            "02.01.2023"
+        );
+        assert_eq!(
+            cell_text(&XlsxCell::Date("2023-01-02T10:30:00".to_string()), "%d.%m.%Y %H:%M", '.', 2),
+            "02.01.2023 10:30"
+        );
+        assert_eq!(cell_text(&XlsxCell::Date("Q1".to_string()), "%d.%m.%Y", '.', 2), "Q1");
+    }
+
+    #[test]
+    fn float_noise_is_rounded_to_currency_exponent() {
+        assert_eq!(number_text(0.1 + 0.2, 2), "0.3");
+        assert_eq!(number_text(-(0.1 + 0.7), 2), "-0.8");
+        assert_eq!(number_text(1000.0000000000001, 0), "1000");
+        assert_eq!(number_text(1234567.89, 2), "1234567.89");
+        // Лишние знаки не шум: остаются, и Money::parse их отклонит.
+        assert_eq!(number_text(1.2345, 2), "1.2345");
+        assert_eq!(number_text(0.5, 0), "0.5");
+    }
+
+    #[test]
+    fn header_is_found_below_title_on_selected_sheet() {
+        let mut workbook = Workbook::new();
+        workbook.add_worksheet().set_name("Summary").unwrap();
+        let sheet = workbook.add_worksheet().set_name("Data").unwrap();
+        sheet.write_string(0, 0, "Выписка за январь").unwrap();
+        for (col, name) in (1..).zip(["Дата", "Сумма", "Назначение"]) {
+            sheet.write_string(2, col, name).unwrap();
+        }
+        let date = rust_xlsxwriter::Format::new().set_num_format("dd.mm.yyyy");
+        sheet
+            .write_datetime_with_format(3, 1, ExcelDateTime::from_ymd(2023, 1, 31).unwrap(), &date)
+            .unwrap();
+        sheet.write_number(3, 2, -99.9).unwrap();
+        sheet
+            .write_datetime_with_format(4, 1, ExcelDateTime::from_ymd(2023, 2, 1).unwrap(), &date)
+            .unwrap();
+        // Результат формулы `=0.1+0.2`, сохраненный как есть.
+        sheet
+            .write_formula(4, 2, "=0.1+0.2")
+            .unwrap()
+            .set_formula_result(4, 2, (0.1 + 0.2).to_string());
+        sheet.write_string(3, 3, "Связь").unwrap();
+        sheet.write_string(5, 3, "").unwrap();
+        let bytes = workbook.save_to_buffer().unwrap();
+
+        let parser = XlsxParser::parse_sheet(&bytes[..], Some("data")).unwrap();
+        assert_eq!(parser.sheet, "Data");
+        let options = ConvertOptions {
+            csv_mapping: CsvMapping::from_toml(
+                "value_date = \"Дата\"\ndate_format = \"%d.%m.%Y\"\namount = \"Сумма\"\ndescription = \"Назначение\"\ndefault_currency = \"RUB\"",
+            )
+            .unwrap(),
+            ..Default::default()
+        };
+        let statements = statements(&parser, &options).unwrap();
+        let txs = &statements[0].entries;
+        assert_eq!(txs.len(), 2);
+        assert_eq!(txs[0].amount, Money::new(-9990, 2));
+        assert_eq!(txs[1].amount, Money::new(30, 2));
+        assert_eq!(txs[0].value_date, NaiveDate::from_ymd_opt(2023, 1, 31).unwrap());
+        assert_eq!(txs[0].description, "Связь");
+    }
+}
@@ -0,0 +1,112 @@
+//! Проверка IBAN (ISO 13616) и BIC (ISO 9362) перед записью платежных файлов.
+
+/// Длина IBAN по коду страны для стран SEPA; для остальных проверяется только общая форма.
+const SEPA_LENGTHS: [(&str, usize); 36] = [
+    ("AD", 24),
+    ("AT", 20),
+    ("BE", 16),
+    ("BG", 22),
+    ("CH", 21),
+    ("CY", 28),
+    ("CZ", 24),
+    ("DE", 22),
+    ("DK", 18),
+    ("EE", 20),
+    ("ES", 24),
+    ("FI", 18),
+    ("FR", 27),
+    ("GB", 22),
+    ("GI", 23),
+    ("GR", 27),
+    ("HR", 21),
+    ("HU", 28),
+    ("IE", 22),
+    ("IS", 26),
+    ("IT", 27),
+    ("LI", 21),
+    ("LT", 20),
+    ("LU", 20),
+    ("LV", 21),
+    ("MC", 27),
+    ("MT", 31),
+    ("NL", 18),
+    ("NO", 15),
+    ("PL", 28),
+    ("PT", 25),
+    ("RO", 24),
+    ("SE", 24),
+    ("SI", 19),
+    ("SK", 24),
+    ("SM", 27),
+];
+
+/// IBAN без пробелов в верхнем регистре: `de89 3704 0044 0532 0130 00` → `DE89370400440532013000`.
+pub fn normalize_iban(iban: &str) -> String {
+    iban.chars().filter(|c| !c.is_whitespace()).collect::<String>().to_ascii_uppercase()
+}
+
+/// IBAN в нормальной форме ([`normalize_iban`]): форма `[A-Z]{2}[0-9]{2}[A-Z0-9]{11,30}`,
+/// длина для стран SEPA и контрольная сумма по модулю 97.
+pub fn is_valid_iban(iban: &str) -> bool {
+    let bytes = iban.as_bytes();
+    let shaped = (15..=34).contains(&bytes.len())
+        && bytes[..2].iter().all(u8::is_ascii_uppercase)
+        && bytes[2..4].iter().all(u8::is_ascii_digit)
+        && bytes[4..].iter().all(|b| b.is_ascii_uppercase() || b.is_ascii_digit());
+    if !shaped {
+        return false;
+    }
+    let country = SEPA_LENGTHS.iter().find(|(country, _)| country.as_bytes() == &bytes[..2]);
+    if country.is_some_and(|(_, length)| bytes.len() != *length) {
+        return false;
+    }
+    // Первые четыре символа переносятся в конец, буквы заменяются числами 10..35.
+    let remainder = bytes[4..].iter().chain(&bytes[..4]).fold(0u32, |acc, b| {
+        let value = if b.is_ascii_digit() {
+            u32::from(b - b'0')
+        } else {
+            u32::from(b - b'A') + 10
+        };
+        if value < 10 {
+            (acc * 10 + value) % 97
+        } else {
+            (acc * 100 + value) % 97
+        }
+    });
+    remainder == 1
+}
+
+/// BIC по маске XSD: `[A-Z]{6}[A-Z2-9][A-NP-Z0-9]([A-Z0-9]{3})?`.
+pub fn is_valid_bic(bic: &str) -> bool {
+    let bytes = bic.as_bytes();
+    let upper_or_digit = |b: &u8| b.is_ascii_uppercase() || b.is_ascii_digit();
+    (bytes.len() == 8 || bytes.len() == 11)
+        && bytes[..6].iter().all(u8::is_ascii_uppercase)
+        && (bytes[6].is_ascii_uppercase() || (b'2'..=b'9').contains(&bytes[6]))
+        && upper_or_digit(&bytes[7])
+        && bytes[7] != b'O'
+        && bytes[8..].iter().all(upper_or_digit)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn iban_and_bic_checks() {
+        assert!(is_valid_iban(&normalize_iban("de89 3704 0044 0532 0130 00")));
+        assert!(is_valid_iban("GB82WEST12345698765432"));
+        assert!(is_valid_iban("BE68539007547034"));
+        assert!(!is_valid_iban("DE88370400440532013000"), "контрольная сумма");
+        assert!(!is_valid_iban("DE8937040044053201300"), "длина для DE");
+        assert!(!is_valid_iban("40702810900000000001"));
+
+        assert!(is_valid_bic("COBADEFF"));
+        assert!(is_valid_bic("GKCCBEBB"));
+        assert!(is_valid_bic("DEUTDEFF500"));
+        assert!(!is_valid_bic("COBADEF"));
+        assert!(!is_valid_bic("COBADE1F"));
+        assert!(!is_valid_bic("COBADEFO"));
+        assert!(!is_valid_bic("cobadeff"));
+    }
+}
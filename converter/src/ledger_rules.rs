@@ -0,0 +1,213 @@
+//! Счета для журналов ledger/hledger и beancount.
+//!
+//! Профиль описывается в TOML:
+//!
+//! ```toml
+//! default_account = "Assets:Bank"
+//! opening_account = "Equity:Opening-Balances"
+//!
+//! [accounts]
+//! "DE89370400440532013000" = "Assets:Bank:Checking"
+//!
+//! [[rules]]
+//! pattern = "(?i)miete|rent"
+//! account = "Expenses:Rent"
+//!
+//! [[rules]]
+//! pattern = "(?i)salary"
+//! account = "Income:Salary"
+//! ```
+//!
+//! Второй счет проводки выбирается так: первое правило, шаблон которого найден
+//! в контрагенте, описании или назначении платежа; затем категория транзакции
+//! ([`Transaction::category`]) под `Expenses`/`Income`; затем
+//! [`LedgerRules::expense_account`] или [`LedgerRules::income_account`] по знаку суммы.
+
+use std::{collections::BTreeMap, path::Path};
+
+use regex::Regex;
+use serde::Deserialize;
+
+use crate::{error::ConvertError, model::Transaction, money::Money};
+
+/// Правило выбора второго счета проводки.
+#[derive(Debug, Clone, PartialEq, Deserialize)]
+#[serde(deny_unknown_fields)]
+pub struct CounterRule {
+    /// Регулярное выражение ([`regex`]) по контрагенту, описанию и назначению платежа.
+    pub pattern: String,
+    /// Счет журнала, например `Expenses:Rent`.
+    pub account: String,
+}
+
+/// Профиль счетов журнала.
+#[derive(Debug, Clone, PartialEq, Deserialize)]
+#[serde(default, deny_unknown_fields)]
+pub struct LedgerRules {
+    /// Счет актива для номеров без записи в [`LedgerRules::accounts`]:
+    /// к нему добавляется номер счета, `Assets:Bank:DE89370400440532013000`.
+    pub default_account: String,
+    /// Счет актива по номеру счета выписки ([`Transaction::account`]).
+    pub accounts: BTreeMap<String, String>,
+    /// Правила для второго счета в порядке проверки.
+    pub rules: Vec<CounterRule>,
+    /// Второй счет для списаний, не попавших под правила.
+    pub expense_account: String,
+    /// Второй счет для зачислений, не попавших под правила.
+    pub income_account: String,
+    /// Второй счет проводки входящего сальдо выписки.
+    pub opening_account: String,
+}
+
+impl Default for LedgerRules {
+    fn default() -> Self {
+        Self {
+            default_account: "Assets:Bank".to_string(),
+            accounts: BTreeMap::new(),
+            rules: Vec::new(),
+            expense_account: "Expenses:Unknown".to_string(),
+            income_account: "Income:Unknown".to_string(),
+            opening_account: "Equity:Opening-Balances".to_string(),
+        }
+    }
+}
+
+/// Компонент имени счета: буквы, цифры и `-`, первая буква заглавная (требование beancount).
+fn component(text: &str) -> String {
+    let cleaned: String = text
+        .trim()
+        .chars()
+        .map(|c| if c.is_alphanumeric() || c == '-' { c } else { '-' })
+        .collect();
+    let cleaned = cleaned.trim_matches('-');
+    let mut chars = cleaned.chars();
+    match chars.next() {
+        Some(first) => first.to_uppercase().chain(chars).collect(),
+        None => String::new(),
+    }
+}
+
+/// Имя счета `root:...` из произвольного текста: категории QIF, номера счета.
+pub(crate) fn account_name(root: &str, path: &str) -> String {
+    path.split(':')
+        .map(component)
+        .filter(|c| !c.is_empty())
+        .fold(root.to_string(), |name, c| format!("{}:{}", name, c))
+}
+
+/// Правила профиля с разобранными шаблонами.
+pub(crate) struct CompiledRules<'a> {
+    rules: &'a LedgerRules,
+    patterns: Vec<(Regex, &'a str)>,
+}
+
+impl LedgerRules {
+    /// Читает профиль из TOML-строки; шаблоны правил проверяются сразу.
+    pub fn from_toml(input: &str) -> Result<Self, ConvertError> {
+        let rules: LedgerRules = toml::from_str(input).map_err(|e| ConvertError::Profile(e.to_string()))?;
+        rules.compile()?;
+        Ok(rules)
+    }
+
+    /// Читает профиль из TOML-файла.
+    pub fn load(path: impl AsRef<Path>) -> Result<Self, ConvertError> {
+        Self::from_toml(&std::fs::read_to_string(path)?)
+    }
+
+    pub(crate) fn compile(&self) -> Result<CompiledRules<'_>, ConvertError> {
+        let patterns = self
+            .rules
+            .iter()
+            .map(|rule| {
+                Regex::new(&rule.pattern)
+                    .map(|regex| (regex, rule.account.as_str()))
+                    .map_err(|e| ConvertError::Profile(format!("правило \"{}\": {}", rule.pattern, e)))
+            })
+            .collect::<Result<_, _>>()?;
+        Ok(CompiledRules { rules: self, patterns })
+    }
+}
+
+impl CompiledRules<'_> {
+    /// Второй счет для входящего сальдо.
+    pub(crate) fn opening_account(&self) -> &str {
+        &self.rules.opening_account
+    }
+
+    /// Счет актива для номера счета выписки.
+    pub(crate) fn asset_account(&self, account: &str) -> String {
+        match self.rules.accounts.get(account.trim()) {
+            Some(name) => name.clone(),
+            None => account_name(&self.rules.default_account, account),
+        }
+    }
+
+    /// Второй счет для суммы `amount` транзакции `tx` с категорией `category`.
+    pub(crate) fn counter_account(&self, tx: &Transaction, category: Option<&str>, amount: Money) -> String {
+        let counterparty = tx.counterparty.as_ref().and_then(|c| c.name.as_deref());
+        let texts = [counterparty, Some(tx.description.as_str()), tx.remittance_information.as_deref()];
+        let matched = self
+            .patterns
+            .iter()
+            .find(|(regex, _)| texts.iter().flatten().any(|text| regex.is_match(text)));
+        if let Some((_, account)) = matched {
+            return account.to_string();
+        }
+        // Сумма со стороны банка: списание — расход, зачисление — доход.
+        let (root, fallback) = if amount.is_negative() {
+            ("Expenses", &self.rules.expense_account)
+        } else {
+            ("Income", &self.rules.income_account)
+        };
+        match category.map(|c| account_name(root, c)) {
+            Some(name) if name != root => name,
+            _ => fallback.clone(),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::model::Counterparty;
+
+    #[test]
+    fn accounts_follow_rules_then_category_then_sign() {
+        let rules = LedgerRules::from_toml(
+            r#"
+            [accounts]
+            "DE89" = "Assets:Checking"
+
+            [[rules]]
+            pattern = "(?i)landlord"
+            account = "Expenses:Rent"
+            "#,
+        )
+        .unwrap();
+        let compiled = rules.compile().unwrap();
+        assert_eq!(compiled.asset_account("DE89"), "Assets:Checking");
+        assert_eq!(compiled.asset_account("40702 810/01"), "Assets:Bank:40702-810-01");
+        assert_eq!(compiled.asset_account(""), "Assets:Bank");
+
+        let mut tx = Transaction {
+            amount: Money::new(-100, 2),
+            counterparty: Some(Counterparty {
+                name: Some("LANDLORD Ltd".to_string()),
+                ..Default::default()
+            }),
+            ..Default::default()
+        };
+        assert_eq!(compiled.counter_account(&tx, Some("Food"), tx.amount), "Expenses:Rent");
+        tx.counterparty = None;
+        assert_eq!(
+            compiled.counter_account(&tx, Some("food:dining out"), tx.amount),
+            "Expenses:Food:Dining-out"
+        );
+        assert_eq!(compiled.counter_account(&tx, None, tx.amount), "Expenses:Unknown");
+        assert_eq!(compiled.counter_account(&tx, Some(" "), tx.amount.abs()), "Income:Unknown");
+
+        assert!(matches!(LedgerRules::from_toml("unknown = 1"), Err(ConvertError::Profile(_))));
+        let broken = "[[rules]]\npattern = \"(\"\naccount = \"Expenses:X\"";
+        assert!(matches!(LedgerRules::from_toml(broken), Err(ConvertError::Profile(_))));
+    }
+}
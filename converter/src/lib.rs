@@ -2,20 +2,53 @@
 //!
 //! Поддерживаемые форматы:
 //! - CSV
-//! - MT940 (SWIFT)
-//! - CAMT.053 (ISO 20022 XML)
+//! - MT940, MT942, MT950 (SWIFT)
+//! - MT900/MT910 (SWIFT, только чтение)
+//! - CAMT.053, CAMT.052, CAMT.054 (ISO 20022 XML)
+//! - 1CClientBankExchange (обмен с 1С:Предприятие)
+//! - OFX 1.x (SGML) и 2.x (XML)
+//! - QIF (Quicken Interchange Format)
+//! - BAI2 (Cash Management Balance Reporting)
+//! - CODA (Belgian Coded Statement of Account)
+//! - JSON и JSON Lines (NDJSON) по версионированной схеме, см. [`json_schema`]
+//! - XLSX (Excel), колонки по [`CsvMapping`]
+//! - Apache Parquet (только запись), колонки Arrow по [`transaction_schema`]
+//! - ledger/hledger и beancount (только запись), счета по [`LedgerRules`]
+//! - SEPA pain.001.001.03 и .09 (только запись) с проверкой IBAN и BIC
 
 #![warn(missing_docs)]
+mod arrow;
+mod csv_mapping;
+mod date;
 pub mod error;
 mod from_parser;
+mod iban;
+mod ledger_rules;
 mod model;
+mod money;
+mod narrative;
+mod options;
+mod schema;
 mod to_format;
 
+pub use arrow::{AMOUNT_PRECISION, AMOUNT_SCALE, ARROW_SCHEMA_VERSION, record_batch, transaction_schema};
+pub use csv_mapping::{CsvColumn, CsvMapping};
 pub use error::ConvertError;
 pub use from_parser::FromParser;
+pub use iban::{is_valid_bic, is_valid_iban, normalize_iban};
+pub use ledger_rules::{CounterRule, LedgerRules};
+pub use model::{Balance, BalanceKind, Counterparty, Split, Statement, Summary, Transaction};
+pub use money::{MAX_EXPONENT, Money, currency_exponent};
+pub use narrative::{Narrative, NarrativeDialect, SlashCodeDialect, SubfieldDialect};
+pub use options::{CamtVersion, ConvertOptions, OfxVersion, PainVersion, QifDateFormat, SepaDebtor};
+pub use parser::{CsvDialect, CsvQuoting, Encoding};
+pub use schema::{JsonDocument, JsonLine, SCHEMA_VERSION, json_lines_schema, json_schema};
 pub use to_format::ToFormat;
 
-use parser::{Camt053Parser, CsvParser, Mt940Parser, Parser};
+use parser::{
+    Bai2Parser, Camt052Parser, Camt053Parser, Camt054Parser, ClientBank1CParser, CodaParser, CsvParser, JsonLinesParser, JsonParser,
+    Mt900Parser, Mt910Parser, Mt940Parser, Mt942Parser, Mt950Parser, OfxParser, Parser, QifParser, XlsxParser,
+};
 use std::{io::Read, io::Write};
 
 /// Контейнер для поддерживаемых форматов.
@@ -25,8 +58,44 @@ pub enum Format {
     Csv,
     /// MT940 (SWIFT).
     Mt940,
+    /// MT942 (SWIFT), промежуточный отчет.
+    Mt942,
+    /// MT950 (SWIFT), выписка корреспондентского счета без `:86:`.
+    Mt950,
+    /// MT900 (SWIFT), подтверждение дебета. Только чтение.
+    Mt900,
+    /// MT910 (SWIFT), подтверждение кредита. Только чтение.
+    Mt910,
     /// CAMT.053 (ISO 20022 XML).
     Camt053,
+    /// CAMT.052 (ISO 20022 XML), внутридневной отчет.
+    Camt052,
+    /// CAMT.054 (ISO 20022 XML), уведомления о дебете и кредите.
+    Camt054,
+    /// 1CClientBankExchange (обмен с 1С:Предприятие).
+    ClientBank1C,
+    /// OFX / QFX (Quicken, GnuCash).
+    Ofx,
+    /// QIF (Quicken Interchange Format).
+    Qif,
+    /// BAI2 (Cash Management Balance Reporting).
+    Bai2,
+    /// CODA (Belgian Coded Statement of Account).
+    Coda,
+    /// JSON: выписки целиком, схема [`JsonDocument`].
+    Json,
+    /// JSON Lines (NDJSON): по транзакции на строку, схема [`JsonLine`].
+    JsonLines,
+    /// Excel `.xlsx`: лист [`ConvertOptions::xlsx_sheet`], колонки по [`ConvertOptions::csv_mapping`].
+    Xlsx,
+    /// Apache Parquet: колонки [`transaction_schema`] для DuckDB и Spark. Только запись.
+    Parquet,
+    /// Журнал ledger / hledger. Только запись.
+    Ledger,
+    /// Beancount. Только запись.
+    Beancount,
+    /// Платежные поручения SEPA pain.001 (ISO 20022 XML). Только запись.
+    Pain001,
 }
 
 impl From<&str> for Format {
@@ -34,7 +103,25 @@ impl From<&str> for Format {
         match s.to_lowercase().as_str() {
             "csv" => Format::Csv,
             "mt940" => Format::Mt940,
+            "mt942" => Format::Mt942,
+            "mt950" => Format::Mt950,
+            "mt900" => Format::Mt900,
+            "mt910" => Format::Mt910,
             "camt053" => Format::Camt053,
+            "camt052" => Format::Camt052,
+            "camt054" => Format::Camt054,
+            "1c" | "clientbank1c" => Format::ClientBank1C,
+            "ofx" | "qfx" => Format::Ofx,
+            "qif" => Format::Qif,
+            "bai2" | "bai" => Format::Bai2,
+            "coda" | "cod" => Format::Coda,
+            "json" => Format::Json,
+            "jsonl" | "ndjson" | "jsonlines" => Format::JsonLines,
+            "xlsx" | "excel" => Format::Xlsx,
+            "parquet" => Format::Parquet,
+            "ledger" | "hledger" | "journal" => Format::Ledger,
+            "beancount" | "bean" => Format::Beancount,
+            "pain001" | "pain.001" | "sepa" => Format::Pain001,
             _ => Format::Csv,
         }
     }
@@ -43,27 +130,118 @@ impl From<&str> for Format {
 /// Конверте в различные форматы
 /// Поддерживаемые форматы: [`Format`]
 pub fn convert<R: Read, W: Write>(input: R, from: &Format, to: &Format, output: W) -> Result<(), ConvertError> {
-    let transactions = match from {
+    convert_with_options(input, from, to, output, &ConvertOptions::default())
+}
+
+/// То же, что [`convert`], но с явными настройками [`ConvertOptions`].
+pub fn convert_with_options<R: Read, W: Write>(
+    input: R,
+    from: &Format,
+    to: &Format,
+    output: W,
+    options: &ConvertOptions,
+) -> Result<(), ConvertError> {
+    let statements = match from {
         Format::Csv => {
-            let parser = CsvParser::parse(input)?;
-            parser.to_transactions()
+            let parser = CsvParser::parse_with(input, &options.csv_dialect)?;
+            parser.to_statements(options)?
         }
         Format::Mt940 => {
             let parser = Mt940Parser::parse(input)?;
-            parser.to_transactions()
+            parser.to_statements(options)?
+        }
+        Format::Mt942 => {
+            let parser = Mt942Parser::parse(input)?;
+            parser.to_statements(options)?
+        }
+        Format::Mt950 => {
+            let parser = Mt950Parser::parse(input)?;
+            parser.to_statements(options)?
+        }
+        Format::Mt900 => {
+            let parser = Mt900Parser::parse(input)?;
+            parser.to_statements(options)?
+        }
+        Format::Mt910 => {
+            let parser = Mt910Parser::parse(input)?;
+            parser.to_statements(options)?
         }
         Format::Camt053 => {
             let parser = Camt053Parser::parse(input)?;
-            parser.to_transactions()
+            parser.to_statements(options)?
+        }
+        Format::Camt052 => {
+            let parser = Camt052Parser::parse(input)?;
+            parser.to_statements(options)?
+        }
+        Format::Camt054 => {
+            let parser = Camt054Parser::parse(input)?;
+            parser.to_statements(options)?
+        }
+        Format::ClientBank1C => {
+            let parser = ClientBank1CParser::parse(input)?;
+            parser.to_statements(options)?
+        }
+        Format::Ofx => {
+            let parser = OfxParser::parse(input)?;
+            parser.to_statements(options)?
+        }
+        Format::Qif => {
+            let parser = QifParser::parse(input)?;
+            parser.to_statements(options)?
+        }
+        Format::Bai2 => {
+            let parser = Bai2Parser::parse(input)?;
+            parser.to_statements(options)?
+        }
+        Format::Coda => {
+            let parser = CodaParser::parse(input)?;
+            parser.to_statements(options)?
+        }
+        Format::Json => {
+            let parser = JsonParser::parse(input)?;
+            parser.to_statements(options)?
+        }
+        Format::JsonLines => {
+            let parser = JsonLinesParser::parse(input)?;
+            parser.to_statements(options)?
+        }
+        Format::Xlsx => {
+            let parser = XlsxParser::parse_sheet(input, options.xlsx_sheet.as_deref())?;
+            parser.to_statements(options)?
+        }
+        Format::Parquet | Format::Ledger | Format::Beancount | Format::Pain001 => {
+            return Err(ConvertError::Unsupported {
+                from: format!("{:?}", from),
+                to: format!("{:?}", to),
+            });
         }
     };
-    let _ = match to {
-        Format::Csv => to_format::CsvFormat::from_transactions(&transactions, output),
-        Format::Mt940 => to_format::Mt940Format::from_transactions(&transactions, output),
-        Format::Camt053 => to_format::Camt053Format::from_transactions(&transactions, output),
-    };
-
-    Ok(())
+    match to {
+        Format::Csv => to_format::CsvFormat::from_statements(&statements, options, output),
+        Format::Mt940 => to_format::Mt940Format::from_statements(&statements, options, output),
+        Format::Mt942 => to_format::Mt942Format::from_statements(&statements, options, output),
+        Format::Mt950 => to_format::Mt950Format::from_statements(&statements, options, output),
+        Format::Mt900 | Format::Mt910 => Err(ConvertError::Unsupported {
+            from: format!("{:?}", from),
+            to: format!("{:?}", to),
+        }),
+        Format::Camt053 => to_format::Camt053Format::from_statements(&statements, options, output),
+        Format::Camt052 => to_format::Camt052Format::from_statements(&statements, options, output),
+        Format::Camt054 => to_format::Camt054Format::from_statements(&statements, options, output),
+        Format::ClientBank1C => to_format::ClientBank1CFormat::from_statements(&statements, options, output),
+        Format::Ofx => to_format::OfxFormat::from_statements(&statements, options, output),
+        Format::Qif => to_format::QifFormat::from_statements(&statements, options, output),
+        Format::Bai2 => to_format::Bai2Format::from_statements(&statements, options, output),
+        Format::Coda => to_format::CodaFormat::from_statements(&statements, options, output),
+        Format::Json => to_format::JsonFormat::from_statements(&statements, options, output),
+        Format::JsonLines => to_format::JsonLinesFormat::from_statements(&statements, options, output),
+        Format::Xlsx => to_format::XlsxFormat::from_statements(&statements, options, output),
+        Format::Parquet => to_format::ParquetFormat::from_statements(&statements, options, output),
+        Format::Ledger => to_format::LedgerFormat::from_statements(&statements, options, output),
+        Format::Beancount => to_format::BeancountFormat::from_statements(&statements, options, output),
+        Format::Pain001 => to_format::Pain001Format::from_statements(&statements, options, output),
+    }
 }
 
 #[cfg(test)]
@@ -81,7 +259,7 @@ mod tests {
 
     #[test]
     fn test_csv_to_mt940() {
-        let csv = "ref,acc,comment\nREF123,ACC456,Buy bread";
+        let csv = "ref,acc,comment,date\nREF123,ACC456,Buy bread,2023-01-05";
         let mut buffer = Vec::new();
 
         convert(csv.as_bytes(), &Format::Csv, &Format::Mt940, &mut buffer).unwrap();
@@ -100,4 +278,321 @@ mod tests {
         assert!(output.contains("REF999"));
         assert!(output.contains("ACC789"));
     }
+
+    #[test]
+    fn test_mt940_to_mt940_keeps_exact_amounts() {
+        let mt940 = ":20:REF1\n:25:ACC1\n:60F:C230101EUR0,00\n:61:230101CR100,10NMSCNONREF\n:61:230102DR0,07NMSCNONREF\n";
+        let mut buffer = Vec::new();
+        convert(mt940.as_bytes(), &Format::Mt940, &Format::Mt940, &mut buffer).unwrap();
+        let output = String::from_utf8(buffer).unwrap();
+        assert!(output.contains(":61:230101CR100,10NMSCNONREF"), "{}", output);
+        assert!(output.contains(":61:230102DR0,07NMSCNONREF"), "{}", output);
+    }
+
+    #[test]
+    fn test_mt940_to_mt940_keeps_statement_line_subfields() {
+        let lines = [
+            ":61:2301020103RCR100,00NTRFINV-2023-001//BANK123456",
+            "REVERSED PAYMENT",
+            ":61:230105D5,25NCHGNONREF",
+            ":61:230106RD7,00FMSCCUST//B2",
+        ];
+        let mt940 = format!(":20:STMT\n:25:ACC\n:60F:C230101EUR0,00\n{}\n", lines.join("\n"));
+        let mut buffer = Vec::new();
+        convert(mt940.as_bytes(), &Format::Mt940, &Format::Mt940, &mut buffer).unwrap();
+        let output = String::from_utf8(buffer).unwrap();
+        assert!(output.contains(&lines.join("\n")), "{}", output);
+    }
+
+    #[test]
+    fn test_mt940_to_mt940_is_single_statement() {
+        let mt940 = ":20:STMT\n:25:ACC\n:28C:7/1\n:60F:C230101EUR10,00\n:61:230101CR1,00\n:86:a\n:61:230101DR2,50\n:86:b\n:62F:C230101EUR8,50\n";
+        let mut buffer = Vec::new();
+        convert(mt940.as_bytes(), &Format::Mt940, &Format::Mt940, &mut buffer).unwrap();
+        let output = String::from_utf8(buffer).unwrap();
+        assert_eq!(output.matches(":20:").count(), 1, "{}", output);
+        assert!(output.contains(":28C:7/1"));
+        assert!(output.contains(":60F:C230101EUR10,00"));
+        assert!(output.contains(":62F:C230101EUR8,50"));
+    }
+
+    #[test]
+    fn test_mt940_to_mt940_keeps_multiline_narrative() {
+        let narrative: Vec<String> = (1..=6).map(|n| format!("{}{}", n, "N".repeat(64))).collect();
+        assert!(narrative.iter().all(|line| line.chars().count() == 65));
+        let mt940 = format!(
+            ":20:STMT\n:25:ACC\n:61:230101CR1,00NMSCNONREF\nSUPPLEMENTARY\n:86:{}\n:62F:C230101EUR1,00\n",
+            narrative.join("\n")
+        );
+        let mut buffer = Vec::new();
+        convert(mt940.as_bytes(), &Format::Mt940, &Format::Mt940, &mut buffer).unwrap();
+        let output = String::from_utf8(buffer).unwrap();
+        assert!(output.contains(&format!(":86:{}\n", narrative.join("\n"))), "{}", output);
+
+        let mut buffer = Vec::new();
+        convert(mt940.as_bytes(), &Format::Mt940, &Format::Csv, &mut buffer).unwrap();
+        let output = String::from_utf8(buffer).unwrap();
+        assert!(output.contains(&narrative[5]), "{}", output);
+    }
+
+    #[test]
+    fn test_csv_dialect_round_trip() {
+        let options = ConvertOptions {
+            csv_dialect: CsvDialect {
+                delimiter: Some(b';'),
+                decimal_comma: true,
+                encoding: CsvDialect::encoding_for_label("windows-1251"),
+                ..Default::default()
+            },
+            csv_mapping: CsvMapping {
+                amount: Some(2.into()),
+                currency: Some(3.into()),
+                value_date: Some(4.into()),
+                description: Some(5.into()),
+                ..Default::default()
+            },
+            ..Default::default()
+        };
+        let mt940 = ":20:STMT\n:25:ACC\n:60F:C230101RUB0,00\n:61:230102D1500,50NMSCNONREF\n:86:Аренда; офис\n";
+        let mut csv = Vec::new();
+        convert_with_options(mt940.as_bytes(), &Format::Mt940, &Format::Csv, &mut csv, &options).unwrap();
+        let (expected, _, _) = parser::Encoding::for_label(b"windows-1251")
+            .unwrap()
+            .encode("reference;account;amount;currency;date;description\nSTMT;ACC;-1500,50;RUB;2023-01-02;\"Аренда; офис\"\n");
+        assert_eq!(csv, expected.into_owned());
+
+        // Чтение с автоопределением разделителя и кодировки.
+        let read_options = ConvertOptions {
+            csv_dialect: CsvDialect {
+                decimal_comma: true,
+                ..Default::default()
+            },
+            ..options.clone()
+        };
+        let mut mt940_again = Vec::new();
+        convert_with_options(&csv[..], &Format::Csv, &Format::Mt940, &mut mt940_again, &read_options).unwrap();
+        let output = String::from_utf8(mt940_again).unwrap();
+        assert!(output.contains(":61:230102D1500,50NMSCNONREF"), "{}", output);
+        assert!(output.contains(":86:Аренда; офис"), "{}", output);
+    }
+
+    #[test]
+    fn test_csv_to_mt940_computes_closing_balance() {
+        let csv = "ref,acc,comment,date\nREF1,ACC1,first,2023-01-05\nREF1,ACC1,second,2023-01-06";
+        let mut buffer = Vec::new();
+        convert(csv.as_bytes(), &Format::Csv, &Format::Mt940, &mut buffer).unwrap();
+        let output = String::from_utf8(buffer).unwrap();
+        assert_eq!(output.matches(":20:").count(), 1, "{}", output);
+        assert!(output.contains(":60F:C230105XXX0,00"), "{}", output);
+        assert!(output.contains(":62F:C230106XXX0,00"), "{}", output);
+    }
+
+    #[test]
+    fn test_camt054_and_camt052_to_mt942() {
+        let notification = r#"<Document xmlns="urn:iso:std:iso:20022:tech:xsd:camt.054.001.08">
+<BkToCstmrDbtCdtNtfctn>
+  <GrpHdr><MsgId>N1</MsgId><CreDtTm>2023-01-02T10:30:00</CreDtTm></GrpHdr>
+  <Ntfctn>
+    <Id>NTF-1</Id>
+    <Acct><Id><IBAN>DE89370400440532013000</IBAN></Id><Ccy>EUR</Ccy></Acct>
+    <Ntry><Amt Ccy="EUR">250.00</Amt><CdtDbtInd>CRDT</CdtDbtInd><Sts><Cd>BOOK</Cd></Sts>
+      <BookgDt><Dt>2023-01-02</Dt></BookgDt><ValDt><Dt>2023-01-02</Dt></ValDt>
+      <AcctSvcrRef>B1</AcctSvcrRef><AddtlNtryInf>Incoming</AddtlNtryInf></Ntry>
+    <Ntry><Amt Ccy="EUR">10.50</Amt><CdtDbtInd>DBIT</CdtDbtInd><Sts><Cd>BOOK</Cd></Sts>
+      <BookgDt><Dt>2023-01-02</Dt></BookgDt><ValDt><Dt>2023-01-02</Dt></ValDt><AddtlNtryInf>Fee</AddtlNtryInf></Ntry>
+  </Ntfctn>
+</BkToCstmrDbtCdtNtfctn>
+</Document>"#;
+        let mut buffer = Vec::new();
+        convert(notification.as_bytes(), &Format::Camt054, &Format::Mt942, &mut buffer).unwrap();
+        let output = String::from_utf8(buffer).unwrap();
+        for line in [
+            ":20:NTF-1",
+            ":25:DE89370400440532013000",
+            ":34F:EUR0,00",
+            ":13D:2301021030+0000",
+            ":61:2301020102C250,00NMSCNONREF//B1",
+            ":86:Incoming",
+            ":61:2301020102D10,50NMSCNONREF",
+            ":90D:1EUR10,50",
+            ":90C:1EUR250,00",
+        ] {
+            assert!(output.contains(line), "нет {} в {}", line, output);
+        }
+        assert!(!output.contains(":60F:"), "{}", output);
+
+        // camt.052 через запись и повторное чтение.
+        let mut report = Vec::new();
+        convert(notification.as_bytes(), &Format::Camt054, &Format::Camt052, &mut report).unwrap();
+        let mut buffer = Vec::new();
+        convert(&report[..], &Format::Camt052, &Format::Mt942, &mut buffer).unwrap();
+        assert_eq!(String::from_utf8(buffer).unwrap(), output);
+    }
+
+    #[test]
+    fn test_mt942_round_trip_and_to_camt052() {
+        let mt942 = ":20:INTRA1\n:25:DE89370400440532013000\n:28C:5/1\n:34F:EURD100,00\n:34F:EURC50,00\n\
+                     :13D:2301021030+0000\n:61:2301020102D150,00NTRFNONREF//B1\n:86:Rent\n:90D:1EUR150,00\n:90C:0EUR0,00\n-\n";
+        let mut buffer = Vec::new();
+        convert(mt942.as_bytes(), &Format::Mt942, &Format::Mt942, &mut buffer).unwrap();
+        assert_eq!(String::from_utf8(buffer).unwrap(), mt942);
+
+        let mut camt = Vec::new();
+        convert(mt942.as_bytes(), &Format::Mt942, &Format::Camt052, &mut camt).unwrap();
+        let output = String::from_utf8(camt.clone()).unwrap();
+        assert!(output.contains("<BkToCstmrAcctRpt>"), "{}", output);
+        assert!(output.contains("<CreDtTm>2023-01-02T10:30:00</CreDtTm>"), "{}", output);
+        assert!(!output.contains("<Bal>"), "{}", output);
+
+        // Пороги и номер страницы в camt.052 не передаются: обратно пишется нулевой `:34F:`.
+        let mut buffer = Vec::new();
+        convert(&camt[..], &Format::Camt052, &Format::Mt942, &mut buffer).unwrap();
+        let expected = mt942
+            .replace(":34F:EURD100,00\n:34F:EURC50,00", ":34F:EUR0,00")
+            .replace(":28C:5/1", ":28C:5");
+        assert_eq!(String::from_utf8(buffer).unwrap(), expected);
+    }
+
+    #[test]
+    fn test_mt950_and_confirmations_to_camt() {
+        let mt950 = ":20:CORR1\n:25:ACC1\n:28C:3/1\n:60F:C230101USD100,00\n:61:230102D40,00NTRFREF1//B1\n:62F:C230102USD60,00\n";
+        let mut buffer = Vec::new();
+        convert(mt950.as_bytes(), &Format::Mt950, &Format::Mt950, &mut buffer).unwrap();
+        assert_eq!(String::from_utf8(buffer).unwrap(), format!("{}-\n", mt950));
+
+        let mut buffer = Vec::new();
+        convert(mt950.as_bytes(), &Format::Mt950, &Format::Camt053, &mut buffer).unwrap();
+        let output = String::from_utf8(buffer).unwrap();
+        assert!(output.contains("<AcctSvcrRef>B1</AcctSvcrRef>"), "{}", output);
+        assert!(output.contains("<Amt Ccy=\"USD\">60.00</Amt>"), "{}", output);
+
+        let mt910 = ":20:C11126C9224\n:21:494936/DEV\n:25:6-9412771\n:32A:230103EUR1000,00\n:50K:JOHN SMITH\n";
+        let mut buffer = Vec::new();
+        convert(mt910.as_bytes(), &Format::Mt910, &Format::Camt054, &mut buffer).unwrap();
+        let output = String::from_utf8(buffer).unwrap();
+        assert!(output.contains("<CdtDbtInd>CRDT</CdtDbtInd>"), "{}", output);
+        assert!(output.contains("<EndToEndId>494936/DEV</EndToEndId>"), "{}", output);
+        assert!(output.contains("<Nm>JOHN SMITH</Nm>"), "{}", output);
+
+        let mut buffer = Vec::new();
+        let result = convert(mt910.as_bytes(), &Format::Mt910, &Format::Mt900, &mut buffer);
+        assert!(matches!(result, Err(ConvertError::Unsupported { .. })));
+    }
+
+    #[test]
+    fn test_coda_to_camt053_and_mt940() {
+        let line = |text: String| format!("{:<128}", text);
+        let coda = [
+            line("0000005012372505        CODA000001ACME NV                   GEBABEBB   00000000000 00000".to_string()),
+            line(format!("12001{:<34}EUR0{:015}311222", "BE68539007547034", 1_000_000)),
+            line(format!(
+                "2100010000{:<21}1{:015}030123001010001101123456789012{:<38}04012300101 0",
+                "B1", 50_000, ""
+            )),
+            line(format!("2200010000{:<53}{:<35}GKCCBEBB{:19}1 0", "", "E2E-1", "")),
+            line(format!("2300010000{:<34}EUR{:<35}", "BE71096123456769", "Supplier BVBA")),
+            line(format!("8001{:<34}EUR0{:015}040123", "BE68539007547034", 950_000)),
+            line(format!("9{:15}{:06}{:015}{:015}", "", 5, 50_000, 0)),
+        ]
+        .join("\r\n");
+
+        let mut buffer = Vec::new();
+        convert(coda.as_bytes(), &Format::Coda, &Format::Camt053, &mut buffer).unwrap();
+        let output = String::from_utf8(buffer).unwrap();
+        assert!(output.contains("<IBAN>BE71096123456769</IBAN>"), "{}", output);
+        assert!(output.contains("<BIC>GKCCBEBB</BIC>"), "{}", output);
+        assert!(output.contains("+++123/4567/89012+++"), "{}", output);
+        assert!(output.contains("<Amt Ccy=\"EUR\">950.00</Amt>"), "{}", output);
+
+        let mut buffer = Vec::new();
+        convert(coda.as_bytes(), &Format::Coda, &Format::Mt940, &mut buffer).unwrap();
+        let output = String::from_utf8(buffer).unwrap();
+        assert!(output.contains(":25:BE68539007547034"), "{}", output);
+        assert!(output.contains(":60F:C221231EUR1000,00"), "{}", output);
+        assert!(output.contains(":61:2301030104D50,00"), "{}", output);
+        assert!(output.contains(":62F:C230104EUR950,00"), "{}", output);
+    }
+
+    #[test]
+    fn test_json_and_json_lines_round_trip() {
+        let mt940 =
+            ":20:STMT\n:25:ACC\n:28C:7/1\n:60F:C230101EUR10,00\n:61:2301020102D2,50NTRFREF1//B1\n:86:Rent\n:62F:C230102EUR7,50\n";
+        let mut json = Vec::new();
+        convert(mt940.as_bytes(), &Format::Mt940, &Format::Json, &mut json).unwrap();
+        let document: serde_json::Value = serde_json::from_slice(&json).unwrap();
+        assert_eq!(document["schema_version"], SCHEMA_VERSION);
+        let statement = &document["statements"][0];
+        assert_eq!(statement["opening_balance"]["kind"], "opening");
+        assert_eq!(statement["entries"][0]["amount"], "-2.50");
+        assert_eq!(statement["entries"][0]["value_date"], "2023-01-02");
+
+        let mut buffer = Vec::new();
+        convert(&json[..], &Format::Json, &Format::Mt940, &mut buffer).unwrap();
+        assert_eq!(String::from_utf8(buffer).unwrap(), format!("{}-\n", mt940));
+
+        let mut lines = Vec::new();
+        convert(mt940.as_bytes(), &Format::Mt940, &Format::JsonLines, &mut lines).unwrap();
+        let text = String::from_utf8(lines.clone()).unwrap();
+        assert_eq!(text.lines().count(), 1, "{}", text);
+        assert!(text.starts_with("{\"schema_version\":1,\"reference\":\"STMT\""), "{}", text);
+        let mut csv = Vec::new();
+        convert(&lines[..], &Format::JsonLines, &Format::Csv, &mut csv).unwrap();
+        assert!(String::from_utf8(csv).unwrap().contains("STMT,ACC,-2.50,EUR,2023-01-02,Rent"));
+
+        let tiny = text.replace("\"-2.50\"", "\"0.000000000000000000001\"");
+        let result = convert(tiny.as_bytes(), &Format::JsonLines, &Format::Csv, &mut Vec::new());
+        assert!(result.is_err());
+    }
+
+    #[test]
+    fn test_mt940_to_parquet() {
+        let mt940 =
+            ":20:STMT\n:25:ACC\n:28C:7/1\n:60F:C230101EUR10,00\n:61:2301020102D2,50NTRFREF1//B1\n:86:Rent\n:62F:C230102EUR7,50\n";
+        let mut parquet = Vec::new();
+        convert(mt940.as_bytes(), &Format::Mt940, &Format::Parquet, &mut parquet).unwrap();
+        assert!(parquet.starts_with(b"PAR1") && parquet.ends_with(b"PAR1"));
+
+        let result = convert(&parquet[..], &Format::Parquet, &Format::Csv, &mut Vec::new());
+        assert!(matches!(result, Err(ConvertError::Unsupported { .. })));
+    }
+
+    #[test]
+    fn test_csv_payment_list_to_pain001() {
+        let csv =
+            "Empfänger;IBAN;BIC;Betrag;Datum;Verwendungszweck\nACME Ltd;GB82 WEST 1234 5698 7654 32;;1.200,50;02.01.2023;Invoice 7\n";
+        let mut options = ConvertOptions {
+            csv_mapping: CsvMapping::from_toml(
+                r#"
+                counterparty_name = "Empfänger"
+                counterparty_account = "IBAN"
+                counterparty_bic = "BIC"
+                amount = "Betrag"
+                default_currency = "EUR"
+                value_date = "Datum"
+                date_format = "%d.%m.%Y"
+                description = "Verwendungszweck"
+                "#,
+            )
+            .unwrap(),
+            pain_version: PainVersion::V03,
+            sepa_debtor: SepaDebtor {
+                name: "Muster GmbH".to_string(),
+                iban: Some("DE89370400440532013000".to_string()),
+                bic: None,
+            },
+            ..Default::default()
+        };
+        options.csv_dialect.decimal_comma = true;
+        let mut buffer = Vec::new();
+        convert_with_options(csv.as_bytes(), &Format::Csv, &Format::Pain001, &mut buffer, &options).unwrap();
+        let xml = String::from_utf8(buffer).unwrap();
+        assert!(xml.contains("<CtrlSum>1200.50</CtrlSum>"), "{}", xml);
+        assert!(xml.contains("<IBAN>GB82WEST12345698765432</IBAN>"), "{}", xml);
+        assert!(xml.contains("<Id>NOTPROVIDED</Id>"), "{}", xml);
+        assert!(!xml.contains("<CdtrAgt>"), "{}", xml);
+
+        options.sepa_debtor.iban = Some("DE00370400440532013000".to_string());
+        let result = convert_with_options(csv.as_bytes(), &Format::Csv, &Format::Pain001, &mut Vec::new(), &options);
+        assert!(matches!(result, Err(ConvertError::InvalidAccount(_))));
+    }
 }
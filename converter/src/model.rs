@@ -1,16 +1,217 @@
+use chrono::{NaiveDate, NaiveDateTime};
+use schemars::JsonSchema;
+use serde::{Deserialize, Serialize};
+
+use crate::{
+    error::ConvertError,
+    money::{Money, currency_exponent, overflow},
+};
+
 /// Базовая структура Транзакции
-#[derive(Debug, Clone, PartialEq)]
+///
+/// Одна запись (entry) выписки: `:61:` + `:86:` в MT940, `Ntry` в CAMT.053.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize, JsonSchema)]
 pub struct Transaction {
     /// :20: в MT940
     pub reference: String,
     /// :25:
     pub account: String,
-    ///  из :61:
-    pub amount: f64,
+    ///  из :61:, дебет со знаком минус
+    pub amount: Money,
     /// USD, EUR
     pub currency: String,
-    /// YYYY-MM-DD
-    pub value_date: String,
+    /// Дата валютирования
+    pub value_date: NaiveDate,
+    /// Дата проводки (entry date в :61:, `BookgDt` в CAMT), если отличается
+    pub booking_date: Option<NaiveDate>,
+    /// Референс клиента: подполе 7 в :61:, `Refs/EndToEndId` в CAMT
+    pub customer_reference: Option<String>,
+    /// Референс банка: подполе 8 (`//...`) в :61:, `AcctSvcrRef` в CAMT
+    pub bank_reference: Option<String>,
+    /// Сторно: `RC`/`RD` в :61:, `RvslInd` в CAMT. Знак суммы — как у фактической проводки
+    /// (`RC` — дебет, `RD` — кредит).
+    #[serde(default)]
+    pub reversal: bool,
+    /// Funds code — третий символ кода валюты в :61:
+    pub funds_code: Option<char>,
+    /// Код типа операции SWIFT: `NTRF`, `NMSC`... (`BkTxCd/Prtry/Cd` в CAMT)
+    pub transaction_type: Option<String>,
+    /// Supplementary details — вторая строка :61:
+    pub supplementary_details: Option<String>,
+    /// Контрагент: из структурированного :86: или `RltdPties` в CAMT
+    pub counterparty: Option<Counterparty>,
+    /// Назначение платежа из структурированного :86: (`?20`–`?29`, `/REMI/`)
+    pub remittance_information: Option<String>,
+    /// Национальный код операции: GVC (`166` в `166?00...`) из :86:, type code `16` в BAI2
+    pub gvc: Option<String>,
+    /// Категория учета: `L` в QIF (`[Счет]` — перевод между счетами)
+    pub category: Option<String>,
+    /// Разбиение суммы по категориям: `S`/`E`/`$` в QIF
+    #[serde(default)]
+    pub splits: Vec<Split>,
     /// :86: или комментарий
     pub description: String,
 }
+
+/// Часть суммы транзакции со своей категорией.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize, JsonSchema)]
+pub struct Split {
+    /// Категория (`S` в QIF).
+    pub category: Option<String>,
+    /// Комментарий (`E` в QIF).
+    pub memo: Option<String>,
+    /// Сумма части, со знаком как у транзакции (`$` в QIF).
+    pub amount: Money,
+}
+
+/// Контрагент по операции: плательщик для кредита, получатель для дебета.
+#[derive(Debug, Clone, PartialEq, Default, Serialize, Deserialize, JsonSchema)]
+pub struct Counterparty {
+    /// Наименование (`?32`/`?33`, `/NAME/`, `Dbtr/Nm`).
+    pub name: Option<String>,
+    /// IBAN или номер счета (`?31`, `/IBAN/`, `DbtrAcct`).
+    pub account: Option<String>,
+    /// BIC банка (`?30` в SEPA-выписках, `/BIC/`, `DbtrAgt`).
+    pub bic: Option<String>,
+    /// Национальный код банка: BLZ из `?30`, БИК в 1С.
+    pub bank_code: Option<String>,
+    /// ИНН (1С).
+    pub inn: Option<String>,
+    /// КПП (1С).
+    pub kpp: Option<String>,
+}
+
+impl Counterparty {
+    /// Нет ни одного заполненного поля.
+    pub fn is_empty(&self) -> bool {
+        self == &Counterparty::default()
+    }
+}
+
+/// Вид сальдо выписки.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize, JsonSchema)]
+#[serde(rename_all = "snake_case")]
+pub enum BalanceKind {
+    /// Входящее сальдо: `:60F:`, `OPBD`.
+    Opening,
+    /// Промежуточное входящее сальдо: `:60M:`, `PRCD`.
+    InterimOpening,
+    /// Исходящее сальдо: `:62F:`, `CLBD`.
+    Closing,
+    /// Промежуточное исходящее сальдо: `:62M:`, `ITBD`.
+    InterimClosing,
+    /// Доступный остаток на конец периода: `:64:`, `CLAV`.
+    ClosingAvailable,
+    /// Доступный остаток на будущую дату: `:65:`, `FWAV`.
+    ForwardAvailable,
+}
+
+/// Сальдо выписки на дату.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize, JsonSchema)]
+pub struct Balance {
+    /// Вид сальдо.
+    pub kind: BalanceKind,
+    /// Сумма, дебетовое сальдо со знаком минус.
+    pub amount: Money,
+    /// USD, EUR
+    pub currency: String,
+    /// Дата, на которую рассчитано сальдо.
+    pub date: NaiveDate,
+}
+
+/// Итог выписки по коду операции.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize, JsonSchema)]
+pub struct Summary {
+    /// Код итога: `100`, `400`, `040` в BAI2.
+    pub type_code: String,
+    /// Сумма итога.
+    pub amount: Money,
+    /// Количество операций, если указано.
+    pub item_count: Option<u32>,
+}
+
+/// Выписка по одному счету: заголовок, сальдо и записи.
+#[derive(Debug, Clone, PartialEq, Default, Serialize, Deserialize, JsonSchema)]
+pub struct Statement {
+    /// `:20:` в MT940, `Stmt/Id` в CAMT.053.
+    pub id: String,
+    /// Номер выписки: `:28C:`, `ElctrncSeqNb`.
+    pub sequence_number: Option<String>,
+    /// `:25:`, `Acct/Id`.
+    pub account: String,
+    /// Валюта счета.
+    pub currency: String,
+    /// Входящее сальдо (`:60F:`/`:60M:`).
+    pub opening_balance: Option<Balance>,
+    /// Исходящее сальдо (`:62F:`/`:62M:`).
+    pub closing_balance: Option<Balance>,
+    /// Доступные остатки (`:64:`, `:65:`).
+    #[serde(default)]
+    pub available_balances: Vec<Balance>,
+    /// Итоги по кодам операций (`03` в BAI2: `100` — всего кредитов, `400` — дебетов).
+    #[serde(default)]
+    pub summaries: Vec<Summary>,
+    /// Порог дебетовых операций MT942 (`:34F:`): меньшие суммы в отчет не попадают.
+    pub debit_floor_limit: Option<Money>,
+    /// Порог кредитовых операций MT942; при одном `:34F:` совпадает с дебетовым.
+    pub credit_floor_limit: Option<Money>,
+    /// BIC отправителя из SWIFT-конверта (блоки 1/2).
+    pub sender_bic: Option<String>,
+    /// BIC получателя из SWIFT-конверта.
+    pub receiver_bic: Option<String>,
+    /// Время формирования выписки (`CreDtTm`, `:13D:`).
+    pub created_at: Option<NaiveDateTime>,
+    /// Записи выписки.
+    #[serde(default)]
+    pub entries: Vec<Transaction>,
+}
+
+impl Statement {
+    /// Собирает выписку из списка транзакций без сальдо.
+    ///
+    /// Идентификатор, счет и валюта берутся из первой транзакции.
+    pub fn from_entries(entries: Vec<Transaction>) -> Self {
+        let first = entries.first();
+        Statement {
+            id: first.map(|tx| tx.reference.clone()).unwrap_or_default(),
+            account: first.map(|tx| tx.account.clone()).unwrap_or_default(),
+            currency: first.map(|tx| tx.currency.clone()).unwrap_or_default(),
+            entries,
+            ..Default::default()
+        }
+    }
+
+    /// Оборот по выписке: сумма всех записей с учетом знака.
+    ///
+    /// Ошибка [`ConvertError::InvalidAmount`] при переполнении.
+    pub fn turnover(&self) -> Result<Money, ConvertError> {
+        Money::checked_sum(self.entries.iter().map(|tx| tx.amount)).ok_or_else(overflow)
+    }
+
+    /// Входящее и исходящее сальдо для записи в форматы, где они обязательны.
+    ///
+    /// Отсутствующее входящее сальдо считается нулевым на дату первой записи,
+    /// отсутствующее исходящее — вычисляется как входящее плюс оборот.
+    pub fn balances(&self) -> Result<(Balance, Balance), ConvertError> {
+        let currency = if self.currency.is_empty() { "XXX" } else { self.currency.as_str() };
+        let first_date = self.entries.first().map(|tx| tx.value_date);
+        let last_date = self.entries.iter().map(|tx| tx.value_date).max();
+
+        let opening = self.opening_balance.clone().unwrap_or_else(|| Balance {
+            kind: BalanceKind::Opening,
+            amount: Money::zero(currency_exponent(currency)),
+            currency: currency.to_string(),
+            date: first_date.or(self.created_at.map(|t| t.date())).unwrap_or_default(),
+        });
+        let closing = match self.closing_balance.clone() {
+            Some(closing) => closing,
+            None => Balance {
+                kind: BalanceKind::Closing,
+                amount: opening.amount.checked_add(self.turnover()?).ok_or_else(overflow)?,
+                currency: opening.currency.clone(),
+                date: last_date.unwrap_or(opening.date),
+            },
+        };
+        Ok((opening, closing))
+    }
+}
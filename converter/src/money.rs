@@ -0,0 +1,358 @@
+//! Денежные суммы с фиксированной точкой.
+
+use std::{borrow::Cow, cmp::Ordering, fmt};
+
+use schemars::{JsonSchema, Schema, SchemaGenerator, json_schema};
+use serde::{Deserialize, Deserializer, Serialize, Serializer, de};
+
+use crate::error::ConvertError;
+
+/// Количество знаков после запятой (minor unit) для валюты по ISO 4217.
+///
+/// Для неизвестных кодов, в том числе `XXX`, возвращает 2.
+pub fn currency_exponent(currency: &str) -> u8 {
+    match currency.trim().to_ascii_uppercase().as_str() {
+        "BIF" | "CLP" | "DJF" | "GNF" | "ISK" | "JPY" | "KMF" | "KRW" | "PYG" | "RWF" | "UGX" | "UYI" | "VND" | "VUV" | "XAF"
+        | "XOF" | "XPF" => 0,
+        "BHD" | "IQD" | "JOD" | "KWD" | "LYD" | "OMR" | "TND" => 3,
+        "CLF" | "UYW" => 4,
+        _ => 2,
+    }
+}
+
+/// Наибольший показатель степени [`Money`]: `10^18` еще помещается в `i64`.
+pub const MAX_EXPONENT: u8 = 18;
+
+/// Денежная сумма: целое число минимальных единиц и показатель степени.
+///
+/// `Money::new(10010, 2)` — это `100.10`. Вся арифметика целочисленная,
+/// поэтому при конвертации не теряется ни одной копейки. Показатель степени
+/// не больше [`MAX_EXPONENT`].
+#[derive(Debug, Clone, Copy, Default)]
+pub struct Money {
+    minor_units: i64,
+    exponent: u8,
+}
+
+impl Money {
+    /// Создает сумму из минимальных единиц (`10010`) и показателя степени (`2`).
+    ///
+    /// # Panics
+    ///
+    /// Если `exponent` больше [`MAX_EXPONENT`]. Показатель из входных данных
+    /// проверяют [`Money::parse`] и [`Money::rescale`].
+    pub const fn new(minor_units: i64, exponent: u8) -> Self {
+        assert!(exponent <= MAX_EXPONENT, "показатель степени Money больше MAX_EXPONENT");
+        Money { minor_units, exponent }
+    }
+
+    /// Нулевая сумма с заданным количеством знаков после запятой.
+    pub const fn zero(exponent: u8) -> Self {
+        Money::new(0, exponent)
+    }
+
+    /// Сумма в минимальных единицах.
+    pub fn minor_units(&self) -> i64 {
+        self.minor_units
+    }
+
+    /// Количество знаков после запятой.
+    pub fn exponent(&self) -> u8 {
+        self.exponent
+    }
+
+    /// Разбирает строку вида `100,10`, `-100.10`, `100,` или `,50`.
+    ///
+    /// Разделителем дробной части может быть как `,`, так и `.`.
+    /// Если знаков после разделителя больше, чем `exponent`, лишние знаки
+    /// допускаются только нулевые — иначе возвращается ошибка, а не округление.
+    /// `exponent` больше [`MAX_EXPONENT`] — тоже ошибка.
+    pub fn parse(input: &str, exponent: u8) -> Result<Self, ConvertError> {
+        let invalid = || ConvertError::InvalidAmount(input.to_string());
+        if exponent > MAX_EXPONENT {
+            return Err(ConvertError::InvalidAmount(format!(
+                "{} (больше {} знаков после запятой)",
+                input, MAX_EXPONENT
+            )));
+        }
+        let s = input.trim();
+        let (negative, digits) = match s.as_bytes().first() {
+            Some(b'-') => (true, &s[1..]),
+            Some(b'+') => (false, &s[1..]),
+            _ => (false, s),
+        };
+        let (int_part, frac_part) = match digits.find([',', '.']) {
+            Some(pos) => (&digits[..pos], &digits[pos + 1..]),
+            None => (digits, ""),
+        };
+        if int_part.is_empty() && frac_part.is_empty() {
+            return Err(invalid());
+        }
+        if !int_part.bytes().all(|b| b.is_ascii_digit()) || !frac_part.bytes().all(|b| b.is_ascii_digit()) {
+            return Err(invalid());
+        }
+
+        let exp = exponent as usize;
+        let frac_part = if frac_part.len() > exp {
+            let (kept, rest) = frac_part.split_at(exp);
+            if rest.bytes().any(|b| b != b'0') {
+                return Err(ConvertError::InvalidAmount(format!(
+                    "{} (больше {} знаков после запятой)",
+                    input, exponent
+                )));
+            }
+            kept
+        } else {
+            frac_part
+        };
+
+        let mut minor: i64 = 0;
+        for b in int_part
+            .bytes()
+            .chain(frac_part.bytes())
+            .chain(std::iter::repeat_n(b'0', exp - frac_part.len()))
+        {
+            minor = minor
+                .checked_mul(10)
+                .and_then(|m| m.checked_add(i64::from(b - b'0')))
+                .ok_or_else(invalid)?;
+        }
+        Ok(Money::new(if negative { -minor } else { minor }, exponent))
+    }
+
+    /// Сумма меньше нуля (дебет).
+    pub fn is_negative(&self) -> bool {
+        self.minor_units < 0
+    }
+
+    /// Сумма равна нулю.
+    pub fn is_zero(&self) -> bool {
+        self.minor_units == 0
+    }
+
+    /// Абсолютное значение суммы.
+    ///
+    /// Без паники: для `i64::MIN` минорных единиц — `i64::MAX`.
+    pub fn abs(self) -> Self {
+        Money::new(self.minor_units.saturating_abs(), self.exponent)
+    }
+
+    /// Переводит сумму к другому количеству знаков после запятой.
+    ///
+    /// Возвращает `None`, если это невозможно без потери точности, при переполнении
+    /// или если `exponent` больше [`MAX_EXPONENT`].
+    pub fn rescale(self, exponent: u8) -> Option<Self> {
+        if exponent > MAX_EXPONENT {
+            return None;
+        }
+        match exponent.cmp(&self.exponent) {
+            Ordering::Equal => Some(self),
+            Ordering::Greater => {
+                let factor = 10i64.checked_pow(u32::from(exponent - self.exponent))?;
+                Some(Money::new(self.minor_units.checked_mul(factor)?, exponent))
+            }
+            Ordering::Less => {
+                let factor = 10i64.checked_pow(u32::from(self.exponent - exponent))?;
+                (self.minor_units % factor == 0).then(|| Money::new(self.minor_units / factor, exponent))
+            }
+        }
+    }
+
+    /// Сложение без паники при переполнении.
+    pub fn checked_add(self, other: Money) -> Option<Self> {
+        let exponent = self.exponent.max(other.exponent);
+        let (a, b) = (self.rescale(exponent)?, other.rescale(exponent)?);
+        let sum = a.minor_units.checked_add(b.minor_units).filter(|&m| m != i64::MIN)?;
+        Some(Money::new(sum, exponent))
+    }
+
+    /// Вычитание без паники при переполнении.
+    pub fn checked_sub(self, other: Money) -> Option<Self> {
+        self.checked_add(other.checked_neg()?)
+    }
+
+    /// Смена знака без паники при переполнении.
+    pub fn checked_neg(self) -> Option<Self> {
+        Some(Money::new(self.minor_units.checked_neg()?, self.exponent))
+    }
+
+    /// Сумма всех значений без паники при переполнении; пустая последовательность — ноль.
+    pub fn checked_sum(amounts: impl IntoIterator<Item = Money>) -> Option<Self> {
+        amounts.into_iter().try_fold(Money::default(), Money::checked_add)
+    }
+
+    /// Форматирует сумму с указанным разделителем дробной части.
+    ///
+    /// `Money::new(-10010, 2).format_with_separator(',')` → `Ok("-100,10")`.
+    pub fn format_with_separator(&self, separator: char) -> Result<String, ConvertError> {
+        let sign = if self.is_negative() { "-" } else { "" };
+        let abs = self.minor_units.unsigned_abs();
+        if self.exponent == 0 {
+            return Ok(format!("{}{}", sign, abs));
+        }
+        let factor = 10u64
+            .checked_pow(u32::from(self.exponent))
+            .ok_or_else(|| ConvertError::InvalidAmount(format!("{} знаков после запятой", self.exponent)))?;
+        Ok(format!(
+            "{}{}{}{:0width$}",
+            sign,
+            abs / factor,
+            separator,
+            abs % factor,
+            width = self.exponent as usize
+        ))
+    }
+}
+
+/// Ошибка для переполнения в `checked_*`: суммы из файла не должны приводить к панике.
+pub(crate) fn overflow() -> ConvertError {
+    ConvertError::InvalidAmount("переполнение при сложении сумм".to_string())
+}
+
+impl fmt::Display for Money {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(&self.format_with_separator('.').map_err(|_| fmt::Error)?)
+    }
+}
+
+/// В JSON сумма — строка `"-100.10"`: число с плавающей точкой потеряло бы точность.
+///
+/// Количество знаков после точки задает показатель степени: `"5.00"` и `"5"` — разные [`Money::exponent`].
+impl Serialize for Money {
+    fn serialize<S: Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        serializer.collect_str(self)
+    }
+}
+
+impl<'de> Deserialize<'de> for Money {
+    fn deserialize<D: Deserializer<'de>>(deserializer: D) -> Result<Self, D::Error> {
+        let text = String::deserialize(deserializer)?;
+        if text.contains(',') {
+            return Err(de::Error::custom(format!("Invalid amount: {}", text)));
+        }
+        let exponent = text.split_once('.').map_or(0, |(_, frac)| frac.len());
+        let exponent = u8::try_from(exponent)
+            .ok()
+            .filter(|exponent| *exponent <= MAX_EXPONENT)
+            .ok_or_else(|| de::Error::custom(format!("Invalid amount: {} (больше {} знаков после точки)", text, MAX_EXPONENT)))?;
+        Money::parse(&text, exponent).map_err(de::Error::custom)
+    }
+}
+
+impl JsonSchema for Money {
+    fn schema_name() -> Cow<'static, str> {
+        "Money".into()
+    }
+
+    fn json_schema(_generator: &mut SchemaGenerator) -> Schema {
+        json_schema!({
+            "type": "string",
+            "pattern": "^[+-]?[0-9]+(\\.[0-9]+)?$",
+            "description": "Сумма с фиксированной точкой, дебет со знаком минус: \"-100.10\"."
+        })
+    }
+}
+
+impl PartialEq for Money {
+    fn eq(&self, other: &Self) -> bool {
+        self.cmp(other) == Ordering::Equal
+    }
+}
+
+impl Eq for Money {}
+
+impl PartialOrd for Money {
+    fn partial_cmp(&self, other: &Self) -> Option<Ordering> {
+        Some(self.cmp(other))
+    }
+}
+
+impl Ord for Money {
+    fn cmp(&self, other: &Self) -> Ordering {
+        let exponent = self.exponent.max(other.exponent);
+        match (self.rescale(exponent), other.rescale(exponent)) {
+            (Some(a), Some(b)) => a.minor_units.cmp(&b.minor_units),
+            // Переполнение i64 при выравнивании: сравниваем в i128.
+            _ => {
+                let widen = |m: &Money| {
+                    10i128
+                        .checked_pow(u32::from(exponent - m.exponent))
+                        .and_then(|f| i128::from(m.minor_units).checked_mul(f))
+                        .unwrap_or(if m.is_negative() { i128::MIN } else { i128::MAX })
+                };
+                widen(self).cmp(&widen(other))
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn parse_accepts_comma_and_dot() {
+        assert_eq!(Money::parse("100,10", 2).unwrap().minor_units(), 10010);
+        assert_eq!(Money::parse("100.10", 2).unwrap().minor_units(), 10010);
+        assert_eq!(Money::parse("-0.5", 2).unwrap().minor_units(), -50);
+        assert_eq!(Money::parse("100,", 2).unwrap().minor_units(), 10000);
+        assert_eq!(Money::parse(",05", 2).unwrap().minor_units(), 5);
+    }
+
+    #[test]
+    fn parse_rejects_lost_precision_and_garbage() {
+        assert!(Money::parse("1,005", 2).is_err());
+        assert_eq!(Money::parse("1,500", 2).unwrap().minor_units(), 150);
+        assert!(Money::parse("100,10", 0).is_err());
+        assert!(Money::parse("", 2).is_err());
+        assert!(Money::parse("1 000", 2).is_err());
+        assert!(Money::parse("99999999999999999999", 2).is_err());
+        assert!(Money::parse("0.000000000000000001", MAX_EXPONENT).is_ok());
+        assert!(matches!(Money::parse("1", MAX_EXPONENT + 1), Err(ConvertError::InvalidAmount(_))));
+        assert_eq!(Money::new(1, 0).rescale(MAX_EXPONENT + 1), None);
+    }
+
+    #[test]
+    fn format_is_exact() {
+        assert_eq!(Money::new(10010, 2).to_string(), "100.10");
+        assert_eq!(Money::new(-5, 2).format_with_separator(',').unwrap(), "-0,05");
+        assert_eq!(Money::new(1500, 0).to_string(), "1500");
+        assert_eq!(Money::new(1, 3).to_string(), "0.001");
+    }
+
+    #[test]
+    fn totals_are_exact() {
+        let total = Money::checked_sum(std::iter::repeat_n(Money::new(10, 2), 10)).unwrap();
+        assert_eq!(total, Money::new(100, 2));
+        assert_eq!(Money::new(1, 2).checked_add(Money::new(1, 3)), Some(Money::new(11, 3)));
+        assert_eq!(Money::new(100, 2).checked_sub(Money::new(1, 0)), Some(Money::zero(2)));
+        assert_eq!(Money::new(i64::MAX, 2).checked_add(Money::new(1, 2)), None);
+        assert_eq!(Money::new(-i64::MAX, 2).checked_sub(Money::new(1, 2)), None);
+        assert_eq!(Money::checked_sum([Money::new(i64::MAX, 0), Money::new(1, 0)]), None);
+    }
+
+    #[test]
+    fn currency_exponents() {
+        assert_eq!(currency_exponent("RUB"), 2);
+        assert_eq!(currency_exponent("jpy"), 0);
+        assert_eq!(currency_exponent("KWD"), 3);
+        assert_eq!(currency_exponent("XXX"), 2);
+    }
+
+    #[test]
+    fn json_keeps_exponent() {
+        let json = serde_json::to_string(&Money::new(-10010, 2)).unwrap();
+        assert_eq!(json, "\"-100.10\"");
+        let money: Money = serde_json::from_str(&json).unwrap();
+        assert_eq!((money.minor_units(), money.exponent()), (-10010, 2));
+        assert_eq!(serde_json::from_str::<Money>("\"5\"").unwrap().exponent(), 0);
+        assert!(serde_json::from_str::<Money>("5.5").is_err());
+        assert!(serde_json::from_str::<Money>("\"5,5\"").is_err());
+        assert_eq!(
+            serde_json::from_str::<Money>("\"0.000000000000000001\"").unwrap().exponent(),
+            MAX_EXPONENT
+        );
+        let error = serde_json::from_str::<Money>("\"0.000000000000000000001\"").unwrap_err();
+        assert!(error.to_string().contains("больше 18 знаков"), "{}", error);
+    }
+}
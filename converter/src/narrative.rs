@@ -0,0 +1,287 @@
+//! Структурированное поле `:86:` MT940.
+//!
+//! Банки кодируют `:86:` по-разному: немецкие и польские — подполями `?20`, `~20`,
+//! голландские и SWIFT-совместимые — кодами `/REMI/`, `/BENM/`, `/ORDP/`.
+//! Используется первый подходящий диалект из [`crate::ConvertOptions::narrative_dialects`].
+
+use std::{fmt, sync::Arc};
+
+use crate::model::Counterparty;
+
+/// Поля, извлеченные из `:86:`.
+#[derive(Debug, Clone, PartialEq, Default)]
+pub struct Narrative {
+    /// Код операции (GVC): `166` в `166?00...`.
+    pub gvc: Option<String>,
+    /// Контрагент.
+    pub counterparty: Counterparty,
+    /// Назначение платежа.
+    pub remittance_information: Option<String>,
+}
+
+/// Диалект поля `:86:`.
+///
+/// Свои диалекты подключаются через [`crate::ConvertOptions::narrative_dialects`].
+pub trait NarrativeDialect: fmt::Debug + Send + Sync {
+    /// Разбирает текст `:86:` (строки через `\n`).
+    ///
+    /// Возвращает `None`, если текст записан не в этом диалекте.
+    fn parse(&self, text: &str) -> Option<Narrative>;
+}
+
+/// Диалекты по умолчанию: немецкий `?`, польский `~` и `<`, коды `/XXXX/`.
+pub(crate) fn default_dialects() -> Vec<Arc<dyn NarrativeDialect>> {
+    vec![
+        Arc::new(SubfieldDialect::german()),
+        Arc::new(SubfieldDialect::polish('~')),
+        Arc::new(SubfieldDialect::polish('<')),
+        Arc::new(SlashCodeDialect),
+    ]
+}
+
+/// Строки поля банки переносят по 65 символов без учета слов — склеиваем без разделителя.
+fn unwrap_lines(text: &str) -> String {
+    text.lines().collect()
+}
+
+fn non_empty(value: &str) -> Option<String> {
+    let value = value.trim();
+    (!value.is_empty()).then(|| value.to_string())
+}
+
+type Subfields = (Option<String>, Vec<(u8, String)>);
+
+/// Подполя с двузначными кодами: `166?00GUTSCHRIFT?20...?32NAME`.
+///
+/// Перед первым разделителем может стоять трехзначный код операции (GVC).
+#[derive(Debug, Clone, PartialEq)]
+pub struct SubfieldDialect {
+    /// Разделитель подполей: `?` (Германия), `~` или `<` (Польша).
+    pub separator: char,
+    /// Коды назначения платежа, склеиваются по порядку.
+    pub purpose_codes: Vec<u8>,
+    /// Коды наименования контрагента, склеиваются по порядку.
+    pub name_codes: Vec<u8>,
+    /// Коды счета контрагента по приоритету: берется первый заполненный.
+    pub account_codes: Vec<u8>,
+    /// Код банка контрагента: BLZ или BIC.
+    pub bank_code: u8,
+}
+
+impl SubfieldDialect {
+    /// Немецкий формат (DFÜ-Abkommen): `?20`–`?29`, `?60`–`?63` назначение,
+    /// `?30` BLZ/BIC, `?31` счет/IBAN, `?32`/`?33` наименование.
+    pub fn german() -> Self {
+        Self {
+            separator: '?',
+            purpose_codes: (20..=29).chain(60..=63).collect(),
+            name_codes: vec![32, 33],
+            account_codes: vec![31],
+            bank_code: 30,
+        }
+    }
+
+    /// Польский формат: `~20`–`~25` назначение, `~27`–`~29`, `~32`/`~33` наименование,
+    /// `~38` IBAN, `~31` счет, `~30` код банка.
+    pub fn polish(separator: char) -> Self {
+        Self {
+            separator,
+            purpose_codes: (20..=25).collect(),
+            name_codes: vec![27, 28, 29, 32, 33],
+            account_codes: vec![38, 31],
+            bank_code: 30,
+        }
+    }
+
+    /// Код операции перед первым разделителем и подполя (код, значение).
+    fn subfields(&self, text: &str) -> Option<Subfields> {
+        let (head, rest) = text.split_once(self.separator)?;
+        let head = head.trim();
+        let gvc_like = head.len() == 3 && head.bytes().all(|b| b.is_ascii_digit());
+        if !head.is_empty() && !gvc_like {
+            return None;
+        }
+        let mut fields: Vec<(u8, String)> = Vec::new();
+        for part in rest.split(self.separator) {
+            let code = part.get(..2).filter(|c| c.bytes().all(|b| b.is_ascii_digit()));
+            match (code, fields.last_mut()) {
+                (Some(code), _) => fields.push((code.parse().ok()?, part[2..].to_string())),
+                // Разделитель внутри текста, а не начало подполя.
+                (None, Some((_, value))) => {
+                    value.push(self.separator);
+                    value.push_str(part);
+                }
+                (None, None) => return None,
+            }
+        }
+        Some((non_empty(head), fields))
+    }
+}
+
+impl NarrativeDialect for SubfieldDialect {
+    fn parse(&self, text: &str) -> Option<Narrative> {
+        let (gvc, fields) = self.subfields(&unwrap_lines(text))?;
+        let joined = |codes: &[u8]| {
+            let value: String = fields
+                .iter()
+                .filter(|(code, _)| codes.contains(code))
+                .map(|(_, value)| value.as_str())
+                .collect();
+            non_empty(&value)
+        };
+        let field = |code: u8| fields.iter().find(|(c, _)| *c == code).and_then(|(_, value)| non_empty(value));
+
+        let bank = field(self.bank_code);
+        let is_bic = bank.as_deref().is_some_and(|b| b.bytes().any(|c| c.is_ascii_alphabetic()));
+        Some(Narrative {
+            gvc,
+            counterparty: Counterparty {
+                name: joined(&self.name_codes),
+                account: self.account_codes.iter().find_map(|code| field(*code)),
+                bic: bank.clone().filter(|_| is_bic),
+                bank_code: bank.filter(|_| !is_bic),
+                ..Default::default()
+            },
+            remittance_information: joined(&self.purpose_codes),
+        })
+    }
+}
+
+/// Коды вида `/REMI/текст/NAME/имя`: ABN AMRO, ING, Rabobank и рекомендации SWIFT.
+#[derive(Debug, Clone, Copy, PartialEq, Default)]
+pub struct SlashCodeDialect;
+
+const SLASH_CODES: &[&str] = &[
+    "TRTP", "IBAN", "BIC", "NAME", "REMI", "EREF", "ORDP", "BENM", "CNTP", "ADDR", "CSID", "MARF", "PREF", "RTRN", "ULTC", "ULTD",
+    "ULTB", "PURP", "ISDT", "ACCW", "SVCL", "ID",
+];
+
+/// Разбивает текст на пары (код, значение) по известным кодам `/XXXX/`.
+fn slash_fields(text: &str) -> Vec<(&'static str, &str)> {
+    let mut markers: Vec<(usize, usize, &'static str)> = Vec::new();
+    let mut pos = 0;
+    while let Some(offset) = text[pos..].find('/') {
+        let start = pos + offset;
+        let code = SLASH_CODES
+            .iter()
+            .find(|code| text[start + 1..].strip_prefix(**code).is_some_and(|rest| rest.starts_with('/')));
+        match code {
+            Some(code) => {
+                let end = start + code.len() + 2;
+                markers.push((start, end, code));
+                // Закрывающий `/` маркера может быть открывающим для следующего: `/ORDP//NAME/`.
+                pos = end - 1;
+            }
+            None => pos = start + 1,
+        }
+    }
+    let mut fields = Vec::new();
+    for (index, (_, end, code)) in markers.iter().enumerate() {
+        let value_end = markers.get(index + 1).map_or(text.len(), |next| next.0.max(*end));
+        fields.push((*code, &text[*end..value_end]));
+    }
+    fields
+}
+
+impl NarrativeDialect for SlashCodeDialect {
+    fn parse(&self, text: &str) -> Option<Narrative> {
+        let text = unwrap_lines(text);
+        if !text.trim_start().starts_with('/') {
+            return None;
+        }
+        let fields = slash_fields(&text);
+        if !fields
+            .iter()
+            .any(|(code, _)| matches!(*code, "REMI" | "NAME" | "IBAN" | "BENM" | "ORDP" | "CNTP"))
+        {
+            return None;
+        }
+
+        let mut narrative = Narrative::default();
+        let counterparty = &mut narrative.counterparty;
+        for (code, value) in fields {
+            let value = value.trim_matches('/');
+            match code {
+                "IBAN" => counterparty.account = counterparty.account.take().or_else(|| non_empty(value)),
+                "BIC" => counterparty.bic = counterparty.bic.take().or_else(|| non_empty(value)),
+                "NAME" => counterparty.name = counterparty.name.take().or_else(|| non_empty(value)),
+                // ING: /CNTP/IBAN/BIC/NAME/CITY/
+                "CNTP" => {
+                    let mut parts = value.split('/');
+                    let mut next = || parts.next().and_then(non_empty);
+                    let (account, bic, name) = (next(), next(), next());
+                    counterparty.account = counterparty.account.take().or(account);
+                    counterparty.bic = counterparty.bic.take().or(bic);
+                    counterparty.name = counterparty.name.take().or(name);
+                }
+                // ING: /REMI/USTD//текст/ или /REMI/STRD/CUR/референс/
+                "REMI" => {
+                    let value = value
+                        .strip_prefix("USTD//")
+                        .or_else(|| value.strip_prefix("STRD/CUR/"))
+                        .unwrap_or(value);
+                    narrative.remittance_information = narrative.remittance_information.take().or_else(|| non_empty(value));
+                }
+                _ => {}
+            }
+        }
+        Some(narrative)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn german_subfields() {
+        let text = "166?00SEPA-UEBERWEISUNG?109310?20EREF+2023-01?21SVWZ+Rechnung 4711\n?30COBADEFFXXX?31DE89370400440532013000?32Max Muster\n?33mann GmbH?34997";
+        let narrative = SubfieldDialect::german().parse(text).unwrap();
+        assert_eq!(narrative.gvc.as_deref(), Some("166"));
+        assert_eq!(narrative.remittance_information.as_deref(), Some("EREF+2023-01SVWZ+Rechnung 4711"));
+        assert_eq!(narrative.counterparty.name.as_deref(), Some("Max Mustermann GmbH"));
+        assert_eq!(narrative.counterparty.account.as_deref(), Some("DE89370400440532013000"));
+        assert_eq!(narrative.counterparty.bic.as_deref(), Some("COBADEFFXXX"));
+
+        let narrative = SubfieldDialect::german().parse("051?20Miete?3037040044?310532013000").unwrap();
+        assert_eq!(narrative.counterparty.bank_code.as_deref(), Some("37040044"));
+        assert_eq!(narrative.counterparty.bic, None);
+    }
+
+    #[test]
+    fn polish_subfields() {
+        let text =
+            "020~00VE02~20Faktura FV/1/2023~21 za styczen~27Jan Kowalski~28ul. Prosta 1~3010201026\n~38PL61109010140000071219812874";
+        let narrative = SubfieldDialect::polish('~').parse(text).unwrap();
+        assert_eq!(narrative.gvc.as_deref(), Some("020"));
+        assert_eq!(narrative.remittance_information.as_deref(), Some("Faktura FV/1/2023 za styczen"));
+        assert_eq!(narrative.counterparty.name.as_deref(), Some("Jan Kowalskiul. Prosta 1"));
+        assert_eq!(narrative.counterparty.account.as_deref(), Some("PL61109010140000071219812874"));
+        assert!(SubfieldDialect::german().parse(text).is_none());
+    }
+
+    #[test]
+    fn slash_codes() {
+        let abn =
+            "/TRTP/SEPA OVERBOEKING/IBAN/NL44RABO0123456789/BIC/RABONL2U/NAME/J. J\nANSEN/REMI/Factuur 2023-001/EREF/NOTPROVIDED";
+        let narrative = SlashCodeDialect.parse(abn).unwrap();
+        assert_eq!(narrative.counterparty.account.as_deref(), Some("NL44RABO0123456789"));
+        assert_eq!(narrative.counterparty.bic.as_deref(), Some("RABONL2U"));
+        assert_eq!(narrative.counterparty.name.as_deref(), Some("J. JANSEN"));
+        assert_eq!(narrative.remittance_information.as_deref(), Some("Factuur 2023-001"));
+
+        let rabo = SlashCodeDialect.parse("/ORDP//NAME/Acme B.V./ADDR/Dam 1/REMI/order 42").unwrap();
+        assert_eq!(rabo.counterparty.name.as_deref(), Some("Acme B.V."));
+        assert_eq!(rabo.remittance_information.as_deref(), Some("order 42"));
+
+        let ing = SlashCodeDialect
+            .parse("/CNTP/NL08INGB0000000555/INGBNL2A/ING Bank/Amsterdam/REMI/USTD//Invoice 7/")
+            .unwrap();
+        assert_eq!(ing.counterparty.bic.as_deref(), Some("INGBNL2A"));
+        assert_eq!(ing.counterparty.name.as_deref(), Some("ING Bank"));
+        assert_eq!(ing.remittance_information.as_deref(), Some("Invoice 7"));
+
+        assert!(SlashCodeDialect.parse("Salary for January").is_none());
+        assert!(SlashCodeDialect.parse("/some/path").is_none());
+    }
+}
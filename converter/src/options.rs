@@ -0,0 +1,316 @@
+//! Настройки конвертации.
+
+use std::{fmt, str::FromStr, sync::Arc};
+
+use parser::CsvDialect;
+
+use crate::{
+    csv_mapping::CsvMapping,
+    ledger_rules::LedgerRules,
+    narrative::{self, NarrativeDialect},
+};
+
+/// Версия сообщения ISO 20022 camt (`camt.053.001.NN`).
+///
+/// Версии отличаются пространством имен и отдельными элементами:
+/// например, начиная с 001.08 статус записи пишется как `Sts/Cd`, а не `Sts`.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Default)]
+#[allow(missing_docs)]
+pub enum CamtVersion {
+    #[default]
+    V02,
+    V03,
+    V04,
+    V05,
+    V06,
+    V07,
+    V08,
+    V09,
+    V10,
+    V11,
+    V12,
+    V13,
+}
+
+impl CamtVersion {
+    /// Все поддерживаемые версии по возрастанию.
+    pub const ALL: [CamtVersion; 12] = [
+        CamtVersion::V02,
+        CamtVersion::V03,
+        CamtVersion::V04,
+        CamtVersion::V05,
+        CamtVersion::V06,
+        CamtVersion::V07,
+        CamtVersion::V08,
+        CamtVersion::V09,
+        CamtVersion::V10,
+        CamtVersion::V11,
+        CamtVersion::V12,
+        CamtVersion::V13,
+    ];
+
+    /// Номер версии: `2` для 001.02.
+    pub fn number(self) -> u8 {
+        self as u8 + 2
+    }
+
+    /// Пространство имен документа, например `urn:iso:std:iso:20022:tech:xsd:camt.053.001.02`.
+    pub fn namespace(self, message: &str) -> String {
+        format!("urn:iso:std:iso:20022:tech:xsd:{}.001.{:02}", message, self.number())
+    }
+
+    /// Определяет сообщение и версию по пространству имен: `("camt.053", V08)`.
+    pub fn from_namespace(namespace: &str) -> Option<(&str, CamtVersion)> {
+        let rest = namespace.strip_prefix("urn:iso:std:iso:20022:tech:xsd:")?;
+        let (message, version) = rest.split_once(".001.")?;
+        let version = version.parse::<u8>().ok()?;
+        let found = CamtVersion::ALL.into_iter().find(|v| v.number() == version)?;
+        Some((message, found))
+    }
+
+    /// Статус записи — структура `Sts/Cd` (с 001.08), а не код `Sts`.
+    pub(crate) fn structured_status(self) -> bool {
+        self >= CamtVersion::V08
+    }
+
+    /// Стороны `RltdPties/Dbtr` — выбор `Pty`/`Agt` (с 001.07), а не сразу `Nm`.
+    pub(crate) fn party_choice(self) -> bool {
+        self >= CamtVersion::V07
+    }
+
+    /// Элемент BIC в `FinInstnId`: `BIC` до 001.03, затем `BICFI`.
+    pub(crate) fn bic_element(self) -> &'static str {
+        if self >= CamtVersion::V04 { "BICFI" } else { "BIC" }
+    }
+}
+
+impl fmt::Display for CamtVersion {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "001.{:02}", self.number())
+    }
+}
+
+impl FromStr for CamtVersion {
+    type Err = String;
+
+    /// Принимает `001.08`, `08` или `8`.
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let number = s.trim().trim_start_matches("001.");
+        number
+            .parse::<u8>()
+            .ok()
+            .and_then(|n| CamtVersion::ALL.into_iter().find(|v| v.number() == n))
+            .ok_or_else(|| format!("неизвестная версия camt: {} (поддерживаются 001.02 - 001.13)", s))
+    }
+}
+
+/// Версия OFX для записи: 1.02 (SGML, привычна Quicken) или 2.2 (XML).
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum OfxVersion {
+    /// OFX 1.02: заголовок `KEY:VALUE`, листья без закрывающих тегов.
+    V102,
+    /// OFX 2.2: XML с инструкцией `<?OFX ...?>`.
+    #[default]
+    V220,
+}
+
+impl OfxVersion {
+    /// Значение `VERSION` в заголовке: `102`, `220`.
+    pub fn number(self) -> u16 {
+        match self {
+            OfxVersion::V102 => 102,
+            OfxVersion::V220 => 220,
+        }
+    }
+}
+
+impl fmt::Display for OfxVersion {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{}", self.number())
+    }
+}
+
+impl FromStr for OfxVersion {
+    type Err = String;
+
+    /// Принимает `102`/`1` и `220`/`2`.
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s.trim() {
+            "102" | "1" => Ok(OfxVersion::V102),
+            "220" | "2" => Ok(OfxVersion::V220),
+            _ => Err(format!("неизвестная версия OFX: {} (поддерживаются 102 и 220)", s)),
+        }
+    }
+}
+
+/// Версия сообщения ISO 20022 pain.001 (`pain.001.001.NN`) для платежных поручений SEPA.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum PainVersion {
+    /// pain.001.001.03: BIC в `BIC`, дата исполнения — просто дата.
+    V03,
+    /// pain.001.001.09 (SEPA Rulebook 2019+): `BICFI`, дата исполнения — выбор `Dt`/`DtTm`.
+    #[default]
+    V09,
+}
+
+impl PainVersion {
+    /// Номер версии: `3` для 001.03.
+    pub fn number(self) -> u8 {
+        match self {
+            PainVersion::V03 => 3,
+            PainVersion::V09 => 9,
+        }
+    }
+
+    /// Пространство имен документа, например `urn:iso:std:iso:20022:tech:xsd:pain.001.001.09`.
+    pub fn namespace(self) -> String {
+        format!("urn:iso:std:iso:20022:tech:xsd:pain.001.001.{:02}", self.number())
+    }
+
+    /// Элемент BIC в `FinInstnId`.
+    pub(crate) fn bic_element(self) -> &'static str {
+        match self {
+            PainVersion::V03 => "BIC",
+            PainVersion::V09 => "BICFI",
+        }
+    }
+}
+
+impl fmt::Display for PainVersion {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "001.{:02}", self.number())
+    }
+}
+
+impl FromStr for PainVersion {
+    type Err = String;
+
+    /// Принимает `001.03`, `03` или `3` (и так же для 09).
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s.trim().trim_start_matches("001.").trim_start_matches('0') {
+            "3" => Ok(PainVersion::V03),
+            "9" => Ok(PainVersion::V09),
+            _ => Err(format!("неизвестная версия pain.001: {} (поддерживаются 001.03 и 001.09)", s)),
+        }
+    }
+}
+
+/// Плательщик платежного файла pain.001: в списке платежей обычно только получатели.
+#[derive(Debug, Clone, PartialEq, Default)]
+pub struct SepaDebtor {
+    /// Наименование плательщика (`Dbtr/Nm`, `InitgPty/Nm`), обязательно.
+    pub name: String,
+    /// IBAN плательщика; `None` — счет из [`crate::Transaction::account`].
+    pub iban: Option<String>,
+    /// BIC банка плательщика; `None` — `NOTPROVIDED`, как допускает SEPA.
+    pub bic: Option<String>,
+}
+
+/// Порядок дня и месяца в датах QIF: в QIF нет единого формата даты.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum QifDateFormat {
+    /// `MM/DD/YYYY`, как пишет Quicken в США; при чтении и `M/D'YY`.
+    #[default]
+    Us,
+    /// `DD/MM/YYYY`; при чтении и `DD.MM.YY`.
+    European,
+}
+
+impl FromStr for QifDateFormat {
+    type Err = String;
+
+    /// Принимает `us`/`mdy` и `european`/`eu`/`dmy`.
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s.trim().to_ascii_lowercase().as_str() {
+            "us" | "mdy" => Ok(QifDateFormat::Us),
+            "european" | "eu" | "dmy" => Ok(QifDateFormat::European),
+            _ => Err(format!("неизвестный формат даты QIF: {} (us или european)", s)),
+        }
+    }
+}
+
+/// Настройки, влияющие на разбор и запись форматов.
+///
+/// Значения по умолчанию подходят для большинства выписок;
+/// используется в [`crate::convert_with_options`].
+#[derive(Debug, Clone)]
+pub struct ConvertOptions {
+    /// Граница столетия для дат YYMMDD (MT940 и т.п.):
+    /// годы `00..century_pivot` — это 20xx, остальные — 19xx.
+    pub century_pivot: u8,
+    /// Версия camt для записи. При чтении версия определяется по пространству имен.
+    pub camt_version: CamtVersion,
+    /// Версия OFX для записи. При чтении версия определяется по заголовку.
+    pub ofx_version: OfxVersion,
+    /// Диалекты структурированного `:86:` MT940 в порядке проверки.
+    /// Пустой список — `:86:` сохраняется только как текст.
+    pub narrative_dialects: Vec<Arc<dyn NarrativeDialect>>,
+    /// Порядок дня и месяца в датах QIF при чтении и записи.
+    pub qif_date_format: QifDateFormat,
+    /// Валюта операций QIF: в самом файле ее нет.
+    pub qif_currency: String,
+    /// Сопоставление колонок CSV полям транзакции.
+    pub csv_mapping: CsvMapping,
+    /// Диалект CSV для чтения и записи.
+    pub csv_dialect: CsvDialect,
+    /// Лист xlsx для чтения; `None` — первый лист книги.
+    /// Колонки листа сопоставляются по [`ConvertOptions::csv_mapping`].
+    pub xlsx_sheet: Option<String>,
+    /// Счета журналов ledger/hledger и beancount.
+    pub ledger_rules: LedgerRules,
+    /// Версия pain.001 для записи.
+    pub pain_version: PainVersion,
+    /// Плательщик для pain.001.
+    pub sepa_debtor: SepaDebtor,
+}
+
+impl Default for ConvertOptions {
+    fn default() -> Self {
+        Self {
+            century_pivot: 70,
+            camt_version: CamtVersion::default(),
+            ofx_version: OfxVersion::default(),
+            narrative_dialects: narrative::default_dialects(),
+            qif_date_format: QifDateFormat::default(),
+            qif_currency: "XXX".to_string(),
+            csv_mapping: CsvMapping::default(),
+            csv_dialect: CsvDialect::default(),
+            xlsx_sheet: None,
+            ledger_rules: LedgerRules::default(),
+            pain_version: PainVersion::default(),
+            sepa_debtor: SepaDebtor::default(),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn camt_version_namespace_round_trip() {
+        for version in CamtVersion::ALL {
+            let namespace = version.namespace("camt.053");
+            assert_eq!(CamtVersion::from_namespace(&namespace), Some(("camt.053", version)));
+            assert_eq!(version.to_string().parse::<CamtVersion>(), Ok(version));
+        }
+        assert_eq!(
+            CamtVersion::V08.namespace("camt.053"),
+            "urn:iso:std:iso:20022:tech:xsd:camt.053.001.08"
+        );
+        assert_eq!(CamtVersion::from_namespace("urn:iso:std:iso:20022:tech:xsd:camt.053.001.99"), None);
+        assert_eq!("10".parse::<CamtVersion>(), Ok(CamtVersion::V10));
+        assert!("001.01".parse::<CamtVersion>().is_err());
+    }
+
+    #[test]
+    fn pain_version_parse() {
+        for version in [PainVersion::V03, PainVersion::V09] {
+            assert_eq!(version.to_string().parse::<PainVersion>(), Ok(version));
+        }
+        assert_eq!("3".parse::<PainVersion>(), Ok(PainVersion::V03));
+        assert_eq!(PainVersion::V09.namespace(), "urn:iso:std:iso:20022:tech:xsd:pain.001.001.09");
+        assert!("001.10".parse::<PainVersion>().is_err());
+        assert!("".parse::<PainVersion>().is_err());
+    }
+}
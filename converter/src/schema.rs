@@ -0,0 +1,96 @@
+//! Версионированная JSON-схема модели для [`Format::Json`](crate::Format::Json) и
+//! [`Format::JsonLines`](crate::Format::JsonLines).
+//!
+//! - JSON — документ [`JsonDocument`]: `{"schema_version": 1, "statements": [...]}`.
+//! - JSON Lines — по одной транзакции на строку ([`JsonLine`]): поля [`Transaction`] и `schema_version`.
+//!   При чтении подряд идущие строки с одинаковыми `reference`, `account` и `currency` собираются в выписку.
+//!
+//! Суммы — строки `"-100.10"` (дебет со знаком минус), даты — `YYYY-MM-DD`,
+//! время — `YYYY-MM-DDTHH:MM:SS`, вид сальдо — `opening`, `closing`, `interim_opening`...
+//! Необязательные поля могут отсутствовать или быть `null`.
+//!
+//! [`SCHEMA_VERSION`] повышается при несовместимых изменениях: переименовании, удалении
+//! или смене типа поля. Новые необязательные поля версию не меняют.
+
+use parser::ParseError;
+use schemars::{JsonSchema, schema_for};
+use serde::{Deserialize, Serialize};
+
+use crate::{
+    error::ConvertError,
+    model::{Statement, Transaction},
+};
+
+/// Текущая версия схемы; документы с большей версией не читаются.
+pub const SCHEMA_VERSION: u32 = 1;
+
+/// Документ JSON: выписки целиком.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize, JsonSchema)]
+pub struct JsonDocument {
+    /// Версия схемы, [`SCHEMA_VERSION`] при записи.
+    pub schema_version: u32,
+    /// Выписки в порядке исходного файла.
+    pub statements: Vec<Statement>,
+}
+
+/// Строка JSON Lines: одна транзакция.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize, JsonSchema)]
+pub struct JsonLine {
+    /// Версия схемы, [`SCHEMA_VERSION`] при записи.
+    pub schema_version: u32,
+    /// Поля транзакции на верхнем уровне объекта.
+    #[serde(flatten)]
+    pub transaction: Transaction,
+}
+
+/// JSON Schema документа [`JsonDocument`].
+pub fn json_schema() -> serde_json::Value {
+    schema_for!(JsonDocument).to_value()
+}
+
+/// JSON Schema строки [`JsonLine`].
+pub fn json_lines_schema() -> serde_json::Value {
+    schema_for!(JsonLine).to_value()
+}
+
+/// Версия поддерживается этой библиотекой.
+pub(crate) fn check_version(version: u32) -> Result<(), ConvertError> {
+    if version == 0 || version > SCHEMA_VERSION {
+        return Err(ParseError::InvalidFormat(format!(
+            "JSON: schema_version {} не поддерживается (ожидалась {})",
+            version, SCHEMA_VERSION
+        ))
+        .into());
+    }
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn schemas_describe_versioned_envelope() {
+        let schema = json_schema();
+        assert_eq!(schema["required"], serde_json::json!(["schema_version", "statements"]));
+        let defs = &schema["$defs"];
+        assert_eq!(defs["Money"]["type"], "string");
+        assert!(defs["Transaction"]["required"].as_array().unwrap().contains(&"value_date".into()));
+        assert!(
+            defs["BalanceKind"].to_string().contains("interim_opening"),
+            "{}",
+            defs["BalanceKind"]
+        );
+
+        let line = json_lines_schema();
+        let required = line["required"].as_array().unwrap();
+        assert!(
+            required.contains(&"schema_version".into()) && required.contains(&"amount".into()),
+            "{}",
+            line
+        );
+
+        assert!(check_version(SCHEMA_VERSION).is_ok());
+        assert!(check_version(SCHEMA_VERSION + 1).is_err());
+    }
+}
@@ -1,125 +1,374 @@
+mod bai2;
+mod camt;
+mod client_bank;
+mod coda;
+mod json;
+mod ledger;
+mod ofx;
+mod pain;
+mod parquet;
+mod qif;
+mod xlsx;
+
 use std::io::Write;
 
-use crate::model::Transaction;
+use chrono::{NaiveDate, Utc};
+use parser::{CsvDialect, CsvQuoting, ParseError};
+
+use crate::{
+    error::ConvertError,
+    from_parser::camt::CamtMessage,
+    model::{Balance, BalanceKind, Statement, Transaction},
+    money::{Money, currency_exponent, overflow},
+    options::ConvertOptions,
+};
 
 /// Базовый trait для конвертеров форматов.
 pub trait ToFormat {
     /// Обязательный метод для всех кто реализует ToFormat.
     ///
     /// Записывает в Write данные преобразования.
-    fn from_transactions<W: Write>(txs: &[Transaction], writer: W) -> std::io::Result<()>;
+    fn from_statements<W: Write>(statements: &[Statement], options: &ConvertOptions, writer: W) -> Result<(), ConvertError>;
+
+    /// Записывает транзакции как одну выписку без сальдо с настройками по умолчанию.
+    fn from_transactions<W: Write>(txs: &[Transaction], writer: W) -> Result<(), ConvertError> {
+        Self::from_statements(&[Statement::from_entries(txs.to_vec())], &ConvertOptions::default(), writer)
+    }
 }
 
-fn escape_csv_field(field: &str) -> String {
-    if field.contains([',', '"', '\n', '\r']) {
-        let escaped = field.replace('"', "\"\"");
-        format!("\"{}\"", escaped)
+/// Поле CSV по правилу кавычек. Без кавычек поле с разделителем, кавычкой или переводом
+/// строки не прочитать обратно, поэтому при [`CsvQuoting::Never`] это ошибка.
+fn escape_csv_field(field: &str, dialect: &CsvDialect) -> Result<String, ConvertError> {
+    let delimiter = char::from(dialect.write_delimiter());
+    let quote = char::from(dialect.quote);
+    let special = field.contains([delimiter, quote, '\n', '\r']);
+    let needs_quotes = match dialect.quoting {
+        CsvQuoting::Always => true,
+        CsvQuoting::Never if special => {
+            return Err(ParseError::InvalidFormat(format!(
+                "CSV без кавычек: поле {:?} содержит разделитель, кавычку или перевод строки",
+                field
+            ))
+            .into());
+        }
+        CsvQuoting::Never => false,
+        CsvQuoting::Necessary => special,
+    };
+    if needs_quotes {
+        let escaped = field.replace(quote, &format!("{}{}", quote, quote));
+        Ok(format!("{}{}{}", quote, escaped, quote))
     } else {
-        field.to_string()
+        Ok(field.to_string())
     }
 }
 
 pub struct CsvFormat;
 impl ToFormat for CsvFormat {
-    fn from_transactions<W: Write>(txs: &[Transaction], mut writer: W) -> std::io::Result<()> {
-        writeln!(writer, "reference,account,amount,currency,date,description")?;
-
-        for tx in txs {
-            writeln!(
-                writer,
-                "{},{},{},{},{},{}",
-                escape_csv_field(&tx.reference),
-                escape_csv_field(&tx.account),
-                tx.amount,
-                escape_csv_field(&tx.currency),
-                escape_csv_field(&tx.value_date),
-                escape_csv_field(&tx.description)
-            )?;
+    fn from_statements<W: Write>(statements: &[Statement], options: &ConvertOptions, mut writer: W) -> Result<(), ConvertError> {
+        let dialect = &options.csv_dialect;
+        let delimiter = char::from(dialect.write_delimiter()).to_string();
+        let decimal = if dialect.decimal_comma { ',' } else { '.' };
+        // Собираем текст целиком: кодировка применяется ко всему файлу.
+        let mut text = String::new();
+        let mut push_row = |fields: [&str; 6]| -> Result<(), ConvertError> {
+            let fields = fields.iter().map(|f| escape_csv_field(f, dialect)).collect::<Result<Vec<_>, _>>()?;
+            text.push_str(&fields.join(&delimiter));
+            text.push('\n');
+            Ok(())
+        };
+        if dialect.has_header {
+            push_row(["reference", "account", "amount", "currency", "date", "description"])?;
+        }
+        for tx in statements.iter().flat_map(|st| &st.entries) {
+            push_row([
+                &tx.reference,
+                &tx.account,
+                &tx.amount.format_with_separator(decimal)?,
+                &tx.currency,
+                &tx.value_date.to_string(),
+                &tx.description,
+            ])?;
         }
+        writer.write_all(&dialect.encode(&text)?)?;
         Ok(())
     }
 }
 
-pub struct Mt940Format;
-impl ToFormat for Mt940Format {
-    fn from_transactions<W: Write>(txs: &[Transaction], mut writer: W) -> std::io::Result<()> {
-        for tx in txs {
-            writeln!(writer, ":20:{}", tx.reference)?;
-            writeln!(writer, ":25:{}", tx.account)?;
+/// Сальдо MT940: `C230101USD1000,00`.
+fn mt940_balance(balance: &Balance) -> Result<String, ConvertError> {
+    Ok(format!(
+        "{}{}{}{}",
+        if balance.amount.is_negative() { "D" } else { "C" },
+        balance.date.format("%y%m%d"),
+        balance.currency,
+        balance.amount.abs().format_with_separator(',')?
+    ))
+}
+
+/// Строки поля MT940: переводы строк и пустые строки внутри текста сохраняются, длинные строки
+/// режутся по `max_chars` символов, строки сверх `max_lines` отбрасываются.
+fn mt940_lines(text: &str, max_chars: usize, max_lines: usize) -> Vec<String> {
+    let mut lines = Vec::new();
+    for line in text.lines() {
+        let chars: Vec<char> = line.chars().collect();
+        if chars.is_empty() {
+            lines.push(String::new());
+        }
+        lines.extend(chars.chunks(max_chars).map(|chunk| chunk.iter().collect::<String>()));
+    }
+    lines.truncate(max_lines);
+    while lines.last().is_some_and(|line| line.trim().is_empty()) {
+        lines.pop();
+    }
+    lines
+}
+
+/// Строка `:61:` и, если `narrative`, поле `:86:` одной операции (MT940, MT942; в MT950 `:86:` нет).
+fn write_mt940_entry<W: Write>(writer: &mut W, tx: &Transaction, narrative: bool) -> Result<(), ConvertError> {
+    let entry_date = tx.booking_date.map(|d| d.format("%m%d").to_string()).unwrap_or_default();
+    let mark = match (tx.reversal, tx.amount.is_negative()) {
+        (false, true) => "D",
+        (false, false) => "C",
+        (true, true) => "RC",
+        (true, false) => "RD",
+    };
+    write!(
+        writer,
+        ":61:{}{}{}{}{}{}{}",
+        tx.value_date.format("%y%m%d"),
+        entry_date,
+        mark,
+        tx.funds_code.map(String::from).unwrap_or_default(),
+        tx.amount.abs().format_with_separator(',')?,
+        tx.transaction_type.as_deref().unwrap_or("NMSC"),
+        tx.customer_reference.as_deref().unwrap_or("NONREF")
+    )?;
+    if let Some(bank_reference) = &tx.bank_reference {
+        write!(writer, "//{}", bank_reference)?;
+    }
+    writeln!(writer)?;
+    if let Some(details) = &tx.supplementary_details {
+        writeln!(writer, "{}", details)?;
+    }
+
+    // :86: — до 6 строк по 65 символов, перенос строк сохраняется, остаток текста отбрасывается.
+    let lines = mt940_lines(&tx.description, 65, 6);
+    if narrative && !lines.is_empty() {
+        writeln!(writer, ":86:{}", lines.join("\n"))?;
+    }
+    Ok(())
+}
+
+/// Выписки MT940 или, без `:86:`, MT950.
+fn write_mt940_statements<W: Write>(statements: &[Statement], narrative: bool, mut writer: W) -> Result<(), ConvertError> {
+    for st in statements {
+        let (opening, closing) = st.balances()?;
+        writeln!(writer, ":20:{}", st.id)?;
+        writeln!(writer, ":25:{}", st.account)?;
+        writeln!(writer, ":28C:{}", st.sequence_number.as_deref().unwrap_or("1"))?;
+        let opening_tag = if opening.kind == BalanceKind::InterimOpening {
+            "60M"
+        } else {
+            "60F"
+        };
+        writeln!(writer, ":{}:{}", opening_tag, mt940_balance(&opening)?)?;
+
+        for tx in &st.entries {
+            write_mt940_entry(&mut writer, tx, narrative)?;
+        }
 
-            let (yy, mm, dd) = if tx.value_date.len() >= 10 {
-                (&tx.value_date[2..4], &tx.value_date[5..7], &tx.value_date[8..10])
+        let closing_tag = if closing.kind == BalanceKind::InterimClosing {
+            "62M"
+        } else {
+            "62F"
+        };
+        writeln!(writer, ":{}:{}", closing_tag, mt940_balance(&closing)?)?;
+        for balance in &st.available_balances {
+            let tag = if balance.kind == BalanceKind::ForwardAvailable {
+                "65"
             } else {
-                ("00", "01", "01")
+                "64"
             };
+            writeln!(writer, ":{}:{}", tag, mt940_balance(balance)?)?;
+        }
+        // Конец текстового блока сообщения, как `-}` в SWIFT-конверте.
+        writeln!(writer, "-")?;
+    }
+    Ok(())
+}
+
+pub struct Mt940Format;
+impl ToFormat for Mt940Format {
+    fn from_statements<W: Write>(statements: &[Statement], _options: &ConvertOptions, writer: W) -> Result<(), ConvertError> {
+        write_mt940_statements(statements, true, writer)
+    }
+}
 
-            let sign = if tx.amount < 0.0 { "D" } else { "C" };
-            writeln!(writer, ":61:{}{}{}{}{:.2}NMSCNONREF", yy, mm, dd, sign, tx.amount)?;
+/// Выписка MT950: поля MT940 без `:86:`.
+pub struct Mt950Format;
+impl ToFormat for Mt950Format {
+    fn from_statements<W: Write>(statements: &[Statement], _options: &ConvertOptions, writer: W) -> Result<(), ConvertError> {
+        write_mt940_statements(statements, false, writer)
+    }
+}
 
-            if !tx.description.is_empty() {
-                writeln!(writer, ":86:{}", tx.description)?;
+/// Промежуточный отчет MT942: операции без сальдо, итоги `:90D:`/`:90C:`.
+///
+/// Без порогов [`Statement::debit_floor_limit`]/[`Statement::credit_floor_limit`]
+/// пишется нулевой `:34F:` — в отчет попадают все операции.
+pub struct Mt942Format;
+impl ToFormat for Mt942Format {
+    fn from_statements<W: Write>(statements: &[Statement], _options: &ConvertOptions, mut writer: W) -> Result<(), ConvertError> {
+        for st in statements {
+            let (_, closing) = st.balances()?;
+            let currency = closing.currency;
+            let zero = Money::zero(currency_exponent(&currency));
+            let created_at = st.created_at.unwrap_or_else(|| Utc::now().naive_utc());
+            writeln!(writer, ":20:{}", st.id)?;
+            writeln!(writer, ":25:{}", st.account)?;
+            writeln!(writer, ":28C:{}", st.sequence_number.as_deref().unwrap_or("1"))?;
+            let limit = |limit: Option<Money>| limit.unwrap_or(zero).format_with_separator(',');
+            if st.debit_floor_limit == st.credit_floor_limit {
+                writeln!(writer, ":34F:{}{}", currency, limit(st.debit_floor_limit)?)?;
+            } else {
+                writeln!(writer, ":34F:{}D{}", currency, limit(st.debit_floor_limit)?)?;
+                writeln!(writer, ":34F:{}C{}", currency, limit(st.credit_floor_limit)?)?;
+            }
+            // Часовой пояс в модели не хранится.
+            writeln!(writer, ":13D:{}+0000", created_at.format("%y%m%d%H%M"))?;
+            for tx in &st.entries {
+                write_mt940_entry(&mut writer, tx, true)?;
             }
-            writeln!(writer)?;
+            let (debits, credits): (Vec<&Transaction>, Vec<&Transaction>) = st.entries.iter().partition(|tx| tx.amount.is_negative());
+            for (tag, entries) in [("90D", debits), ("90C", credits)] {
+                let amounts = entries.iter().map(|tx| tx.amount.abs());
+                let sum = Money::checked_sum(std::iter::once(zero).chain(amounts)).ok_or_else(overflow)?;
+                writeln!(writer, ":{}:{}{}{}", tag, entries.len(), currency, sum.format_with_separator(',')?)?;
+            }
+            writeln!(writer, "-")?;
         }
         Ok(())
     }
 }
 
+/// camt.053 версии [`ConvertOptions::camt_version`].
 pub struct Camt053Format;
 impl ToFormat for Camt053Format {
-    fn from_transactions<W: Write>(txs: &[Transaction], mut writer: W) -> std::io::Result<()> {
-        if txs.is_empty() {
-            write!(
-                writer,
-                r#"<Document xmlns="urn:iso:std:iso:20022:tech:xsd:camt.053.001.02"></Document>"#
-            )?;
-            return Ok(());
-        }
+    fn from_statements<W: Write>(statements: &[Statement], options: &ConvertOptions, writer: W) -> Result<(), ConvertError> {
+        camt::write_document(statements, CamtMessage::Statement, options.camt_version, writer)
+    }
+}
 
-        let statement_id = &txs[0].reference;
-        let account_id = &txs[0].account;
-
-        writeln!(
-            writer,
-            r#"
-            <Document xmlns="urn:iso:std:iso:20022:tech:xsd:camt.053.001.02">
-            <BkToCstmrStmt>
-            <Stmt>
-            <Id>{}</Id>
-            <Acct>
-                <Id>{}</Id>
-            </Acct>
-            "#,
-            statement_id, account_id
-        )?;
-
-        for tx in txs {
-            writeln!(
-                writer,
-                r#"
-                <Ntry>
-                    <Amt Ccy="{}">{}</Amt>
-                    <RvslInd>{}</RvslInd>
-                    <AddtlNtryInf>{}</AddtlNtryInf>
-                </Ntry>
-                "#,
-                tx.currency,
-                tx.amount.abs(),
-                if tx.amount < 0.0 { "true" } else { "false" },
-                tx.description
-            )?;
-        }
+/// Внутридневной отчет camt.052 версии [`ConvertOptions::camt_version`].
+pub struct Camt052Format;
+impl ToFormat for Camt052Format {
+    fn from_statements<W: Write>(statements: &[Statement], options: &ConvertOptions, writer: W) -> Result<(), ConvertError> {
+        camt::write_document(statements, CamtMessage::Report, options.camt_version, writer)
+    }
+}
 
-        writeln!(
-            writer,
-            r#"
-            </Stmt>
-            </BkToCstmrStmt>
-            </Document>
-            "#
-        )?;
+/// Уведомления camt.054 версии [`ConvertOptions::camt_version`]; сальдо не пишутся.
+pub struct Camt054Format;
+impl ToFormat for Camt054Format {
+    fn from_statements<W: Write>(statements: &[Statement], options: &ConvertOptions, writer: W) -> Result<(), ConvertError> {
+        camt::write_document(statements, CamtMessage::Notification, options.camt_version, writer)
+    }
+}
 
-        Ok(())
+/// OFX версии [`ConvertOptions::ofx_version`].
+pub struct OfxFormat;
+impl ToFormat for OfxFormat {
+    fn from_statements<W: Write>(statements: &[Statement], options: &ConvertOptions, writer: W) -> Result<(), ConvertError> {
+        ofx::write_document(statements, options.ofx_version, writer)
+    }
+}
+
+/// QIF с датами в формате [`ConvertOptions::qif_date_format`].
+pub struct QifFormat;
+impl ToFormat for QifFormat {
+    fn from_statements<W: Write>(statements: &[Statement], options: &ConvertOptions, writer: W) -> Result<(), ConvertError> {
+        qif::write_file(statements, options.qif_date_format, writer)
+    }
+}
+
+/// BAI2 с контрольными суммами в трейлерах `49`/`98`/`99`.
+pub struct Bai2Format;
+impl ToFormat for Bai2Format {
+    fn from_statements<W: Write>(statements: &[Statement], _options: &ConvertOptions, writer: W) -> Result<(), ConvertError> {
+        bai2::write_file(statements, writer)
+    }
+}
+
+/// CODA (Бельгия): записи по 128 символов в Windows-1252.
+pub struct CodaFormat;
+impl ToFormat for CodaFormat {
+    fn from_statements<W: Write>(statements: &[Statement], _options: &ConvertOptions, writer: W) -> Result<(), ConvertError> {
+        coda::write_file(statements, writer)
+    }
+}
+
+/// JSON-документ по схеме [`crate::schema`] версии [`crate::SCHEMA_VERSION`].
+pub struct JsonFormat;
+impl ToFormat for JsonFormat {
+    fn from_statements<W: Write>(statements: &[Statement], _options: &ConvertOptions, writer: W) -> Result<(), ConvertError> {
+        json::write_document(statements, writer)
+    }
+}
+
+/// JSON Lines (NDJSON): по транзакции на строку, можно читать потоком.
+pub struct JsonLinesFormat;
+impl ToFormat for JsonLinesFormat {
+    fn from_statements<W: Write>(statements: &[Statement], _options: &ConvertOptions, writer: W) -> Result<(), ConvertError> {
+        json::write_lines(statements, writer)
+    }
+}
+
+/// Excel `.xlsx`: суммы числами с форматом валюты, даты — ячейками-датами.
+pub struct XlsxFormat;
+impl ToFormat for XlsxFormat {
+    fn from_statements<W: Write>(statements: &[Statement], _options: &ConvertOptions, writer: W) -> Result<(), ConvertError> {
+        xlsx::write_file(statements, writer)
+    }
+}
+
+/// Apache Parquet: колонки [`crate::transaction_schema`], по группе строк на выписку.
+pub struct ParquetFormat;
+impl ToFormat for ParquetFormat {
+    fn from_statements<W: Write>(statements: &[Statement], _options: &ConvertOptions, writer: W) -> Result<(), ConvertError> {
+        parquet::write_file(statements, writer)
+    }
+}
+
+/// Журнал ledger, читается и hledger; счета по [`ConvertOptions::ledger_rules`].
+pub struct LedgerFormat;
+impl ToFormat for LedgerFormat {
+    fn from_statements<W: Write>(statements: &[Statement], options: &ConvertOptions, writer: W) -> Result<(), ConvertError> {
+        ledger::write_ledger(statements, &options.ledger_rules, writer)
+    }
+}
+
+/// Файл beancount; счета по [`ConvertOptions::ledger_rules`].
+pub struct BeancountFormat;
+impl ToFormat for BeancountFormat {
+    fn from_statements<W: Write>(statements: &[Statement], options: &ConvertOptions, writer: W) -> Result<(), ConvertError> {
+        ledger::write_beancount(statements, &options.ledger_rules, writer)
+    }
+}
+
+/// Платежные поручения SEPA pain.001 версии [`ConvertOptions::pain_version`]
+/// от плательщика [`ConvertOptions::sepa_debtor`].
+pub struct Pain001Format;
+impl ToFormat for Pain001Format {
+    fn from_statements<W: Write>(statements: &[Statement], options: &ConvertOptions, writer: W) -> Result<(), ConvertError> {
+        pain::write_document(statements, options.pain_version, &options.sepa_debtor, writer)
+    }
+}
+
+/// Файл обмена 1С `1CClientBankExchange` в кодировке Windows-1251.
+pub struct ClientBank1CFormat;
+impl ToFormat for ClientBank1CFormat {
+    fn from_statements<W: Write>(statements: &[Statement], _options: &ConvertOptions, writer: W) -> Result<(), ConvertError> {
+        client_bank::write_file(statements, writer)
     }
 }
 
@@ -128,9 +377,21 @@ impl Default for Transaction {
         Self {
             reference: "DEFAULT".to_string(),
             account: "DEFAULT".to_string(),
-            amount: 0.0,
+            amount: Money::zero(2),
             currency: "XXX".to_string(),
-            value_date: "1970-01-01".to_string(),
+            value_date: NaiveDate::default(),
+            booking_date: None,
+            customer_reference: None,
+            bank_reference: None,
+            reversal: false,
+            funds_code: None,
+            transaction_type: None,
+            supplementary_details: None,
+            counterparty: None,
+            remittance_information: None,
+            gvc: None,
+            category: None,
+            splits: Vec::new(),
             description: "Default".to_string(),
         }
     }
@@ -140,24 +401,63 @@ impl Default for Transaction {
 mod tests {
     use super::*;
     use parser::Parser;
+
+    #[test]
+    fn csv_never_quoting_and_unmappable_characters_are_errors() {
+        let tx = Transaction {
+            reference: "R1".to_string(),
+            account: "ACC".to_string(),
+            amount: Money::new(500, 2),
+            currency: "EUR".to_string(),
+            value_date: NaiveDate::from_ymd_opt(2023, 1, 2).unwrap(),
+            ..Default::default()
+        };
+        let write = |dialect: CsvDialect, description: &str| {
+            let tx = Transaction {
+                description: description.to_string(),
+                ..tx.clone()
+            };
+            let options = ConvertOptions {
+                csv_dialect: dialect,
+                ..Default::default()
+            };
+            CsvFormat::from_statements(&[Statement::from_entries(vec![tx])], &options, Vec::new())
+        };
+        let never = CsvDialect {
+            quoting: CsvQuoting::Never,
+            ..Default::default()
+        };
+        assert!(write(never.clone(), "Аренда офис").is_ok());
+        assert!(matches!(write(never.clone(), "Аренда, офис"), Err(ConvertError::Parse(_))));
+        assert!(matches!(write(never, "\"Ромашка\""), Err(ConvertError::Parse(_))));
+
+        let cp1251 = CsvDialect {
+            encoding: CsvDialect::encoding_for_label("windows-1251"),
+            ..Default::default()
+        };
+        let error = write(cp1251, "Müller GmbH").unwrap_err().to_string();
+        assert!(error.contains("U+00FC"), "{}", error);
+    }
     #[test]
     fn test_multiple_transactions_to_camt053() {
         let txs = vec![
             Transaction {
                 reference: "STMT1".to_string(),
                 account: "ACC1".to_string(),
-                amount: -100.5,
+                amount: Money::new(-10050, 2),
                 currency: "USD".to_string(),
-                value_date: "2023-01-01".to_string(),
+                value_date: NaiveDate::from_ymd_opt(2023, 1, 1).unwrap(),
                 description: "Debit".to_string(),
+                ..Default::default()
             },
             Transaction {
                 reference: "STMT1".to_string(),
                 account: "ACC1".to_string(),
-                amount: 200.0,
+                amount: Money::new(20000, 2),
                 currency: "USD".to_string(),
-                value_date: "2023-01-02".to_string(),
+                value_date: NaiveDate::from_ymd_opt(2023, 1, 2).unwrap(),
                 description: "Credit".to_string(),
+                ..Default::default()
             },
         ];
         let mut buffer = Vec::new();
@@ -169,15 +469,46 @@ mod tests {
         assert!(output.contains("200"));
     }
 
+    #[test]
+    fn mt940_lines_keeps_breaks_and_wraps() {
+        let long = "x".repeat(70);
+        assert_eq!(
+            mt940_lines(&format!("a\n{}", long), 65, 6),
+            vec!["a".to_string(), "x".repeat(65), "x".repeat(5)]
+        );
+        assert!(mt940_lines("", 65, 6).is_empty());
+        assert_eq!(mt940_lines("a\n\n  b\n\n", 65, 6), vec!["a", "", "  b"]);
+        assert_eq!(mt940_lines(&"y\n".repeat(10), 65, 6).len(), 6);
+    }
+
+    #[test]
+    fn mt940_narrative_round_trip_keeps_layout() {
+        let tx = Transaction {
+            reference: "STMT1".to_string(),
+            account: "ACC1".to_string(),
+            amount: Money::new(-10050, 2),
+            currency: "EUR".to_string(),
+            value_date: NaiveDate::from_ymd_opt(2023, 1, 1).unwrap(),
+            description: "Invoice 7\n\n  indented line".to_string(),
+            ..Default::default()
+        };
+        let mut buffer = Vec::new();
+        Mt940Format::from_transactions(&[tx], &mut buffer).unwrap();
+        let parser = parser::Mt940Parser::parse(&buffer[..]).unwrap();
+        let statements = crate::FromParser::to_statements(&parser, &ConvertOptions::default()).unwrap();
+        assert_eq!(statements[0].entries[0].description, "Invoice 7\n\n  indented line");
+    }
+
     #[test]
     fn test_csv_format_with_special_chars() {
         let txs = vec![Transaction {
             reference: "REF,001".to_string(), // содержит запятую
             account: "ACC\"123".to_string(),  // содержит кавычку
-            amount: 100.5,
+            amount: Money::new(10050, 2),
             currency: "USD".to_string(),
-            value_date: "2023-01-01".to_string(),
+            value_date: NaiveDate::from_ymd_opt(2023, 1, 1).unwrap(),
             description: "Line1\nLine2".to_string(), // содержит \n -> должно экранировать
+            ..Default::default()
         }];
 
         let mut buffer = Vec::new();
@@ -0,0 +1,297 @@
+//! Запись выписок в BAI2 с контрольными суммами и счетчиками записей в трейлерах.
+//!
+//! Выписки с одинаковыми датой и валютой попадают в одну группу `02`.
+
+use std::io::Write;
+
+use chrono::{NaiveDate, Utc};
+
+use crate::{
+    error::ConvertError,
+    from_parser::bai2::is_debit_code,
+    model::{BalanceKind, Statement, Transaction},
+    money::{Money, currency_exponent},
+};
+
+/// Длина физической записи; длинные записи продолжаются в `88`.
+const RECORD_LENGTH: usize = 80;
+
+/// Коды прочих кредитов и дебетов для операций без кода BAI2.
+const MISC_CREDIT: &str = "399";
+const MISC_DEBIT: &str = "699";
+
+fn date(date: NaiveDate) -> String {
+    date.format("%y%m%d").to_string()
+}
+
+/// Поле записи: запятые и `/` разорвали бы поле.
+fn clean(value: &str) -> String {
+    value.replace([',', '/', '\r', '\n'], " ").trim().to_string()
+}
+
+/// Сумма в минимальных единицах валюты без разделителя: знаки после запятой в BAI2
+/// подразумеваются по валюте, поэтому сумма приводится к [`currency_exponent`].
+fn minor_units(money: Money, exponent: u8) -> Result<i64, ConvertError> {
+    money
+        .rescale(exponent)
+        .map(|m| m.minor_units())
+        .ok_or_else(|| ConvertError::InvalidAmount(format!("{} (BAI2: больше {} знаков после запятой)", money, exponent)))
+}
+
+fn overflow() -> ConvertError {
+    ConvertError::InvalidAmount("BAI2: переполнение контрольной суммы".to_string())
+}
+
+/// Сложение контрольных сумм без переполнения.
+fn add(total: i64, value: i64) -> Result<i64, ConvertError> {
+    total.checked_add(value).ok_or_else(overflow)
+}
+
+/// Физические записи с учетом продолжений `88`.
+///
+/// Поля переносятся целиком, строка с полями завершается `/`.
+/// Текст `16` переносится по словам и `/` не завершается.
+fn physical_records(code: &str, fields: &[String], text: Option<&str>) -> Vec<String> {
+    let mut lines = vec![code.to_string()];
+    for field in fields {
+        let current = lines.last_mut().expect("всегда есть строка");
+        if current.len() + field.len() + 2 > RECORD_LENGTH {
+            current.push('/');
+            lines.push(format!("88,{}", field));
+        } else {
+            current.push(',');
+            current.push_str(field);
+        }
+    }
+    match text {
+        None => lines.last_mut().expect("всегда есть строка").push('/'),
+        Some(text) => {
+            lines.last_mut().expect("всегда есть строка").push(',');
+            let mut empty = true;
+            for word in text.split_whitespace() {
+                let current = lines.last_mut().expect("всегда есть строка");
+                if empty {
+                    current.push_str(word);
+                    empty = false;
+                } else if current.len() + 1 + word.len() > RECORD_LENGTH {
+                    lines.push(format!("88,{}", word));
+                } else {
+                    current.push(' ');
+                    current.push_str(word);
+                }
+            }
+            if empty {
+                lines.last_mut().expect("всегда есть строка").push('/');
+            }
+        }
+    }
+    lines
+}
+
+/// Код BAI2 операции: из [`Transaction::gvc`], если это код нужного знака, иначе прочий кредит/дебет.
+fn type_code(tx: &Transaction) -> &str {
+    let debit = tx.amount.is_negative();
+    tx.gvc
+        .as_deref()
+        .filter(|code| code.len() == 3 && code.bytes().all(|b| b.is_ascii_digit()))
+        .filter(|code| is_debit_code(code) == debit)
+        .unwrap_or(if debit { MISC_DEBIT } else { MISC_CREDIT })
+}
+
+/// Записи одного счета (`03`, `16`, `49`) и его контрольная сумма.
+fn account_records(statement: &Statement) -> Result<(Vec<String>, i64), ConvertError> {
+    let (opening, closing) = statement.balances()?;
+    let exponent = currency_exponent(&closing.currency);
+    let mut total = 0;
+    let mut fields = vec![clean(&statement.account), closing.currency.clone()];
+    let mut push_group = |code: &str, units: i64, count: Option<usize>, fields: &mut Vec<String>| -> Result<(), ConvertError> {
+        total = add(total, units)?;
+        fields.extend([
+            code.to_string(),
+            units.to_string(),
+            count.map(|c| c.to_string()).unwrap_or_default(),
+            String::new(),
+        ]);
+        Ok(())
+    };
+    push_group("010", minor_units(opening.amount, exponent)?, None, &mut fields)?;
+    push_group("015", minor_units(closing.amount, exponent)?, None, &mut fields)?;
+    for balance in statement
+        .available_balances
+        .iter()
+        .filter(|b| b.kind == BalanceKind::ClosingAvailable)
+    {
+        push_group("045", minor_units(balance.amount, exponent)?, None, &mut fields)?;
+    }
+    let details = statement
+        .entries
+        .iter()
+        .map(|tx| Ok((tx, minor_units(tx.amount, exponent)?)))
+        .collect::<Result<Vec<_>, ConvertError>>()?;
+    let (mut credit_total, mut debit_total, mut credits) = (0i64, 0i64, 0);
+    for (_, units) in &details {
+        if *units < 0 {
+            debit_total = add(debit_total, units.checked_abs().ok_or_else(overflow)?)?;
+        } else {
+            credit_total = add(credit_total, *units)?;
+            credits += 1;
+        }
+    }
+    push_group("100", credit_total, Some(credits), &mut fields)?;
+    push_group("400", debit_total, Some(details.len() - credits), &mut fields)?;
+    for summary in statement
+        .summaries
+        .iter()
+        .filter(|s| !matches!(s.type_code.as_str(), "100" | "400"))
+    {
+        let count = summary.item_count.map(|c| c as usize);
+        push_group(&summary.type_code, minor_units(summary.amount, exponent)?, count, &mut fields)?;
+    }
+    let mut records = physical_records("03", &fields, None);
+
+    for (tx, units) in details {
+        let units = units.checked_abs().ok_or_else(overflow)?;
+        total = add(total, units)?;
+        let detail = [
+            type_code(tx).to_string(),
+            units.to_string(),
+            "Z".to_string(),
+            tx.bank_reference.as_deref().map(clean).unwrap_or_default(),
+            tx.customer_reference.as_deref().map(clean).unwrap_or_default(),
+        ];
+        let text = tx.remittance_information.as_deref().unwrap_or(&tx.description);
+        records.extend(physical_records("16", &detail, Some(text)));
+    }
+    let count = records.len() + 1;
+    records.push(format!("49,{},{}/", total, count));
+    Ok((records, total))
+}
+
+/// Записывает файл: `01`, группы `02`…`98` по дате и валюте, `99`.
+pub(crate) fn write_file<W: Write>(statements: &[Statement], mut writer: W) -> Result<(), ConvertError> {
+    let created_at = statements
+        .iter()
+        .find_map(|st| st.created_at)
+        .unwrap_or_else(|| Utc::now().naive_utc());
+    let file_id = statements.first().map(|st| clean(&st.id)).filter(|id| !id.is_empty());
+    let sender = statements.iter().find_map(|st| st.sender_bic.clone()).unwrap_or_default();
+    let receiver = statements.iter().find_map(|st| st.receiver_bic.clone()).unwrap_or_default();
+
+    let mut groups: Vec<((NaiveDate, String), Vec<&Statement>)> = Vec::new();
+    for statement in statements {
+        let (_, closing) = statement.balances()?;
+        let key = (closing.date, closing.currency);
+        match groups.iter_mut().find(|(k, _)| *k == key) {
+            Some((_, members)) => members.push(statement),
+            None => groups.push((key, vec![statement])),
+        }
+    }
+
+    let mut lines = vec![format!(
+        "01,{},{},{},{},{},,,2/",
+        sender,
+        receiver,
+        date(created_at.date()),
+        created_at.format("%H%M"),
+        file_id.unwrap_or_else(|| "1".to_string())
+    )];
+    let mut file_total = 0;
+    for ((as_of, currency), members) in &groups {
+        let mut group = vec![format!("02,{},{},1,{},,{},/", receiver, sender, date(*as_of), currency)];
+        let mut group_total = 0;
+        for statement in members {
+            let (records, total) = account_records(statement)?;
+            group.extend(records);
+            group_total = add(group_total, total)?;
+        }
+        let count = group.len() + 1;
+        group.push(format!("98,{},{},{}/", group_total, members.len(), count));
+        lines.extend(group);
+        file_total = add(file_total, group_total)?;
+    }
+    let count = lines.len() + 1;
+    lines.push(format!("99,{},{},{}/", file_total, groups.len(), count));
+
+    for line in lines {
+        writeln!(writer, "{}", line)?;
+    }
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use parser::{Bai2Parser, Parser};
+
+    use super::*;
+    use crate::{FromParser, model::Balance, options::ConvertOptions};
+
+    fn statement(account: &str) -> Statement {
+        let tx = |amount: i64, gvc: Option<&str>, description: &str| Transaction {
+            reference: "FILE7".to_string(),
+            account: account.to_string(),
+            amount: Money::new(amount, 2),
+            currency: "USD".to_string(),
+            value_date: NaiveDate::from_ymd_opt(2023, 1, 31).unwrap(),
+            bank_reference: Some("BR".to_string()),
+            gvc: gvc.map(str::to_string),
+            description: description.to_string(),
+            ..Default::default()
+        };
+        let mut statement = Statement::from_entries(vec![
+            tx(50000, Some("195"), "Incoming wire, ACME Corp"),
+            tx(-2500, Some("195"), &"Long fee description ".repeat(6)),
+        ]);
+        statement.opening_balance = Some(Balance {
+            kind: BalanceKind::Opening,
+            amount: Money::new(-1000, 2),
+            currency: "USD".to_string(),
+            date: NaiveDate::from_ymd_opt(2023, 1, 31).unwrap(),
+        });
+        statement
+    }
+
+    #[test]
+    fn trailers_match_parser_checks() {
+        let statements = [statement("111"), statement("222")];
+        let mut buffer = Vec::new();
+        write_file(&statements, &mut buffer).unwrap();
+        let text = String::from_utf8(buffer.clone()).unwrap();
+        assert!(text.lines().all(|l| l.len() <= RECORD_LENGTH), "{}", text);
+        assert_eq!(text.matches("\n02,").count(), 1, "{}", text);
+        assert!(text.contains("\n88,"), "{}", text);
+
+        let parser = Bai2Parser::parse(&buffer[..]).unwrap();
+        let parsed = parser.to_statements(&ConvertOptions::default()).unwrap();
+        assert_eq!(parsed.len(), 2);
+        let st = &parsed[1];
+        assert_eq!(st.account, "222");
+        assert_eq!(st.opening_balance.as_ref().unwrap().amount, Money::new(-1000, 2));
+        assert_eq!(st.closing_balance.as_ref().unwrap().amount, Money::new(46500, 2));
+        assert_eq!(st.summaries[1].type_code, "400");
+        assert_eq!(st.summaries[1].item_count, Some(1));
+        assert_eq!(st.entries[0].gvc.as_deref(), Some("195"));
+        assert_eq!(st.entries[0].description, "Incoming wire, ACME Corp");
+        assert_eq!(st.entries[1].gvc.as_deref(), Some(MISC_DEBIT));
+        assert_eq!(st.entries[1].amount, Money::new(-2500, 2));
+        assert_eq!(st.entries[1].description, "Long fee description ".repeat(6).trim());
+    }
+
+    #[test]
+    fn amounts_are_scaled_to_currency_exponent() {
+        let mut statement = statement("333");
+        statement.entries[0].amount = Money::new(55, 1);
+        statement.entries[1].amount = Money::new(-7, 0);
+        statement.opening_balance.as_mut().unwrap().amount = Money::new(0, 0);
+        let mut buffer = Vec::new();
+        write_file(&[statement.clone()], &mut buffer).unwrap();
+        let parser = Bai2Parser::parse(&buffer[..]).unwrap();
+        let parsed = parser.to_statements(&ConvertOptions::default()).unwrap();
+        assert_eq!(parsed[0].entries[0].amount, Money::new(550, 2));
+        assert_eq!(parsed[0].entries[1].amount, Money::new(-700, 2));
+        assert_eq!(parsed[0].closing_balance.as_ref().unwrap().amount, Money::new(-150, 2));
+
+        statement.entries[0].amount = Money::new(5001, 3);
+        let result = write_file(&[statement], &mut Vec::new());
+        assert!(matches!(result, Err(ConvertError::InvalidAmount(_))));
+    }
+}
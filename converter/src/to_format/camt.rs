@@ -0,0 +1,462 @@
+//! Запись выписок ISO 20022 camt.052, camt.053 и camt.054 через [`quick_xml::Writer`].
+//!
+//! Порядок элементов соответствует XSD `camt.05x.001.02` — `001.13`: любая
+//! перестановка делает документ невалидным для банковских систем импорта.
+
+use std::io::{self, Write};
+
+use chrono::{NaiveDateTime, Utc};
+use quick_xml::{Writer, events::BytesDecl, events::BytesText, events::Event};
+
+use crate::{
+    error::ConvertError,
+    from_parser::camt::CamtMessage,
+    model::{Balance, BalanceKind, Counterparty, Statement, Transaction},
+    options::CamtVersion,
+};
+
+/// `Max35Text` и подобные ограничения длины в XSD.
+pub(super) fn truncate(text: &str, max_chars: usize) -> &str {
+    text.char_indices().nth(max_chars).map_or(text, |(pos, _)| &text[..pos])
+}
+
+/// Делит текст на строки не длиннее `max_chars` по границам слов (для `Ustrd`, `Max140Text`).
+fn wrap(text: &str, max_chars: usize) -> Vec<String> {
+    let mut lines: Vec<String> = Vec::new();
+    let mut current = String::new();
+    for word in text.split_whitespace() {
+        let mut word = word;
+        // Слово длиннее строки режем принудительно.
+        while word.chars().count() > max_chars {
+            if !current.is_empty() {
+                lines.push(std::mem::take(&mut current));
+            }
+            let head = truncate(word, max_chars);
+            lines.push(head.to_string());
+            word = &word[head.len()..];
+        }
+        let needed = current.chars().count() + usize::from(!current.is_empty()) + word.chars().count();
+        if needed > max_chars {
+            lines.push(std::mem::take(&mut current));
+        }
+        if !current.is_empty() {
+            current.push(' ');
+        }
+        current.push_str(word);
+    }
+    if !current.is_empty() {
+        lines.push(current);
+    }
+    lines
+}
+
+/// IBAN по маске XSD: `[A-Z]{2}[0-9]{2}[a-zA-Z0-9]{1,30}`.
+fn is_iban(account: &str) -> bool {
+    let bytes = account.as_bytes();
+    (5..=34).contains(&bytes.len())
+        && bytes[..2].iter().all(u8::is_ascii_uppercase)
+        && bytes[2..4].iter().all(u8::is_ascii_digit)
+        && bytes[4..].iter().all(u8::is_ascii_alphanumeric)
+}
+
+/// `ElctrncSeqNb` — число; из `:28C:` вида `00042/001` берем номер выписки.
+fn sequence_number(statement: &Statement) -> Option<u64> {
+    statement.sequence_number.as_deref()?.split('/').next()?.parse().ok()
+}
+
+fn balance_code(kind: BalanceKind) -> &'static str {
+    match kind {
+        BalanceKind::Opening => "OPBD",
+        BalanceKind::InterimOpening => "PRCD",
+        BalanceKind::Closing => "CLBD",
+        BalanceKind::InterimClosing => "ITBD",
+        BalanceKind::ClosingAvailable => "CLAV",
+        BalanceKind::ForwardAvailable => "FWAV",
+    }
+}
+
+fn credit_debit(negative: bool) -> &'static str {
+    if negative { "DBIT" } else { "CRDT" }
+}
+
+pub(super) fn text_element<W: Write>(w: &mut Writer<W>, name: &str, text: &str) -> io::Result<()> {
+    w.create_element(name).write_text_content(BytesText::new(text))?;
+    Ok(())
+}
+
+fn date_element<W: Write>(w: &mut Writer<W>, name: &str, date: chrono::NaiveDate) -> io::Result<()> {
+    w.create_element(name)
+        .write_inner_content(|w| text_element(w, "Dt", &date.format("%Y-%m-%d").to_string()))?;
+    Ok(())
+}
+
+fn write_balance<W: Write>(w: &mut Writer<W>, balance: &Balance) -> io::Result<()> {
+    w.create_element("Bal").write_inner_content(|w| {
+        w.create_element("Tp").write_inner_content(|w| {
+            w.create_element("CdOrPrtry")
+                .write_inner_content(|w| text_element(w, "Cd", balance_code(balance.kind)))?;
+            Ok(())
+        })?;
+        w.create_element("Amt")
+            .with_attribute(("Ccy", balance.currency.as_str()))
+            .write_text_content(BytesText::new(&balance.amount.abs().to_string()))?;
+        text_element(w, "CdtDbtInd", credit_debit(balance.amount.is_negative()))?;
+        date_element(w, "Dt", balance.date)
+    })?;
+    Ok(())
+}
+
+fn account_element<W: Write>(w: &mut Writer<W>, name: &str, account: &str) -> io::Result<()> {
+    w.create_element(name).write_inner_content(|w| {
+        w.create_element("Id").write_inner_content(|w| {
+            if is_iban(account) {
+                text_element(w, "IBAN", account)
+            } else {
+                w.create_element("Othr")
+                    .write_inner_content(|w| text_element(w, "Id", truncate(account, 34)))?;
+                Ok(())
+            }
+        })?;
+        Ok(())
+    })?;
+    Ok(())
+}
+
+/// `RltdPties` и `RltdAgts`: контрагент — плательщик (`Dbtr`) для кредита, получатель (`Cdtr`) для дебета.
+fn write_counterparty<W: Write>(w: &mut Writer<W>, counterparty: &Counterparty, debit: bool, version: CamtVersion) -> io::Result<()> {
+    let (party, account, agent) = if debit {
+        ("Cdtr", "CdtrAcct", "CdtrAgt")
+    } else {
+        ("Dbtr", "DbtrAcct", "DbtrAgt")
+    };
+    if counterparty.name.is_some() || counterparty.account.is_some() {
+        w.create_element("RltdPties").write_inner_content(|w| {
+            if let Some(name) = &counterparty.name {
+                w.create_element(party).write_inner_content(|w| {
+                    if version.party_choice() {
+                        w.create_element("Pty")
+                            .write_inner_content(|w| text_element(w, "Nm", truncate(name, 140)))?;
+                        Ok(())
+                    } else {
+                        text_element(w, "Nm", truncate(name, 140))
+                    }
+                })?;
+            }
+            if let Some(number) = &counterparty.account {
+                account_element(w, account, number)?;
+            }
+            Ok(())
+        })?;
+    }
+    if let Some(bic) = &counterparty.bic {
+        w.create_element("RltdAgts").write_inner_content(|w| {
+            w.create_element(agent).write_inner_content(|w| {
+                w.create_element("FinInstnId")
+                    .write_inner_content(|w| text_element(w, version.bic_element(), bic))?;
+                Ok(())
+            })?;
+            Ok(())
+        })?;
+    }
+    Ok(())
+}
+
+fn write_entry<W: Write>(w: &mut Writer<W>, tx: &Transaction, version: CamtVersion) -> io::Result<()> {
+    w.create_element("Ntry").write_inner_content(|w| {
+        w.create_element("Amt")
+            .with_attribute(("Ccy", tx.currency.as_str()))
+            .write_text_content(BytesText::new(&tx.amount.abs().to_string()))?;
+        text_element(w, "CdtDbtInd", credit_debit(tx.amount.is_negative()))?;
+        if tx.reversal {
+            text_element(w, "RvslInd", "true")?;
+        }
+        if version.structured_status() {
+            w.create_element("Sts").write_inner_content(|w| text_element(w, "Cd", "BOOK"))?;
+        } else {
+            text_element(w, "Sts", "BOOK")?;
+        }
+        date_element(w, "BookgDt", tx.booking_date.unwrap_or(tx.value_date))?;
+        date_element(w, "ValDt", tx.value_date)?;
+        if let Some(bank_reference) = &tx.bank_reference {
+            text_element(w, "AcctSvcrRef", truncate(bank_reference, 35))?;
+        }
+        w.create_element("BkTxCd").write_inner_content(|w| {
+            w.create_element("Prtry").write_inner_content(|w| {
+                text_element(w, "Cd", tx.transaction_type.as_deref().unwrap_or("NMSC"))?;
+                text_element(w, "Issr", "SWIFT")
+            })?;
+            Ok(())
+        })?;
+
+        let remittance = wrap(tx.remittance_information.as_deref().unwrap_or(&tx.description), 140);
+        let counterparty = tx.counterparty.as_ref().filter(|c| !c.is_empty());
+        if tx.customer_reference.is_some() || counterparty.is_some() || !remittance.is_empty() {
+            w.create_element("NtryDtls").write_inner_content(|w| {
+                w.create_element("TxDtls").write_inner_content(|w| {
+                    if let Some(reference) = &tx.customer_reference {
+                        w.create_element("Refs")
+                            .write_inner_content(|w| text_element(w, "EndToEndId", truncate(reference, 35)))?;
+                    }
+                    if let Some(counterparty) = counterparty {
+                        write_counterparty(w, counterparty, tx.amount.is_negative(), version)?;
+                    }
+                    if !remittance.is_empty() {
+                        w.create_element("RmtInf")
+                            .write_inner_content(|w| remittance.iter().try_for_each(|line| text_element(w, "Ustrd", line)))?;
+                    }
+                    Ok(())
+                })?;
+                Ok(())
+            })?;
+        }
+        Ok(())
+    })?;
+    Ok(())
+}
+
+/// Блок счета `Stmt`, `Rpt` или `Ntfctn`: состав полей у них общий, кроме сальдо.
+fn write_statement<W: Write>(
+    w: &mut Writer<W>,
+    (statement, (opening, closing)): (&Statement, &(Balance, Balance)),
+    created_at: NaiveDateTime,
+    message: CamtMessage,
+    version: CamtVersion,
+) -> io::Result<()> {
+    w.create_element(message.block()).write_inner_content(|w| {
+        text_element(w, "Id", truncate(&statement.id, 35))?;
+        if let Some(number) = sequence_number(statement) {
+            text_element(w, "ElctrncSeqNb", &number.to_string())?;
+        }
+        let created_at = statement.created_at.unwrap_or(created_at);
+        text_element(w, "CreDtTm", &created_at.format("%Y-%m-%dT%H:%M:%S").to_string())?;
+        w.create_element("Acct").write_inner_content(|w| {
+            w.create_element("Id").write_inner_content(|w| {
+                if is_iban(&statement.account) {
+                    text_element(w, "IBAN", &statement.account)
+                } else {
+                    w.create_element("Othr")
+                        .write_inner_content(|w| text_element(w, "Id", truncate(&statement.account, 34)))?;
+                    Ok(())
+                }
+            })?;
+            text_element(w, "Ccy", &opening.currency)
+        })?;
+        // В camt.053 сальдо обязательны и при отсутствии вычисляются, в camt.052 пишутся только
+        // известные, в camt.054 их нет.
+        let balances: Vec<&Balance> = match message {
+            CamtMessage::Statement => vec![opening, closing],
+            CamtMessage::Report => statement.opening_balance.iter().chain(&statement.closing_balance).collect(),
+            CamtMessage::Notification => Vec::new(),
+        };
+        if message != CamtMessage::Notification {
+            let available = statement.available_balances.iter();
+            balances
+                .into_iter()
+                .chain(available)
+                .try_for_each(|balance| write_balance(w, balance))?;
+        }
+        statement.entries.iter().try_for_each(|tx| write_entry(w, tx, version))
+    })?;
+    Ok(())
+}
+
+/// Записывает все выписки одним документом `message`: `BkToCstmrStmt`, `BkToCstmrAcctRpt` или `BkToCstmrDbtCdtNtfctn`.
+pub(crate) fn write_document<W: Write>(
+    statements: &[Statement],
+    message: CamtMessage,
+    version: CamtVersion,
+    writer: W,
+) -> Result<(), ConvertError> {
+    let created_at = statements
+        .iter()
+        .find_map(|st| st.created_at)
+        .unwrap_or_else(|| Utc::now().naive_utc());
+    let message_id = statements
+        .first()
+        .map(|st| st.id.as_str())
+        .filter(|id| !id.is_empty())
+        .unwrap_or("STMT");
+    let balances = statements.iter().map(Statement::balances).collect::<Result<Vec<_>, _>>()?;
+
+    let mut w = Writer::new_with_indent(writer, b' ', 2);
+    w.write_event(Event::Decl(BytesDecl::new("1.0", Some("UTF-8"), None)))?;
+    w.create_element("Document")
+        .with_attribute(("xmlns", version.namespace(message.name()).as_str()))
+        .write_inner_content(|w| {
+            w.create_element(message.root()).write_inner_content(|w| {
+                w.create_element("GrpHdr").write_inner_content(|w| {
+                    text_element(w, "MsgId", truncate(message_id, 35))?;
+                    text_element(w, "CreDtTm", &created_at.format("%Y-%m-%dT%H:%M:%S").to_string())
+                })?;
+                statements
+                    .iter()
+                    .zip(&balances)
+                    .try_for_each(|st| write_statement(w, st, created_at, message, version))
+            })?;
+            Ok(())
+        })?;
+    writeln!(w.get_mut())?;
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use chrono::NaiveDate;
+    use parser::{Camt052Parser, Camt053Parser, Camt054Parser, Parser};
+
+    use super::*;
+    use crate::{FromParser, money::Money, options::ConvertOptions};
+
+    fn statement() -> Statement {
+        let tx = Transaction {
+            reference: "STMT1".to_string(),
+            account: "DE89370400440532013000".to_string(),
+            amount: Money::new(-10010, 2),
+            currency: "EUR".to_string(),
+            value_date: NaiveDate::from_ymd_opt(2023, 1, 1).unwrap(),
+            booking_date: NaiveDate::from_ymd_opt(2023, 1, 2),
+            customer_reference: Some("E2E-1".to_string()),
+            bank_reference: Some("BANK-1".to_string()),
+            transaction_type: Some("NTRF".to_string()),
+            counterparty: Some(Counterparty {
+                name: Some("Max Mustermann".to_string()),
+                account: Some("DE02120300000000202051".to_string()),
+                bic: Some("BYLADEM1001".to_string()),
+                ..Default::default()
+            }),
+            description: "Tom & Jerry <script>".to_string(),
+            ..Default::default()
+        };
+        Statement {
+            sequence_number: Some("00042/001".to_string()),
+            created_at: NaiveDate::from_ymd_opt(2023, 1, 3).unwrap().and_hms_opt(8, 0, 0),
+            ..Statement::from_entries(vec![tx])
+        }
+    }
+
+    #[test]
+    fn document_has_mandatory_elements_and_escaping() {
+        let mut buffer = Vec::new();
+        write_document(&[statement()], CamtMessage::Statement, CamtVersion::V02, &mut buffer).unwrap();
+        let output = String::from_utf8(buffer).unwrap();
+        for element in [
+            "<GrpHdr>",
+            "<MsgId>STMT1</MsgId>",
+            "<ElctrncSeqNb>42</ElctrncSeqNb>",
+            "<Cd>OPBD</Cd>",
+            "<Cd>CLBD</Cd>",
+        ] {
+            assert!(output.contains(element), "нет {} в {}", element, output);
+        }
+        assert!(output.contains("<CdtDbtInd>DBIT</CdtDbtInd>"));
+        assert!(output.contains("<Sts>BOOK</Sts>"));
+        assert!(output.contains("<BkTxCd>"));
+        assert!(output.contains("<Cdtr>\n                <Nm>Max Mustermann</Nm>"), "{}", output);
+        assert!(output.contains("<CdtrAcct>"));
+        assert!(output.contains("Tom &amp; Jerry &lt;script&gt;"));
+        assert!(!output.contains("RvslInd"));
+    }
+
+    #[test]
+    fn document_round_trips_through_parser() {
+        let mut buffer = Vec::new();
+        write_document(&[statement()], CamtMessage::Statement, CamtVersion::V02, &mut buffer).unwrap();
+        let parser = Camt053Parser::parse(&buffer[..]).unwrap();
+        let parsed = parser.to_statements(&ConvertOptions::default()).unwrap();
+        let original = statement();
+        assert_eq!(parsed[0].entries, original.entries);
+        assert_eq!(parsed[0].created_at, original.created_at);
+        assert_eq!(parsed[0].closing_balance.as_ref().unwrap().amount, Money::new(-10010, 2));
+    }
+
+    #[test]
+    fn reversal_and_type_code_round_trip() {
+        let mut st = statement();
+        st.entries[0].reversal = true;
+        let mut buffer = Vec::new();
+        write_document(std::slice::from_ref(&st), CamtMessage::Statement, CamtVersion::V02, &mut buffer).unwrap();
+        let output = String::from_utf8(buffer).unwrap();
+        assert!(output.contains("<RvslInd>true</RvslInd>"));
+        assert!(output.contains("<Cd>NTRF</Cd>"));
+        let parsed = Camt053Parser::parse(output.as_bytes())
+            .unwrap()
+            .to_statements(&ConvertOptions::default())
+            .unwrap();
+        assert_eq!(parsed[0].entries, st.entries);
+    }
+
+    #[test]
+    fn wrap_splits_on_words() {
+        assert_eq!(wrap("aaa bbb ccc", 7), vec!["aaa bbb", "ccc"]);
+        assert_eq!(wrap("abcdefghij", 4), vec!["abcd", "efgh", "ij"]);
+        assert!(wrap("   ", 140).is_empty());
+    }
+
+    #[test]
+    fn iban_detection() {
+        assert!(is_iban("DE89370400440532013000"));
+        assert!(!is_iban("40702810900000000001"));
+        assert!(!is_iban("ACC1"));
+    }
+
+    #[test]
+    fn every_version_round_trips() {
+        for version in CamtVersion::ALL {
+            let mut buffer = Vec::new();
+            write_document(&[statement()], CamtMessage::Statement, version, &mut buffer).unwrap();
+            let output = String::from_utf8(buffer).unwrap();
+            assert!(output.contains(&version.namespace("camt.053")));
+            assert_eq!(output.contains("<Sts>BOOK</Sts>"), version < CamtVersion::V08, "{}", version);
+            assert_eq!(output.contains("<Pty>"), version >= CamtVersion::V07, "{}", version);
+            assert_eq!(output.contains("<BICFI>"), version >= CamtVersion::V04, "{}", version);
+
+            let parser = Camt053Parser::parse(output.as_bytes()).unwrap();
+            let parsed = parser.to_statements(&ConvertOptions::default()).unwrap();
+            assert_eq!(parsed[0].entries, statement().entries, "{}", version);
+        }
+    }
+
+    #[test]
+    fn report_and_notification_round_trip() {
+        let mut buffer = Vec::new();
+        write_document(&[statement()], CamtMessage::Report, CamtVersion::V08, &mut buffer).unwrap();
+        let output = String::from_utf8(buffer.clone()).unwrap();
+        assert!(output.contains("camt.052.001.08"));
+        assert!(output.contains("<BkToCstmrAcctRpt>") && output.contains("<Rpt>"), "{}", output);
+        let parsed = Camt052Parser::parse(&buffer[..])
+            .unwrap()
+            .to_statements(&ConvertOptions::default())
+            .unwrap();
+        assert_eq!(parsed[0].entries, statement().entries);
+        assert!(!output.contains("<Bal>"), "сальдо отчета не вычисляются: {}", output);
+
+        let mut st = statement();
+        st.closing_balance = Some(Balance {
+            kind: BalanceKind::InterimClosing,
+            amount: Money::new(-10010, 2),
+            currency: "EUR".to_string(),
+            date: NaiveDate::from_ymd_opt(2023, 1, 2).unwrap(),
+        });
+        let mut buffer = Vec::new();
+        write_document(&[st], CamtMessage::Report, CamtVersion::V08, &mut buffer).unwrap();
+        let output = String::from_utf8(buffer).unwrap();
+        assert_eq!(output.matches("<Bal>").count(), 1, "{}", output);
+        assert!(output.contains("<Cd>ITBD</Cd>"), "{}", output);
+
+        let mut buffer = Vec::new();
+        write_document(&[statement()], CamtMessage::Notification, CamtVersion::V02, &mut buffer).unwrap();
+        let output = String::from_utf8(buffer.clone()).unwrap();
+        assert!(
+            output.contains("<BkToCstmrDbtCdtNtfctn>") && output.contains("<Ntfctn>"),
+            "{}",
+            output
+        );
+        assert!(!output.contains("<Bal>"), "{}", output);
+        let parsed = Camt054Parser::parse(&buffer[..])
+            .unwrap()
+            .to_statements(&ConvertOptions::default())
+            .unwrap();
+        assert_eq!(parsed[0].entries, statement().entries);
+        assert_eq!(parsed[0].opening_balance, None);
+    }
+}
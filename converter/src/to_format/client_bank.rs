@@ -0,0 +1,193 @@
+//! Запись выписок в формат 1С `1CClientBankExchange` (версия 1.03, Windows-1251).
+
+use std::io::Write;
+
+use chrono::{NaiveDate, Utc};
+use encoding_rs::WINDOWS_1251;
+
+use crate::{
+    error::ConvertError,
+    from_parser::client_bank::CURRENCY,
+    model::{Counterparty, Statement, Transaction},
+    money::{Money, overflow},
+};
+
+fn date(date: NaiveDate) -> String {
+    date.format("%d.%m.%Y").to_string()
+}
+
+/// Строка `Ключ=Значение`; переводы строк в значении недопустимы.
+fn field(text: &mut String, key: &str, value: &str) {
+    text.push_str(key);
+    text.push('=');
+    text.push_str(&value.replace(['\r', '\n'], " "));
+    text.push_str("\r\n");
+}
+
+/// Реквизиты стороны: `Плательщик*` или `Получатель*`.
+fn write_party(text: &mut String, side: &str, party: &Counterparty) {
+    let value = |v: &Option<String>| v.clone().unwrap_or_default();
+    field(text, &format!("{}Счет", side), &value(&party.account));
+    field(text, side, &value(&party.name));
+    field(text, &format!("{}ИНН", side), &value(&party.inn));
+    field(text, &format!("{}КПП", side), &value(&party.kpp));
+    field(text, &format!("{}БИК", side), &value(&party.bank_code));
+}
+
+fn write_document(text: &mut String, statement: &Statement, tx: &Transaction, number: usize) {
+    let own = Counterparty {
+        account: Some(statement.account.clone()),
+        ..Default::default()
+    };
+    let other = tx.counterparty.clone().unwrap_or_default();
+    let debit = tx.amount.is_negative();
+    let (payer, payee) = if debit { (&own, &other) } else { (&other, &own) };
+
+    field(text, "СекцияДокумент", "Платежное поручение");
+    field(text, "Номер", &tx.customer_reference.clone().unwrap_or_else(|| number.to_string()));
+    field(text, "Дата", &date(tx.value_date));
+    field(text, "Сумма", &tx.amount.abs().to_string());
+    write_party(text, "Плательщик", payer);
+    field(text, "ДатаСписано", &if debit { date(tx.value_date) } else { String::new() });
+    write_party(text, "Получатель", payee);
+    field(text, "ДатаПоступило", &if debit { String::new() } else { date(tx.value_date) });
+    let purpose = tx.remittance_information.as_deref().unwrap_or(&tx.description);
+    field(text, "НазначениеПлатежа", purpose);
+    text.push_str("КонецДокумента\r\n");
+}
+
+/// Валюта выписки: в файле ее нет, при чтении суммы считаются рублевыми.
+fn check_currency(statement: &Statement) -> Result<(), ConvertError> {
+    let balances = statement.opening_balance.iter().chain(&statement.closing_balance);
+    let currencies = balances.map(|b| &b.currency).chain(statement.entries.iter().map(|tx| &tx.currency));
+    match currencies
+        .map(|c| c.trim())
+        .find(|c| !c.is_empty() && !c.eq_ignore_ascii_case(CURRENCY))
+    {
+        Some(currency) => Err(ConvertError::Unsupported {
+            from: format!("{} ({})", currency, statement.id),
+            to: "ClientBank1C".to_string(),
+        }),
+        None => Ok(()),
+    }
+}
+
+/// Записывает файл: заголовок, `СекцияРасчСчет` на каждую выписку, затем документы.
+///
+/// Формат только рублевый: выписки в другой валюте — [`ConvertError::Unsupported`].
+pub(crate) fn write_file<W: Write>(statements: &[Statement], mut writer: W) -> Result<(), ConvertError> {
+    statements.iter().try_for_each(check_currency)?;
+    let created_at = statements
+        .iter()
+        .find_map(|st| st.created_at)
+        .unwrap_or_else(|| Utc::now().naive_utc());
+    let periods: Vec<(NaiveDate, NaiveDate)> = statements
+        .iter()
+        .map(|st| st.balances().map(|(opening, closing)| (opening.date, closing.date)))
+        .collect::<Result<_, _>>()?;
+
+    let mut text = String::from("1CClientBankExchange\r\n");
+    field(&mut text, "ВерсияФормата", "1.03");
+    field(&mut text, "Кодировка", "Windows");
+    field(&mut text, "Отправитель", "");
+    field(&mut text, "Получатель", "");
+    field(&mut text, "ДатаСоздания", &date(created_at.date()));
+    field(&mut text, "ВремяСоздания", &created_at.format("%H:%M:%S").to_string());
+    if let (Some(start), Some(end)) = (periods.iter().map(|p| p.0).min(), periods.iter().map(|p| p.1).max()) {
+        field(&mut text, "ДатаНачала", &date(start));
+        field(&mut text, "ДатаКонца", &date(end));
+    }
+    for statement in statements {
+        field(&mut text, "РасчСчет", &statement.account);
+    }
+    field(&mut text, "Документ", "Платежное поручение");
+
+    for statement in statements {
+        let (opening, closing) = statement.balances()?;
+        // Ноль с exponent сальдо: пустой оборот печатается как `0.00`.
+        let zero = Money::zero(opening.amount.exponent());
+        let total = |debit: bool| {
+            let amounts = statement.entries.iter().map(|tx| tx.amount).filter(|a| a.is_negative() == debit);
+            Money::checked_sum(std::iter::once(zero).chain(amounts)).ok_or_else(overflow)
+        };
+        let (credits, debits) = (total(false)?, total(true)?);
+        text.push_str("СекцияРасчСчет\r\n");
+        field(&mut text, "ДатаНачала", &date(opening.date));
+        field(&mut text, "ДатаКонца", &date(closing.date));
+        field(&mut text, "РасчСчет", &statement.account);
+        field(&mut text, "НачальныйОстаток", &opening.amount.to_string());
+        field(&mut text, "ВсегоПоступило", &credits.to_string());
+        field(&mut text, "ВсегоСписано", &debits.abs().to_string());
+        field(&mut text, "КонечныйОстаток", &closing.amount.to_string());
+        text.push_str("КонецРасчСчет\r\n");
+    }
+    for statement in statements {
+        for (index, tx) in statement.entries.iter().enumerate() {
+            write_document(&mut text, statement, tx, index + 1);
+        }
+    }
+    text.push_str("КонецФайла\r\n");
+
+    writer.write_all(&WINDOWS_1251.encode(&text).0)?;
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use parser::{ClientBank1CParser, Parser};
+
+    use super::*;
+    use crate::{FromParser, options::ConvertOptions};
+
+    #[test]
+    fn round_trip_through_parser() {
+        let tx = Transaction {
+            reference: "S1".to_string(),
+            account: "40702810900000000001".to_string(),
+            amount: Money::new(-150050, 2),
+            currency: "RUB".to_string(),
+            value_date: NaiveDate::from_ymd_opt(2023, 1, 10).unwrap(),
+            customer_reference: Some("42".to_string()),
+            counterparty: Some(Counterparty {
+                name: Some("ООО \"Лютик\"".to_string()),
+                account: Some("40702810100000000002".to_string()),
+                inn: Some("7702345678".to_string()),
+                kpp: Some("770201001".to_string()),
+                bank_code: Some("044525225".to_string()),
+                ..Default::default()
+            }),
+            description: "Оплата\nпо счету 7".to_string(),
+            ..Default::default()
+        };
+        let statement = Statement::from_entries(vec![tx.clone()]);
+        let mut buffer = Vec::new();
+        write_file(std::slice::from_ref(&statement), &mut buffer).unwrap();
+
+        let (text, _, _) = WINDOWS_1251.decode(&buffer);
+        assert!(text.starts_with("1CClientBankExchange\r\n"));
+        assert!(text.contains("ВсегоСписано=1500.50\r\n"), "{}", text);
+        assert!(text.contains("НазначениеПлатежа=Оплата по счету 7\r\n"));
+
+        let parser = ClientBank1CParser::parse(&buffer[..]).unwrap();
+        let parsed = parser.to_statements(&ConvertOptions::default()).unwrap();
+        let entry = &parsed[0].entries[0];
+        assert_eq!(entry.amount, tx.amount);
+        assert_eq!(entry.value_date, tx.value_date);
+        assert_eq!(entry.customer_reference, tx.customer_reference);
+        assert_eq!(entry.counterparty, tx.counterparty);
+        assert_eq!(parsed[0].closing_balance.as_ref().unwrap().amount, Money::new(-150050, 2));
+    }
+
+    #[test]
+    fn rejects_non_rub_statements() {
+        let tx = Transaction {
+            amount: Money::new(100, 2),
+            currency: "EUR".to_string(),
+            ..Default::default()
+        };
+        let mut buffer = Vec::new();
+        let result = write_file(&[Statement::from_entries(vec![tx])], &mut buffer);
+        assert!(matches!(result, Err(ConvertError::Unsupported { .. })));
+        assert!(buffer.is_empty());
+    }
+}
@@ -0,0 +1,278 @@
+//! Запись выписок в CODA (версия 2): записи по 128 символов, CRLF, Windows-1252.
+//!
+//! Каждая выписка — отдельный блок `0`…`9`; сообщение и контрагент переносятся в `2.2`/`2.3`.
+
+use std::io::Write;
+
+use chrono::{NaiveDate, Utc};
+use encoding_rs::WINDOWS_1252;
+
+use crate::{
+    error::ConvertError,
+    from_parser::coda::AMOUNT_EXPONENT,
+    model::{Statement, Transaction},
+    money::{Money, overflow},
+};
+
+/// Коды операций для записей без кода CODA: прочий дебет и прочий кредит.
+const MISC_DEBIT: &str = "00101000";
+const MISC_CREDIT: &str = "00150000";
+
+/// Длины частей свободного сообщения в `2.1`, `2.2` и `2.3`.
+const COMMUNICATION_PARTS: [usize; 3] = [53, 53, 43];
+
+fn date(date: NaiveDate) -> String {
+    date.format("%d%m%y").to_string()
+}
+
+/// Поле фиксированной ширины: обрезается или дополняется пробелами.
+///
+/// Символы вне Windows-1252 заменяются на `?`, управляющие — на пробел, чтобы не сбить длину записи.
+fn pad(value: &str, len: usize) -> String {
+    let text: String = value
+        .chars()
+        .map(|c| match c {
+            ' '..='~' | '\u{a0}'..='\u{ff}' => c,
+            c if c.is_control() => ' ',
+            _ => '?',
+        })
+        .take(len)
+        .collect();
+    format!("{:<width$}", text, width = len)
+}
+
+/// Признак (`0` кредит, `1` дебет) и 15 цифр суммы с тремя знаками после запятой.
+fn amount(money: Money) -> Result<String, ConvertError> {
+    let scaled = money
+        .abs()
+        .rescale(AMOUNT_EXPONENT)
+        .ok_or_else(|| ConvertError::InvalidAmount(money.to_string()))?;
+    Ok(format!("{}{:015}", if money.is_negative() { 1 } else { 0 }, scaled.minor_units()))
+}
+
+/// Структура счета в `1`: `2` — бельгийский IBAN, `3` — иностранный IBAN, `1` — прочий.
+fn account_structure(account: &str) -> char {
+    let bytes = account.as_bytes();
+    let iban = bytes.len() > 4 && bytes[..2].iter().all(u8::is_ascii_uppercase) && bytes[2..4].iter().all(u8::is_ascii_digit);
+    match (iban, account.starts_with("BE")) {
+        (true, true) => '2',
+        (true, false) => '3',
+        _ => '1',
+    }
+}
+
+/// Код операции из [`Transaction::gvc`], если это код CODA из 8 цифр, иначе прочий дебет/кредит.
+fn transaction_code(tx: &Transaction) -> &str {
+    tx.gvc
+        .as_deref()
+        .filter(|code| code.len() == 8 && code.bytes().all(|b| b.is_ascii_digit()))
+        .unwrap_or(if tx.amount.is_negative() { MISC_DEBIT } else { MISC_CREDIT })
+}
+
+/// 12 цифр структурированного сообщения `+++123/4567/89012+++`.
+fn structured_digits(communication: &str) -> Option<String> {
+    let digits: String = communication
+        .strip_prefix("+++")?
+        .strip_suffix("+++")?
+        .chars()
+        .filter(|c| *c != '/')
+        .collect();
+    (digits.len() == 12 && digits.bytes().all(|b| b.is_ascii_digit())).then_some(digits)
+}
+
+/// Записи движения `2.1`, при необходимости `2.2` и `2.3`.
+fn movement_records(tx: &Transaction, sequence: usize, paper: &str) -> Result<Vec<String>, ConvertError> {
+    let text = tx.remittance_information.as_deref().unwrap_or(&tx.description);
+    let structured = structured_digits(text);
+    let (structure, communication, rest) = match &structured {
+        Some(digits) => ('1', format!("101{}", digits), String::new()),
+        None => {
+            let free: String = text.replace(['\r', '\n'], " ");
+            let first: String = free.chars().take(COMMUNICATION_PARTS[0]).collect();
+            ('0', first, free.chars().skip(COMMUNICATION_PARTS[0]).collect())
+        }
+    };
+    let second: String = rest.chars().take(COMMUNICATION_PARTS[1]).collect();
+    let third: String = rest.chars().skip(COMMUNICATION_PARTS[1]).take(COMMUNICATION_PARTS[2]).collect();
+    let counterparty = tx.counterparty.clone().unwrap_or_default();
+    let need_part3 = counterparty.account.is_some() || counterparty.name.is_some() || !third.is_empty();
+    let need_part2 = need_part3 || !second.is_empty() || tx.customer_reference.is_some() || counterparty.bic.is_some();
+    let prefix = |kind: &str| format!("{}{:04}0000", kind, sequence % 10_000);
+
+    let mut records = vec![format!(
+        "{}{}{}{}{}{}{}{}{}0{} 0",
+        prefix("21"),
+        pad(tx.bank_reference.as_deref().unwrap_or_default(), 21),
+        amount(tx.amount)?,
+        date(tx.value_date),
+        transaction_code(tx),
+        structure,
+        pad(&communication, 53),
+        date(tx.booking_date.unwrap_or(tx.value_date)),
+        paper,
+        if need_part2 { 1 } else { 0 },
+    )];
+    if need_part2 {
+        records.push(format!(
+            "{}{}{}{}{:16}{} 0",
+            prefix("22"),
+            pad(&second, 53),
+            pad(tx.customer_reference.as_deref().unwrap_or_default(), 35),
+            pad(counterparty.bic.as_deref().unwrap_or_default(), 11),
+            "",
+            if need_part3 { 1 } else { 0 },
+        ));
+    }
+    if need_part3 {
+        records.push(format!(
+            "{}{}{}{}{}  0",
+            prefix("23"),
+            pad(counterparty.account.as_deref().unwrap_or_default(), 34),
+            pad(&tx.currency, 3),
+            pad(counterparty.name.as_deref().unwrap_or_default(), 35),
+            pad(&third, 43),
+        ));
+    }
+    Ok(records)
+}
+
+/// Блок одной выписки `0`…`9`; `last` — последний файл в `9`.
+fn statement_records(statement: &Statement, last: bool) -> Result<Vec<String>, ConvertError> {
+    let (opening, closing) = statement.balances()?;
+    let created_at = statement.created_at.unwrap_or_else(|| Utc::now().naive_utc());
+    let paper = statement
+        .sequence_number
+        .as_deref()
+        .and_then(|s| s.split('/').next())
+        .and_then(|s| s.trim().parse::<u32>().ok())
+        .map(|n| format!("{:03}", n % 1000))
+        .unwrap_or_else(|| "001".to_string());
+    let account = format!("{}{}", pad(&statement.account, 34), pad(&closing.currency, 3));
+
+    let header = format!(
+        "00000{}{}05{:8}{}{}{}{}{:45}2",
+        date(created_at.date()),
+        "000",
+        "",
+        pad(&statement.id, 10),
+        pad("", 26),
+        pad(statement.sender_bic.as_deref().unwrap_or_default(), 11),
+        "00000000000",
+        "",
+    );
+    let mut records = vec![format!(
+        "1{}{}{}{}{}{}{}{}",
+        account_structure(&statement.account),
+        paper,
+        account,
+        amount(opening.amount)?,
+        date(opening.date),
+        pad("", 26),
+        pad("", 35),
+        paper,
+    )];
+    let (mut debit, mut credit) = (Money::zero(AMOUNT_EXPONENT), Money::zero(AMOUNT_EXPONENT));
+    for (index, tx) in statement.entries.iter().enumerate() {
+        if tx.amount.is_negative() {
+            debit = debit.checked_add(tx.amount.abs()).ok_or_else(overflow)?;
+        } else {
+            credit = credit.checked_add(tx.amount).ok_or_else(overflow)?;
+        }
+        records.extend(movement_records(tx, index + 1, &paper)?);
+    }
+    records.push(format!(
+        "8{}{}{}{}{:64}0",
+        paper,
+        account,
+        amount(closing.amount)?,
+        date(closing.date),
+        ""
+    ));
+    let totals = |money: Money| amount(money).map(|a| a[1..].to_string());
+    let trailer = format!(
+        "9{:15}{:06}{}{}{:75}{}",
+        "",
+        records.len(),
+        totals(debit)?,
+        totals(credit)?,
+        "",
+        if last { 2 } else { 1 },
+    );
+    let mut block = vec![header];
+    block.extend(records);
+    block.push(trailer);
+    Ok(block)
+}
+
+/// Записывает файл: блок `0`…`9` на каждую выписку.
+pub(crate) fn write_file<W: Write>(statements: &[Statement], mut writer: W) -> Result<(), ConvertError> {
+    let mut text = String::new();
+    for (index, statement) in statements.iter().enumerate() {
+        for record in statement_records(statement, index + 1 == statements.len())? {
+            debug_assert_eq!(record.chars().count(), 128, "{}", record);
+            text.push_str(&record);
+            text.push_str("\r\n");
+        }
+    }
+    writer.write_all(&WINDOWS_1252.encode(&text).0)?;
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use parser::{CodaParser, Parser};
+
+    use super::*;
+    use crate::{FromParser, model::Counterparty, options::ConvertOptions};
+
+    #[test]
+    fn round_trip_through_parser() {
+        let tx = |amount: i64, text: &str, counterparty: Option<Counterparty>| Transaction {
+            reference: "ST1".to_string(),
+            account: "BE68539007547034".to_string(),
+            amount: Money::new(amount, 2),
+            currency: "EUR".to_string(),
+            value_date: NaiveDate::from_ymd_opt(2023, 1, 3).unwrap(),
+            booking_date: NaiveDate::from_ymd_opt(2023, 1, 4),
+            counterparty,
+            description: text.to_string(),
+            ..Default::default()
+        };
+        let supplier = Counterparty {
+            name: Some("Société Générale".to_string()),
+            account: Some("FR7630006000011234567890189".to_string()),
+            bic: Some("AGRIFRPP".to_string()),
+            ..Default::default()
+        };
+        let mut statement = Statement::from_entries(vec![
+            tx(-5000, "+++123/4567/89012+++", Some(supplier)),
+            tx(123456, &"Long refund text ".repeat(8), None),
+        ]);
+        statement.id = "ST1".to_string();
+        statement.account = "BE68539007547034".to_string();
+        statement.sequence_number = Some("42/1".to_string());
+
+        let mut buffer = Vec::new();
+        write_file(&[statement], &mut buffer).unwrap();
+        let text = WINDOWS_1252.decode(&buffer).0;
+        assert!(text.lines().all(|l| l.chars().count() == 128), "{}", text);
+        assert!(text.contains("\r\n22"), "{}", text);
+        assert!(text.starts_with("00000"));
+
+        let parsed = CodaParser::parse(&buffer[..])
+            .unwrap()
+            .to_statements(&ConvertOptions::default())
+            .unwrap();
+        let st = &parsed[0];
+        assert_eq!((st.id.as_str(), st.account.as_str()), ("ST1", "BE68539007547034"));
+        assert_eq!(st.sequence_number.as_deref(), Some("042"));
+        assert_eq!(st.closing_balance.as_ref().unwrap().amount, Money::new(118456, 2));
+        let payment = &st.entries[0];
+        assert_eq!(payment.amount, Money::new(-5000, 2));
+        assert_eq!(payment.gvc.as_deref(), Some(MISC_DEBIT));
+        assert_eq!(payment.remittance_information.as_deref(), Some("+++123/4567/89012+++"));
+        assert_eq!(payment.counterparty.as_ref().unwrap().name.as_deref(), Some("Société Générale"));
+        assert_eq!(payment.counterparty.as_ref().unwrap().bic.as_deref(), Some("AGRIFRPP"));
+        assert_eq!(st.entries[1].description, "Long refund text ".repeat(8).trim());
+    }
+}
@@ -0,0 +1,37 @@
+//! Запись выписок в JSON и JSON Lines по схеме [`crate::schema`].
+
+use std::io::Write;
+
+use crate::{
+    error::ConvertError,
+    model::Statement,
+    schema::{JsonDocument, JsonLine, SCHEMA_VERSION},
+};
+
+fn io_error(error: serde_json::Error) -> ConvertError {
+    ConvertError::Io(error.into())
+}
+
+/// Документ [`JsonDocument`] с отступами.
+pub(crate) fn write_document<W: Write>(statements: &[Statement], mut writer: W) -> Result<(), ConvertError> {
+    let document = JsonDocument {
+        schema_version: SCHEMA_VERSION,
+        statements: statements.to_vec(),
+    };
+    serde_json::to_writer_pretty(&mut writer, &document).map_err(io_error)?;
+    writeln!(writer)?;
+    Ok(())
+}
+
+/// По строке [`JsonLine`] на транзакцию; сальдо выписок не записываются.
+pub(crate) fn write_lines<W: Write>(statements: &[Statement], mut writer: W) -> Result<(), ConvertError> {
+    for tx in statements.iter().flat_map(|st| &st.entries) {
+        let line = JsonLine {
+            schema_version: SCHEMA_VERSION,
+            transaction: tx.clone(),
+        };
+        serde_json::to_writer(&mut writer, &line).map_err(io_error)?;
+        writeln!(writer)?;
+    }
+    Ok(())
+}
@@ -0,0 +1,423 @@
+//! Журналы plain-text accounting: ledger/hledger и beancount.
+//!
+//! Каждая транзакция — проводка из двух и более строк: счет актива по
+//! [`crate::LedgerRules::accounts`] и второй счет по правилам профиля; разбивка
+//! ([`crate::Split`]) дает по строке на часть. Входящее сальдо выписки доводит
+//! счет актива до суммы за счет [`crate::LedgerRules::opening_account`], исходящее
+//! записывается проверкой баланса.
+
+use std::{collections::BTreeMap, io::Write};
+
+use chrono::{Days, NaiveDate};
+
+use crate::{
+    error::ConvertError,
+    ledger_rules::{CompiledRules, LedgerRules},
+    model::{Statement, Transaction},
+    money::{Money, overflow},
+};
+
+/// Строка проводки.
+struct Posting {
+    account: String,
+    amount: Money,
+    memo: Option<String>,
+}
+
+/// Текст в одну строку: переводы строк и повторные пробелы сворачиваются.
+fn one_line(text: &str) -> String {
+    text.split_whitespace().collect::<Vec<_>>().join(" ")
+}
+
+/// Код валюты как товар журнала; без валюты — `XXX`, как в [`Statement::balances`].
+fn commodity(currency: &str) -> String {
+    let code: String = currency.trim().chars().filter(char::is_ascii_alphanumeric).collect();
+    if code.is_empty() {
+        "XXX".to_string()
+    } else {
+        code.to_ascii_uppercase()
+    }
+}
+
+fn postings(tx: &Transaction, rules: &CompiledRules) -> Result<Vec<Posting>, ConvertError> {
+    let mut postings = vec![Posting {
+        account: rules.asset_account(&tx.account),
+        amount: tx.amount,
+        memo: None,
+    }];
+    for split in &tx.splits {
+        postings.push(Posting {
+            account: rules.counter_account(tx, split.category.as_deref(), split.amount),
+            amount: split.amount.checked_neg().ok_or_else(overflow)?,
+            memo: split.memo.as_deref().map(one_line),
+        });
+    }
+    // Без разбивки или с разбивкой не на всю сумму — остаток на второй счет транзакции.
+    let split = Money::checked_sum(tx.splits.iter().map(|s| s.amount)).ok_or_else(overflow)?;
+    let rest = tx.amount.checked_sub(split).ok_or_else(overflow)?;
+    if tx.splits.is_empty() || !rest.is_zero() {
+        postings.push(Posting {
+            account: rules.counter_account(tx, tx.category.as_deref(), rest),
+            amount: rest.checked_neg().ok_or_else(overflow)?,
+            memo: None,
+        });
+    }
+    Ok(postings)
+}
+
+fn posting_line(text: &mut String, indent: &str, posting: &Posting, commodity: &str) {
+    let line = format!(
+        "{}{:<40}  {:>14} {}",
+        indent,
+        posting.account,
+        posting.amount.to_string(),
+        commodity
+    );
+    text.push_str(&line);
+    if let Some(memo) = posting.memo.as_deref().filter(|m| !m.is_empty()) {
+        text.push_str("  ; ");
+        text.push_str(memo);
+    }
+    text.push('\n');
+}
+
+/// Получатель и описание: контрагент и назначение платежа (или описание).
+fn payee_and_narration(tx: &Transaction) -> (Option<String>, String) {
+    let payee = tx.counterparty.as_ref().and_then(|c| c.name.as_deref()).map(one_line);
+    let narration = one_line(tx.remittance_information.as_deref().unwrap_or(&tx.description));
+    (payee.filter(|p| !p.is_empty()), narration)
+}
+
+fn write_ledger_transaction(text: &mut String, tx: &Transaction, rules: &CompiledRules) -> Result<(), ConvertError> {
+    text.push_str(&tx.value_date.format("%Y-%m-%d").to_string());
+    text.push_str(" *");
+    if let Some(code) = tx.customer_reference.as_deref().map(one_line).filter(|c| !c.is_empty()) {
+        text.push_str(&format!(" ({})", code.replace(['(', ')'], "")));
+    }
+    let (payee, narration) = payee_and_narration(tx);
+    match payee {
+        Some(payee) => {
+            text.push_str(&format!(" {}\n", payee));
+            if !narration.is_empty() && narration != payee {
+                text.push_str(&format!("    ; {}\n", narration));
+            }
+        }
+        None => text.push_str(&format!(" {}\n", narration)),
+    }
+    if let Some(reference) = tx.bank_reference.as_deref().map(one_line).filter(|r| !r.is_empty()) {
+        text.push_str(&format!("    ; bank_reference: {}\n", reference));
+    }
+    let commodity = commodity(&tx.currency);
+    for posting in postings(tx, rules)? {
+        posting_line(text, "    ", &posting, &commodity);
+    }
+    text.push('\n');
+    Ok(())
+}
+
+/// Журнал ledger; hledger читает его без изменений.
+///
+/// Входящее сальдо — присваивание баланса `= сумма` перед записями выписки: ledger сам
+/// считает проводку на счет входящих остатков, и для следующей выписки того же счета она нулевая.
+/// Исходящее сальдо — проводка с нулевой суммой и проверкой `= сумма` после записей выписки.
+pub(crate) fn write_ledger<W: Write>(statements: &[Statement], rules: &LedgerRules, mut writer: W) -> Result<(), ConvertError> {
+    let rules = rules.compile()?;
+    let mut text = String::new();
+    for statement in statements {
+        if let Some(balance) = &statement.opening_balance {
+            let commodity = commodity(&balance.currency);
+            text.push_str(&format!(
+                "{} * Opening balance {}\n",
+                balance.date.format("%Y-%m-%d"),
+                one_line(&statement.id)
+            ));
+            let account = rules.asset_account(&statement.account);
+            text.push_str(&format!(
+                "    {:<40}  {:>14} = {} {}\n    {}\n\n",
+                account,
+                "",
+                balance.amount,
+                commodity,
+                rules.opening_account()
+            ));
+        }
+        for tx in &statement.entries {
+            write_ledger_transaction(&mut text, tx, &rules)?;
+        }
+        if let Some(balance) = &statement.closing_balance {
+            let commodity = commodity(&balance.currency);
+            text.push_str(&format!(
+                "{} * Closing balance {}\n",
+                balance.date.format("%Y-%m-%d"),
+                one_line(&statement.id)
+            ));
+            let account = rules.asset_account(&statement.account);
+            text.push_str(&format!(
+                "    {:<40}  {:>14} {} = {} {}\n\n",
+                account, 0, commodity, balance.amount, commodity
+            ));
+        }
+    }
+    writer.write_all(text.as_bytes())?;
+    Ok(())
+}
+
+/// Строка beancount в кавычках.
+fn quoted(text: &str) -> String {
+    format!("\"{}\"", one_line(text).replace('\\', "\\\\").replace('"', "\\\""))
+}
+
+fn write_beancount_transaction(text: &mut String, tx: &Transaction, postings: &[Posting]) {
+    text.push_str(&tx.value_date.format("%Y-%m-%d").to_string());
+    text.push_str(" *");
+    let (payee, narration) = payee_and_narration(tx);
+    if let Some(payee) = payee {
+        text.push(' ');
+        text.push_str(&quoted(&payee));
+    }
+    text.push(' ');
+    text.push_str(&quoted(&narration));
+    text.push('\n');
+    let metadata = [
+        ("customer_reference", &tx.customer_reference),
+        ("bank_reference", &tx.bank_reference),
+    ];
+    for (key, value) in metadata {
+        if let Some(value) = value.as_deref().filter(|v| !v.trim().is_empty()) {
+            text.push_str(&format!("  {}: {}\n", key, quoted(value)));
+        }
+    }
+    let commodity = commodity(&tx.currency);
+    for posting in postings {
+        posting_line(text, "  ", posting, &commodity);
+    }
+    text.push('\n');
+}
+
+/// Файл beancount: `open` для всех счетов на дату первой записи, транзакции и `balance`.
+///
+/// `balance` проверяет сальдо на начало дня, поэтому исходящее сальдо ставится на день
+/// после своей даты. Входящее сальдо — `pad` накануне и `balance` на дату сальдо.
+pub(crate) fn write_beancount<W: Write>(statements: &[Statement], rules: &LedgerRules, mut writer: W) -> Result<(), ConvertError> {
+    let rules = rules.compile()?;
+    let mut opened: BTreeMap<String, NaiveDate> = BTreeMap::new();
+    let mut open = |account: &str, date: NaiveDate| {
+        let entry = opened.entry(account.to_string()).or_insert(date);
+        *entry = (*entry).min(date);
+    };
+    let mut body = String::new();
+    for statement in statements {
+        if let Some(balance) = &statement.opening_balance {
+            let account = rules.asset_account(&statement.account);
+            let pad_date = balance.date.checked_sub_days(Days::new(1)).unwrap_or(balance.date);
+            open(&account, pad_date);
+            open(rules.opening_account(), pad_date);
+            body.push_str(&format!(
+                "{} pad {} {}\n",
+                pad_date.format("%Y-%m-%d"),
+                account,
+                rules.opening_account()
+            ));
+            let amount = format!("{} {}", balance.amount, commodity(&balance.currency));
+            body.push_str(&format!(
+                "{} balance {:<40}  {:>14}\n\n",
+                balance.date.format("%Y-%m-%d"),
+                account,
+                amount
+            ));
+        }
+        for tx in &statement.entries {
+            let postings = postings(tx, &rules)?;
+            for posting in &postings {
+                open(&posting.account, tx.value_date);
+            }
+            write_beancount_transaction(&mut body, tx, &postings);
+        }
+        if let Some(balance) = &statement.closing_balance {
+            let account = rules.asset_account(&statement.account);
+            open(&account, balance.date);
+            let date = balance.date.checked_add_days(Days::new(1)).unwrap_or(balance.date);
+            let amount = format!("{} {}", balance.amount, commodity(&balance.currency));
+            body.push_str(&format!("{} balance {:<40}  {:>14}\n\n", date.format("%Y-%m-%d"), account, amount));
+        }
+    }
+    let mut text = String::new();
+    for (account, date) in &opened {
+        text.push_str(&format!("{} open {}\n", date.format("%Y-%m-%d"), account));
+    }
+    if !opened.is_empty() {
+        text.push('\n');
+    }
+    text.push_str(&body);
+    writer.write_all(text.as_bytes())?;
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::model::{Balance, BalanceKind, Counterparty, Split};
+
+    fn statement() -> Statement {
+        let date = NaiveDate::from_ymd_opt(2023, 1, 2).unwrap();
+        let rent = Transaction {
+            reference: "ST1".to_string(),
+            account: "DE89".to_string(),
+            amount: Money::new(-120000, 2),
+            currency: "EUR".to_string(),
+            value_date: date,
+            customer_reference: Some("INV-1".to_string()),
+            counterparty: Some(Counterparty {
+                name: Some("Landlord \"Home\"".to_string()),
+                ..Default::default()
+            }),
+            description: "Rent\nJanuary".to_string(),
+            ..Default::default()
+        };
+        let shopping = Transaction {
+            amount: Money::new(-5000, 2),
+            description: "Market".to_string(),
+            splits: vec![Split {
+                category: Some("Food".to_string()),
+                memo: Some("Groceries".to_string()),
+                amount: Money::new(-3000, 2),
+            }],
+            ..rent.clone()
+        };
+        let shopping = Transaction {
+            counterparty: None,
+            customer_reference: None,
+            bank_reference: Some("B2".to_string()),
+            ..shopping
+        };
+        Statement {
+            closing_balance: Some(Balance {
+                kind: BalanceKind::Closing,
+                amount: Money::new(-125000, 2),
+                currency: "EUR".to_string(),
+                date,
+            }),
+            ..Statement::from_entries(vec![rent, shopping])
+        }
+    }
+
+    fn rules() -> LedgerRules {
+        LedgerRules::from_toml(
+            "[accounts]\n\"DE89\" = \"Assets:Checking\"\n\n[[rules]]\npattern = \"(?i)landlord\"\naccount = \"Expenses:Rent\"",
+        )
+        .unwrap()
+    }
+
+    #[test]
+    fn ledger_journal_with_splits_and_assertion() {
+        let mut buffer = Vec::new();
+        write_ledger(&[statement()], &rules(), &mut buffer).unwrap();
+        let text = String::from_utf8(buffer).unwrap();
+        let expected = [
+            "2023-01-02 * (INV-1) Landlord \"Home\"",
+            "    ; Rent January",
+            &format!("    {:<40}  {:>14} EUR", "Assets:Checking", "-1200.00"),
+            &format!("    {:<40}  {:>14} EUR", "Expenses:Rent", "1200.00"),
+            "",
+            "2023-01-02 * Market",
+            "    ; bank_reference: B2",
+            &format!("    {:<40}  {:>14} EUR", "Assets:Checking", "-50.00"),
+            &format!("    {:<40}  {:>14} EUR  ; Groceries", "Expenses:Food", "30.00"),
+            &format!("    {:<40}  {:>14} EUR", "Expenses:Unknown", "20.00"),
+            "",
+            "2023-01-02 * Closing balance ST1",
+            &format!("    {:<40}  {:>14} EUR = -1250.00 EUR", "Assets:Checking", "0"),
+            "",
+        ];
+        assert_eq!(text.lines().collect::<Vec<_>>(), expected, "{}", text);
+    }
+
+    /// Выписка MT940 `:60F:C…1000,00`, одно списание −100 и `:62F:C…900,00`.
+    fn statement_with_opening() -> Statement {
+        let date = |day| NaiveDate::from_ymd_opt(2023, 1, day).unwrap();
+        let balance = |kind, amount, day| Balance {
+            kind,
+            amount: Money::new(amount, 2),
+            currency: "EUR".to_string(),
+            date: date(day),
+        };
+        let fee = Transaction {
+            reference: "ST2".to_string(),
+            account: "DE89".to_string(),
+            amount: Money::new(-10000, 2),
+            currency: "EUR".to_string(),
+            value_date: date(2),
+            description: "Fee".to_string(),
+            ..Default::default()
+        };
+        Statement {
+            opening_balance: Some(balance(BalanceKind::Opening, 100000, 1)),
+            closing_balance: Some(balance(BalanceKind::Closing, 90000, 2)),
+            ..Statement::from_entries(vec![fee])
+        }
+    }
+
+    #[test]
+    fn opening_balance_is_posted_against_equity() {
+        let mut buffer = Vec::new();
+        write_ledger(&[statement_with_opening()], &rules(), &mut buffer).unwrap();
+        let text = String::from_utf8(buffer).unwrap();
+        let expected = [
+            "2023-01-01 * Opening balance ST2",
+            &format!("    {:<40}  {:>14} = 1000.00 EUR", "Assets:Checking", ""),
+            "    Equity:Opening-Balances",
+            "",
+            "2023-01-02 * Fee",
+            &format!("    {:<40}  {:>14} EUR", "Assets:Checking", "-100.00"),
+            &format!("    {:<40}  {:>14} EUR", "Expenses:Unknown", "100.00"),
+            "",
+            "2023-01-02 * Closing balance ST2",
+            &format!("    {:<40}  {:>14} EUR = 900.00 EUR", "Assets:Checking", "0"),
+            "",
+        ];
+        assert_eq!(text.lines().collect::<Vec<_>>(), expected, "{}", text);
+
+        let mut buffer = Vec::new();
+        write_beancount(&[statement_with_opening()], &rules(), &mut buffer).unwrap();
+        let text = String::from_utf8(buffer).unwrap();
+        assert!(text.contains("2022-12-31 open Equity:Opening-Balances\n"), "{}", text);
+        assert!(
+            text.contains(&format!(
+                "2022-12-31 pad Assets:Checking Equity:Opening-Balances\n2023-01-01 balance {:<40}  {:>14}\n",
+                "Assets:Checking", "1000.00 EUR"
+            )),
+            "{}",
+            text
+        );
+        assert!(
+            text.contains(&format!("2023-01-03 balance {:<40}  {:>14}\n", "Assets:Checking", "900.00 EUR")),
+            "{}",
+            text
+        );
+    }
+
+    #[test]
+    fn beancount_opens_accounts_and_checks_balance_next_day() {
+        let mut buffer = Vec::new();
+        write_beancount(&[statement()], &rules(), &mut buffer).unwrap();
+        let text = String::from_utf8(buffer).unwrap();
+        assert!(
+            text.starts_with(
+                "2023-01-02 open Assets:Checking\n2023-01-02 open Expenses:Food\n2023-01-02 open Expenses:Rent\n2023-01-02 open Expenses:Unknown\n\n"
+            ),
+            "{}",
+            text
+        );
+        assert!(
+            text.contains("2023-01-02 * \"Landlord \\\"Home\\\"\" \"Rent January\"\n  customer_reference: \"INV-1\"\n"),
+            "{}",
+            text
+        );
+        assert!(text.contains("2023-01-02 * \"Market\"\n  bank_reference: \"B2\"\n"), "{}", text);
+        assert!(
+            text.contains(&format!("2023-01-03 balance {:<40}  {:>14}\n", "Assets:Checking", "-1250.00 EUR")),
+            "{}",
+            text
+        );
+    }
+}
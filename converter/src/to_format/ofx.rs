@@ -0,0 +1,281 @@
+//! Запись выписок в OFX 1.02 (SGML) или 2.2 (XML).
+
+use std::io::Write;
+
+use chrono::{NaiveDate, NaiveDateTime, Utc};
+
+use super::camt::truncate;
+use crate::{
+    error::ConvertError,
+    from_parser::ofx::TRANSACTION_TYPES,
+    model::{Balance, BalanceKind, Statement, Transaction},
+    options::OfxVersion,
+};
+
+/// Длина `NAME` в OFX.
+const NAME_LENGTH: usize = 32;
+/// Длина `MEMO` в OFX.
+const MEMO_LENGTH: usize = 255;
+/// `BANKID` обязателен в `BANKACCTFROM`; без BIC отправителя пишется заглушка.
+const UNKNOWN_BANK_ID: &str = "000000000";
+/// Допустимые значения `TRNTYPE` по спецификации OFX.
+const OFX_TRANSACTION_TYPES: [&str; 18] = [
+    "CREDIT",
+    "DEBIT",
+    "INT",
+    "DIV",
+    "FEE",
+    "SRVCHG",
+    "DEP",
+    "ATM",
+    "POS",
+    "XFER",
+    "CHECK",
+    "PAYMENT",
+    "CASH",
+    "DIRECTDEP",
+    "DIRECTDEBIT",
+    "REPEATPMT",
+    "HOLD",
+    "OTHER",
+];
+
+fn date(date: NaiveDate) -> String {
+    date.format("%Y%m%d").to_string()
+}
+
+fn date_time(date_time: NaiveDateTime) -> String {
+    date_time.format("%Y%m%d%H%M%S").to_string()
+}
+
+/// Теги по одному на строку; в SGML у листьев нет закрывающего тега.
+struct OfxWriter {
+    sgml: bool,
+    text: String,
+}
+
+impl OfxWriter {
+    fn open(&mut self, tag: &str) {
+        self.text.push_str(&format!("<{}>\n", tag));
+    }
+
+    fn close(&mut self, tag: &str) {
+        self.text.push_str(&format!("</{}>\n", tag));
+    }
+
+    fn leaf(&mut self, tag: &str, value: &str) {
+        let value = value
+            .replace('&', "&amp;")
+            .replace('<', "&lt;")
+            .replace('>', "&gt;")
+            .replace(['\r', '\n'], " ");
+        if self.sgml {
+            self.text.push_str(&format!("<{}>{}\n", tag, value));
+        } else {
+            self.text.push_str(&format!("<{}>{}</{}>\n", tag, value, tag));
+        }
+    }
+
+    fn status(&mut self) {
+        self.open("STATUS");
+        self.leaf("CODE", "0");
+        self.leaf("SEVERITY", "INFO");
+        self.close("STATUS");
+    }
+
+    fn balance(&mut self, tag: &str, balance: &Balance) {
+        self.open(tag);
+        self.leaf("BALAMT", &balance.amount.to_string());
+        self.leaf("DTASOF", &date(balance.date));
+        self.close(tag);
+    }
+
+    fn transaction(&mut self, statement: &Statement, tx: &Transaction, index: usize) {
+        // Тип OFX пишется как есть, код SWIFT — по таблице, иначе кредит/дебет по знаку.
+        let code = tx.transaction_type.as_deref();
+        let trntype = code
+            .and_then(|code| OFX_TRANSACTION_TYPES.iter().find(|ofx| **ofx == code))
+            .or_else(|| code.and_then(|code| TRANSACTION_TYPES.iter().find(|(_, swift)| *swift == code).map(|(ofx, _)| ofx)))
+            .copied()
+            .unwrap_or(if tx.amount.is_negative() { "DEBIT" } else { "CREDIT" });
+        // FITID обязателен и уникален в пределах счета.
+        let fitid = tx
+            .bank_reference
+            .clone()
+            .unwrap_or_else(|| format!("{}{}{:04}", statement.id, date(tx.value_date), index + 1));
+        let counterparty = tx.counterparty.clone().unwrap_or_default();
+        let memo = tx.remittance_information.as_deref().unwrap_or(&tx.description);
+        let name = counterparty.name.as_deref().unwrap_or(memo);
+
+        self.open("STMTTRN");
+        self.leaf("TRNTYPE", trntype);
+        self.leaf("DTPOSTED", &date(tx.value_date));
+        self.leaf("TRNAMT", &tx.amount.to_string());
+        self.leaf("FITID", &fitid);
+        if let Some(reference) = &tx.customer_reference {
+            self.leaf("CHECKNUM", reference);
+        }
+        if !name.trim().is_empty() {
+            self.leaf("NAME", truncate(name, NAME_LENGTH));
+        }
+        let bank_id = counterparty.bank_code.as_ref().or(counterparty.bic.as_ref());
+        if let (Some(bank_id), Some(account)) = (bank_id, &counterparty.account) {
+            self.open("BANKACCTTO");
+            self.leaf("BANKID", bank_id);
+            self.leaf("ACCTID", account);
+            self.leaf("ACCTTYPE", "CHECKING");
+            self.close("BANKACCTTO");
+        }
+        if !memo.trim().is_empty() && memo != name {
+            self.leaf("MEMO", truncate(memo, MEMO_LENGTH));
+        }
+        self.close("STMTTRN");
+    }
+
+    fn statement(&mut self, statement: &Statement) -> Result<(), ConvertError> {
+        let (opening, closing) = statement.balances()?;
+        self.open("STMTTRNRS");
+        self.leaf("TRNUID", if statement.id.is_empty() { "0" } else { &statement.id });
+        self.status();
+        self.open("STMTRS");
+        self.leaf("CURDEF", &closing.currency);
+        self.open("BANKACCTFROM");
+        // BANKID в модели нет; BIC отправителя — лучшее, что известно о банке.
+        let bank_id = statement.sender_bic.as_deref().filter(|bic| !bic.trim().is_empty());
+        self.leaf("BANKID", bank_id.unwrap_or(UNKNOWN_BANK_ID));
+        self.leaf("ACCTID", &statement.account);
+        self.leaf("ACCTTYPE", "CHECKING");
+        self.close("BANKACCTFROM");
+        self.open("BANKTRANLIST");
+        self.leaf("DTSTART", &date(opening.date));
+        self.leaf("DTEND", &date(closing.date));
+        for (index, tx) in statement.entries.iter().enumerate() {
+            self.transaction(statement, tx, index);
+        }
+        self.close("BANKTRANLIST");
+        self.balance("LEDGERBAL", &closing);
+        if let Some(available) = statement
+            .available_balances
+            .iter()
+            .find(|b| b.kind == BalanceKind::ClosingAvailable)
+        {
+            self.balance("AVAILBAL", available);
+        }
+        self.close("STMTRS");
+        self.close("STMTTRNRS");
+        Ok(())
+    }
+}
+
+/// Записывает все выписки в один `BANKMSGSRSV1`.
+pub(crate) fn write_document<W: Write>(statements: &[Statement], version: OfxVersion, mut writer: W) -> Result<(), ConvertError> {
+    let sgml = version == OfxVersion::V102;
+    let mut ofx = OfxWriter { sgml, text: String::new() };
+    if sgml {
+        ofx.text.push_str(&format!(
+            "OFXHEADER:100\nDATA:OFXSGML\nVERSION:{}\nSECURITY:NONE\nENCODING:UTF-8\nCHARSET:NONE\n\
+             COMPRESSION:NONE\nOLDFILEUID:NONE\nNEWFILEUID:NONE\n\n",
+            version
+        ));
+    } else {
+        ofx.text.push_str("<?xml version=\"1.0\" encoding=\"UTF-8\" standalone=\"no\"?>\n");
+        ofx.text.push_str(&format!(
+            "<?OFX OFXHEADER=\"200\" VERSION=\"{}\" SECURITY=\"NONE\" OLDFILEUID=\"NONE\" NEWFILEUID=\"NONE\"?>\n",
+            version
+        ));
+    }
+    let server_time = statements
+        .iter()
+        .find_map(|st| st.created_at)
+        .unwrap_or_else(|| Utc::now().naive_utc());
+
+    ofx.open("OFX");
+    ofx.open("SIGNONMSGSRSV1");
+    ofx.open("SONRS");
+    ofx.status();
+    ofx.leaf("DTSERVER", &date_time(server_time));
+    ofx.leaf("LANGUAGE", "ENG");
+    ofx.close("SONRS");
+    ofx.close("SIGNONMSGSRSV1");
+    ofx.open("BANKMSGSRSV1");
+    for statement in statements {
+        ofx.statement(statement)?;
+    }
+    ofx.close("BANKMSGSRSV1");
+    ofx.close("OFX");
+
+    let text = if sgml { ofx.text.replace('\n', "\r\n") } else { ofx.text };
+    writer.write_all(text.as_bytes())?;
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use parser::{OfxParser, Parser};
+
+    use super::*;
+    use crate::{FromParser, model::Counterparty, money::Money, options::ConvertOptions};
+
+    fn statement() -> Statement {
+        let tx = Transaction {
+            reference: "S1".to_string(),
+            account: "123456789".to_string(),
+            amount: Money::new(-4250, 2),
+            currency: "USD".to_string(),
+            value_date: NaiveDate::from_ymd_opt(2023, 1, 5).unwrap(),
+            transaction_type: Some("NCHG".to_string()),
+            counterparty: Some(Counterparty {
+                name: Some("Bank & Co".to_string()),
+                account: Some("555".to_string()),
+                bank_code: Some("026009593".to_string()),
+                ..Default::default()
+            }),
+            description: "Monthly fee".to_string(),
+            ..Default::default()
+        };
+        Statement::from_entries(vec![tx])
+    }
+
+    #[test]
+    fn round_trip_both_versions() {
+        for version in [OfxVersion::V102, OfxVersion::V220] {
+            let mut buffer = Vec::new();
+            write_document(&[statement()], version, &mut buffer).unwrap();
+            let text = String::from_utf8(buffer.clone()).unwrap();
+            assert!(text.contains("<NAME>Bank &amp; Co"), "{}", text);
+            assert!(text.contains("<BANKID>000000000"), "{}", text);
+            assert_eq!(text.contains("</TRNAMT>"), version == OfxVersion::V220);
+
+            let parser = OfxParser::parse(&buffer[..]).unwrap();
+            assert_eq!(parser.version(), if version == OfxVersion::V102 { 1 } else { 2 });
+            let parsed = parser.to_statements(&ConvertOptions::default()).unwrap();
+            let tx = &parsed[0].entries[0];
+            let original = &statement().entries[0];
+            assert_eq!(tx.amount, original.amount);
+            assert_eq!(tx.value_date, original.value_date);
+            assert_eq!(tx.transaction_type.as_deref(), Some("NCHG"));
+            assert_eq!(tx.description, "Monthly fee");
+            assert_eq!(tx.counterparty, original.counterparty);
+            assert_eq!(tx.bank_reference.as_deref(), Some("S1202301050001"));
+            assert_eq!(parsed[0].closing_balance.as_ref().unwrap().amount, Money::new(-4250, 2));
+        }
+    }
+
+    #[test]
+    fn ofx_transaction_type_is_kept() {
+        let trntype = |code: Option<&str>| {
+            let mut st = statement();
+            st.entries[0].transaction_type = code.map(str::to_string);
+            let mut buffer = Vec::new();
+            write_document(&[st], OfxVersion::V220, &mut buffer).unwrap();
+            let text = String::from_utf8(buffer).unwrap();
+            let start = text.find("<TRNTYPE>").unwrap() + "<TRNTYPE>".len();
+            text[start..start + text[start..].find('<').unwrap()].to_string()
+        };
+        assert_eq!(trntype(Some("POS")), "POS");
+        assert_eq!(trntype(Some("ATM")), "ATM");
+        assert_eq!(trntype(Some("NCHG")), "FEE");
+        assert_eq!(trntype(Some("NMSC")), "DEBIT");
+        assert_eq!(trntype(None), "DEBIT");
+    }
+}
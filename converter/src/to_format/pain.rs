@@ -0,0 +1,362 @@
+//! Платежные поручения SEPA: ISO 20022 pain.001.001.03 и pain.001.001.09.
+//!
+//! Каждая транзакция — перевод `CdtTrfTxInf` получателю [`Transaction::counterparty`]
+//! на сумму `|amount|`. Переводы с одним счетом плательщика и датой исполнения
+//! ([`Transaction::value_date`]) собираются в блок `PmtInf`. Все IBAN, BIC и суммы
+//! проверяются до записи: файл с ошибкой банк отклонит целиком.
+
+use std::io::{self, Write};
+
+use chrono::{NaiveDate, Utc};
+use quick_xml::{
+    Writer,
+    events::{BytesDecl, BytesText, Event},
+};
+
+use super::camt::{text_element, truncate};
+use crate::{
+    error::ConvertError,
+    iban::{is_valid_bic, is_valid_iban, normalize_iban},
+    model::{Statement, Transaction},
+    money::{Money, overflow},
+    options::{PainVersion, SepaDebtor},
+};
+
+/// Валюта переводов SEPA.
+const SEPA_CURRENCY: &str = "EUR";
+
+/// Проверенный перевод.
+struct Payment {
+    end_to_end_id: String,
+    amount: Money,
+    creditor_name: String,
+    creditor_iban: String,
+    creditor_bic: Option<String>,
+    remittance: String,
+}
+
+/// Блок `PmtInf`: переводы с одного счета на одну дату.
+struct Batch {
+    debtor_iban: String,
+    date: NaiveDate,
+    payments: Vec<Payment>,
+}
+
+/// `CtrlSum`: сумма переводов с двумя знаками.
+fn control_sum<'a>(mut amounts: impl Iterator<Item = &'a Money>) -> Result<Money, ConvertError> {
+    amounts
+        .try_fold(Money::zero(2), |sum, amount| sum.checked_add(*amount))
+        .ok_or_else(overflow)
+}
+
+fn bic(value: Option<&str>, what: &str, problems: &mut Vec<String>) -> Option<String> {
+    let bic = value.map(|b| b.trim().to_ascii_uppercase()).filter(|b| !b.is_empty())?;
+    if !is_valid_bic(&bic) {
+        problems.push(format!("BIC {} {} некорректен", what, bic));
+    }
+    Some(bic)
+}
+
+fn iban(value: &str, what: &str, problems: &mut Vec<String>) -> String {
+    let iban = normalize_iban(value);
+    if iban.is_empty() {
+        problems.push(format!("не задан IBAN {}", what));
+    } else if !is_valid_iban(&iban) {
+        problems.push(format!("IBAN {} {} не проходит проверку", what, iban));
+    }
+    iban
+}
+
+/// Перевод из транзакции; ошибки добавляются в `problems`.
+fn payment(tx: &Transaction, problems: &mut Vec<String>) -> Payment {
+    let counterparty = tx.counterparty.clone().unwrap_or_default();
+    let creditor_name = counterparty.name.as_deref().map(str::trim).unwrap_or_default();
+    if creditor_name.is_empty() {
+        problems.push("не задано наименование получателя".to_string());
+    }
+    if tx.currency != SEPA_CURRENCY {
+        problems.push(format!("валюта {:?}, SEPA принимает только {}", tx.currency, SEPA_CURRENCY));
+    }
+    let amount = tx.amount.abs();
+    let amount = match amount.rescale(2) {
+        Some(amount) if !amount.is_zero() => amount,
+        _ => {
+            problems.push(format!("сумма {} должна быть больше нуля и не больше чем с двумя знаками", amount));
+            amount
+        }
+    };
+    let remittance = tx.remittance_information.as_deref().unwrap_or(&tx.description);
+    Payment {
+        end_to_end_id: tx
+            .customer_reference
+            .as_deref()
+            .map(str::trim)
+            .filter(|r| !r.is_empty())
+            .map_or("NOTPROVIDED", |r| truncate(r, 35))
+            .to_string(),
+        amount,
+        creditor_name: truncate(creditor_name, 70).to_string(),
+        creditor_iban: iban(counterparty.account.as_deref().unwrap_or_default(), "получателя", problems),
+        creditor_bic: bic(counterparty.bic.as_deref(), "получателя", problems),
+        remittance: truncate(&remittance.split_whitespace().collect::<Vec<_>>().join(" "), 140).to_string(),
+    }
+}
+
+/// Проверяет все переводы и собирает их в блоки; ошибки возвращаются одним списком.
+fn batches(statements: &[Statement], debtor: &SepaDebtor) -> Result<Vec<Batch>, ConvertError> {
+    let mut errors: Vec<String> = Vec::new();
+    let mut batches: Vec<Batch> = Vec::new();
+    for (index, tx) in statements.iter().flat_map(|st| &st.entries).enumerate() {
+        let mut problems = Vec::new();
+        let debtor_iban = iban(debtor.iban.as_deref().unwrap_or(&tx.account), "плательщика", &mut problems);
+        let payment = payment(tx, &mut problems);
+        if !problems.is_empty() {
+            errors.push(format!("платеж {}: {}", index + 1, problems.join(", ")));
+            continue;
+        }
+        match batches.iter_mut().find(|b| b.debtor_iban == debtor_iban && b.date == tx.value_date) {
+            Some(batch) => batch.payments.push(payment),
+            None => batches.push(Batch {
+                debtor_iban,
+                date: tx.value_date,
+                payments: vec![payment],
+            }),
+        }
+    }
+    if !errors.is_empty() {
+        return Err(ConvertError::InvalidAccount(format!("pain.001: {}", errors.join("; "))));
+    }
+    if batches.is_empty() {
+        return Err(ConvertError::InvalidAccount("pain.001: нет платежей".to_string()));
+    }
+    Ok(batches)
+}
+
+fn agent_element<W: Write>(w: &mut Writer<W>, name: &str, bic: Option<&str>, version: PainVersion) -> io::Result<()> {
+    w.create_element(name).write_inner_content(|w| {
+        w.create_element("FinInstnId").write_inner_content(|w| match bic {
+            Some(bic) => text_element(w, version.bic_element(), bic),
+            // Для плательщика агент обязателен; SEPA допускает его без BIC.
+            None => {
+                w.create_element("Othr")
+                    .write_inner_content(|w| text_element(w, "Id", "NOTPROVIDED"))?;
+                Ok(())
+            }
+        })?;
+        Ok(())
+    })?;
+    Ok(())
+}
+
+fn iban_element<W: Write>(w: &mut Writer<W>, name: &str, iban: &str) -> io::Result<()> {
+    w.create_element(name).write_inner_content(|w| {
+        w.create_element("Id").write_inner_content(|w| text_element(w, "IBAN", iban))?;
+        Ok(())
+    })?;
+    Ok(())
+}
+
+fn name_element<W: Write>(w: &mut Writer<W>, name: &str, text: &str) -> io::Result<()> {
+    w.create_element(name).write_inner_content(|w| text_element(w, "Nm", text))?;
+    Ok(())
+}
+
+fn write_payment<W: Write>(w: &mut Writer<W>, payment: &Payment, version: PainVersion) -> io::Result<()> {
+    w.create_element("CdtTrfTxInf").write_inner_content(|w| {
+        w.create_element("PmtId")
+            .write_inner_content(|w| text_element(w, "EndToEndId", &payment.end_to_end_id))?;
+        w.create_element("Amt").write_inner_content(|w| {
+            w.create_element("InstdAmt")
+                .with_attribute(("Ccy", SEPA_CURRENCY))
+                .write_text_content(BytesText::new(&payment.amount.to_string()))?;
+            Ok(())
+        })?;
+        if let Some(bic) = payment.creditor_bic.as_deref() {
+            agent_element(w, "CdtrAgt", Some(bic), version)?;
+        }
+        name_element(w, "Cdtr", &payment.creditor_name)?;
+        iban_element(w, "CdtrAcct", &payment.creditor_iban)?;
+        if !payment.remittance.is_empty() {
+            w.create_element("RmtInf")
+                .write_inner_content(|w| text_element(w, "Ustrd", &payment.remittance))?;
+        }
+        Ok(())
+    })?;
+    Ok(())
+}
+
+fn write_batch<W: Write>(
+    w: &mut Writer<W>,
+    id: &str,
+    (batch, sum): (&Batch, &Money),
+    debtor: &SepaDebtor,
+    debtor_bic: Option<&str>,
+    version: PainVersion,
+) -> io::Result<()> {
+    w.create_element("PmtInf").write_inner_content(|w| {
+        text_element(w, "PmtInfId", id)?;
+        text_element(w, "PmtMtd", "TRF")?;
+        text_element(w, "NbOfTxs", &batch.payments.len().to_string())?;
+        text_element(w, "CtrlSum", &sum.to_string())?;
+        w.create_element("PmtTpInf").write_inner_content(|w| {
+            w.create_element("SvcLvl").write_inner_content(|w| text_element(w, "Cd", "SEPA"))?;
+            Ok(())
+        })?;
+        let date = batch.date.format("%Y-%m-%d").to_string();
+        match version {
+            PainVersion::V03 => text_element(w, "ReqdExctnDt", &date)?,
+            PainVersion::V09 => {
+                w.create_element("ReqdExctnDt")
+                    .write_inner_content(|w| text_element(w, "Dt", &date))?;
+            }
+        }
+        name_element(w, "Dbtr", truncate(debtor.name.trim(), 70))?;
+        iban_element(w, "DbtrAcct", &batch.debtor_iban)?;
+        agent_element(w, "DbtrAgt", debtor_bic, version)?;
+        text_element(w, "ChrgBr", "SLEV")?;
+        batch.payments.iter().try_for_each(|payment| write_payment(w, payment, version))
+    })?;
+    Ok(())
+}
+
+/// Записывает все транзакции одним документом `CstmrCdtTrfInitn`.
+///
+/// `MsgId` — идентификатор первой выписки, если он есть, иначе `MSG-` и время создания.
+pub(crate) fn write_document<W: Write>(
+    statements: &[Statement],
+    version: PainVersion,
+    debtor: &SepaDebtor,
+    writer: W,
+) -> Result<(), ConvertError> {
+    let mut problems = Vec::new();
+    if debtor.name.trim().is_empty() {
+        problems.push("не задано наименование плательщика".to_string());
+    }
+    let debtor_bic = bic(debtor.bic.as_deref(), "плательщика", &mut problems);
+    if !problems.is_empty() {
+        return Err(ConvertError::InvalidAccount(format!("pain.001: {}", problems.join(", "))));
+    }
+    let batches = batches(statements, debtor)?;
+    let created_at = statements
+        .iter()
+        .find_map(|st| st.created_at)
+        .unwrap_or_else(|| Utc::now().naive_utc());
+    let message_id = match statements.first().map(|st| st.id.trim()).filter(|id| !id.is_empty()) {
+        Some(id) => truncate(id, 30).to_string(),
+        None => format!("MSG-{}", created_at.format("%Y%m%d%H%M%S")),
+    };
+    let count: usize = batches.iter().map(|b| b.payments.len()).sum();
+    let sums = batches
+        .iter()
+        .map(|b| control_sum(b.payments.iter().map(|p| &p.amount)))
+        .collect::<Result<Vec<_>, _>>()?;
+    let total = control_sum(sums.iter())?;
+
+    let mut w = Writer::new_with_indent(writer, b' ', 2);
+    w.write_event(Event::Decl(BytesDecl::new("1.0", Some("UTF-8"), None)))?;
+    w.create_element("Document")
+        .with_attribute(("xmlns", version.namespace().as_str()))
+        .write_inner_content(|w| {
+            w.create_element("CstmrCdtTrfInitn").write_inner_content(|w| {
+                w.create_element("GrpHdr").write_inner_content(|w| {
+                    text_element(w, "MsgId", &message_id)?;
+                    text_element(w, "CreDtTm", &created_at.format("%Y-%m-%dT%H:%M:%S").to_string())?;
+                    text_element(w, "NbOfTxs", &count.to_string())?;
+                    text_element(w, "CtrlSum", &total.to_string())?;
+                    name_element(w, "InitgPty", truncate(debtor.name.trim(), 70))
+                })?;
+                batches.iter().zip(&sums).enumerate().try_for_each(|(index, batch)| {
+                    let id = format!("{}-{}", message_id, index + 1);
+                    write_batch(w, &id, batch, debtor, debtor_bic.as_deref(), version)
+                })
+            })?;
+            Ok(())
+        })?;
+    writeln!(w.get_mut())?;
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::model::Counterparty;
+
+    fn payment_tx(amount: i64, iban: &str, day: u32) -> Transaction {
+        Transaction {
+            reference: "PAY-2023-01".to_string(),
+            account: "DE89 3704 0044 0532 0130 00".to_string(),
+            amount: Money::new(amount, 2),
+            currency: "EUR".to_string(),
+            value_date: NaiveDate::from_ymd_opt(2023, 1, day).unwrap(),
+            customer_reference: Some("INV-1".to_string()),
+            counterparty: Some(Counterparty {
+                name: Some("Müller & Söhne".to_string()),
+                account: Some(iban.to_string()),
+                bic: Some("gkccbebb".to_string()),
+                ..Default::default()
+            }),
+            description: "Invoice 1".to_string(),
+            ..Default::default()
+        }
+    }
+
+    fn debtor() -> SepaDebtor {
+        SepaDebtor {
+            name: "ACME GmbH".to_string(),
+            bic: Some("COBADEFFXXX".to_string()),
+            ..Default::default()
+        }
+    }
+
+    #[test]
+    fn batches_by_date_with_control_sums() {
+        let statement = Statement::from_entries(vec![
+            payment_tx(-120000, "BE68539007547034", 2),
+            payment_tx(5050, "BE68 5390 0754 7034", 2),
+            payment_tx(100, "GB82WEST12345698765432", 3),
+        ]);
+        for version in [PainVersion::V03, PainVersion::V09] {
+            let mut buffer = Vec::new();
+            write_document(std::slice::from_ref(&statement), version, &debtor(), &mut buffer).unwrap();
+            let xml = String::from_utf8(buffer).unwrap();
+            assert!(xml.contains(&format!("<Document xmlns=\"{}\">", version.namespace())), "{}", xml);
+            assert!(xml.contains("<MsgId>PAY-2023-01</MsgId>"), "{}", xml);
+            assert!(xml.contains("<NbOfTxs>3</NbOfTxs>\n      <CtrlSum>1251.50</CtrlSum>"), "{}", xml);
+            assert!(xml.contains("<PmtInfId>PAY-2023-01-1</PmtInfId>"), "{}", xml);
+            assert!(xml.contains("<NbOfTxs>2</NbOfTxs>\n      <CtrlSum>1250.50</CtrlSum>"), "{}", xml);
+            assert!(xml.contains("<PmtInfId>PAY-2023-01-2</PmtInfId>"), "{}", xml);
+            assert!(xml.contains("<InstdAmt Ccy=\"EUR\">1200.00</InstdAmt>"), "{}", xml);
+            assert!(xml.contains("<IBAN>DE89370400440532013000</IBAN>"), "{}", xml);
+            assert!(xml.contains("<Nm>Müller &amp; Söhne</Nm>"), "{}", xml);
+            assert!(xml.contains("<EndToEndId>INV-1</EndToEndId>"), "{}", xml);
+            assert!(xml.contains("<Ustrd>Invoice 1</Ustrd>"), "{}", xml);
+            let (bic, date) = match version {
+                PainVersion::V03 => ("<BIC>GKCCBEBB</BIC>", "<ReqdExctnDt>2023-01-02</ReqdExctnDt>"),
+                PainVersion::V09 => ("<BICFI>GKCCBEBB</BICFI>", "<ReqdExctnDt>\n        <Dt>2023-01-02</Dt>"),
+            };
+            assert!(xml.contains(bic) && xml.contains(date), "{}", xml);
+        }
+    }
+
+    #[test]
+    fn invalid_accounts_are_reported_before_writing() {
+        let mut wrong = payment_tx(-100, "BE68539007547035", 2);
+        wrong.counterparty.as_mut().unwrap().bic = Some("GKCC".to_string());
+        let mut foreign = payment_tx(-100, "BE68539007547034", 2);
+        foreign.currency = "USD".to_string();
+        let statement = Statement::from_entries(vec![payment_tx(-100, "BE68539007547034", 2), wrong, foreign]);
+        let mut buffer = Vec::new();
+        let error = write_document(&[statement], PainVersion::V09, &debtor(), &mut buffer).unwrap_err();
+        let message = error.to_string();
+        assert!(matches!(error, ConvertError::InvalidAccount(_)));
+        assert!(message.contains("платеж 2: IBAN получателя BE68539007547035"), "{}", message);
+        assert!(message.contains("BIC получателя GKCC"), "{}", message);
+        assert!(message.contains("платеж 3: валюта \"USD\""), "{}", message);
+        assert!(!message.contains("платеж 1"), "{}", message);
+        assert!(buffer.is_empty());
+
+        let unnamed = SepaDebtor::default();
+        let statement = Statement::from_entries(vec![payment_tx(-100, "BE68539007547034", 2)]);
+        assert!(write_document(&[statement], PainVersion::V03, &unnamed, &mut buffer).is_err());
+    }
+}
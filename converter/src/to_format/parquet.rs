@@ -0,0 +1,86 @@
+//! Запись транзакций в Apache Parquet по схеме [`crate::transaction_schema`].
+
+use std::io::{self, Write};
+
+use parquet::{
+    arrow::ArrowWriter,
+    basic::Compression,
+    errors::ParquetError,
+    file::properties::{EnabledStatistics, WriterProperties},
+};
+
+use crate::{
+    arrow::{record_batch, transaction_schema},
+    error::ConvertError,
+    model::Statement,
+};
+
+fn parquet_error(error: ParquetError) -> ConvertError {
+    ConvertError::Io(io::Error::other(error))
+}
+
+/// Записывает файл: по группе строк на выписку, сжатие Snappy, статистика по страницам
+/// (DuckDB и Spark отбрасывают страницы по датам и суммам без чтения).
+pub(crate) fn write_file<W: Write>(statements: &[Statement], mut writer: W) -> Result<(), ConvertError> {
+    let properties = WriterProperties::builder()
+        .set_compression(Compression::SNAPPY)
+        .set_statistics_enabled(EnabledStatistics::Page)
+        .build();
+    // ArrowWriter требует `Send`, поэтому файл собирается в памяти.
+    let mut buffer = Vec::new();
+    let mut file = ArrowWriter::try_new(&mut buffer, transaction_schema(), Some(properties)).map_err(parquet_error)?;
+    for statement in statements.iter().filter(|st| !st.entries.is_empty()) {
+        file.write(&record_batch(&statement.entries)?).map_err(parquet_error)?;
+        file.flush().map_err(parquet_error)?;
+    }
+    file.close().map_err(parquet_error)?;
+    writer.write_all(&buffer)?;
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use arrow_array::{Array, cast::AsArray, types::Int32Type};
+    use bytes::Bytes;
+    use chrono::NaiveDate;
+    use parquet::arrow::arrow_reader::ParquetRecordBatchReaderBuilder;
+
+    use super::*;
+    use crate::{model::Transaction, money::Money};
+
+    #[test]
+    fn statements_become_row_groups() {
+        let tx = |reference: &str, amount: i64| Transaction {
+            reference: reference.to_string(),
+            account: "ACC".to_string(),
+            amount: Money::new(amount, 2),
+            currency: "EUR".to_string(),
+            value_date: NaiveDate::from_ymd_opt(2023, 1, 2).unwrap(),
+            booking_date: NaiveDate::from_ymd_opt(2023, 1, 3),
+            ..Default::default()
+        };
+        let statements = [
+            Statement::from_entries(vec![tx("ST1", -10010), tx("ST1", 500)]),
+            Statement::default(),
+            Statement::from_entries(vec![tx("ST2", 1)]),
+        ];
+        let mut buffer = Vec::new();
+        write_file(&statements, &mut buffer).unwrap();
+
+        let builder = ParquetRecordBatchReaderBuilder::try_new(Bytes::from(buffer)).unwrap();
+        assert_eq!(builder.metadata().num_row_groups(), 2);
+        assert_eq!(builder.schema(), &transaction_schema());
+        let batches: Vec<_> = builder.build().unwrap().collect::<Result<_, _>>().unwrap();
+        let rows: usize = batches.iter().map(|b| b.num_rows()).sum();
+        assert_eq!(rows, 3);
+        let first = &batches[0];
+        let amounts = first
+            .column_by_name("amount")
+            .unwrap()
+            .as_primitive::<arrow_array::types::Decimal128Type>();
+        assert_eq!(amounts.value_as_string(0), "-100.1000");
+        let currencies = first.column_by_name("currency").unwrap().as_dictionary::<Int32Type>();
+        assert_eq!(currencies.values().as_string::<i32>().value(0), "EUR");
+        assert!(!first.column_by_name("booking_date").unwrap().is_null(0));
+    }
+}
@@ -0,0 +1,134 @@
+//! Запись выписок в QIF: раздел `!Type:Bank` на каждую выписку.
+
+use std::io::Write;
+
+use crate::{
+    error::ConvertError,
+    model::{Statement, Transaction},
+    options::QifDateFormat,
+};
+
+/// Строка `<код><значение>`; перевод строки в значении разорвал бы запись.
+fn field(text: &mut String, code: char, value: &str) {
+    text.push(code);
+    text.push_str(&value.replace(['\r', '\n'], " "));
+    text.push('\n');
+}
+
+fn write_transaction(text: &mut String, tx: &Transaction, date_format: QifDateFormat) {
+    let date = match date_format {
+        QifDateFormat::Us => tx.value_date.format("%m/%d/%Y"),
+        QifDateFormat::European => tx.value_date.format("%d/%m/%Y"),
+    };
+    field(text, 'D', &date.to_string());
+    field(text, 'T', &tx.amount.to_string());
+    if let Some(number) = &tx.customer_reference {
+        field(text, 'N', number);
+    }
+    let payee = tx.counterparty.as_ref().and_then(|c| c.name.as_deref());
+    if let Some(payee) = payee {
+        field(text, 'P', payee);
+    }
+    let memo = tx.remittance_information.as_deref().unwrap_or(&tx.description);
+    if !memo.trim().is_empty() && Some(memo) != payee {
+        field(text, 'M', memo);
+    }
+    if let Some(category) = &tx.category {
+        field(text, 'L', category);
+    }
+    for split in &tx.splits {
+        field(text, 'S', split.category.as_deref().unwrap_or_default());
+        if let Some(memo) = &split.memo {
+            field(text, 'E', memo);
+        }
+        field(text, '$', &split.amount.to_string());
+    }
+    text.push_str("^\n");
+}
+
+/// Записывает выписки; счет с именем предваряется записью `!Account`.
+pub(crate) fn write_file<W: Write>(statements: &[Statement], date_format: QifDateFormat, mut writer: W) -> Result<(), ConvertError> {
+    let mut text = String::new();
+    for statement in statements {
+        if !statement.account.is_empty() {
+            text.push_str("!Account\n");
+            field(&mut text, 'N', &statement.account);
+            field(&mut text, 'T', "Bank");
+            text.push_str("^\n");
+        }
+        text.push_str("!Type:Bank\n");
+        for tx in &statement.entries {
+            write_transaction(&mut text, tx, date_format);
+        }
+    }
+    writer.write_all(text.as_bytes())?;
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use chrono::NaiveDate;
+    use parser::{Parser, QifParser};
+
+    use super::*;
+    use crate::{
+        FromParser,
+        model::{Counterparty, Split},
+        money::Money,
+        options::ConvertOptions,
+    };
+
+    #[test]
+    fn round_trip_with_splits() {
+        let tx = Transaction {
+            reference: "S1".to_string(),
+            account: "Checking".to_string(),
+            amount: Money::new(-4250, 2),
+            currency: "XXX".to_string(),
+            value_date: NaiveDate::from_ymd_opt(2023, 1, 5).unwrap(),
+            customer_reference: Some("77".to_string()),
+            counterparty: Some(Counterparty {
+                name: Some("Cafe".to_string()),
+                ..Default::default()
+            }),
+            splits: vec![
+                Split {
+                    category: Some("Food".to_string()),
+                    memo: Some("Breakfast".to_string()),
+                    amount: Money::new(-4000, 2),
+                },
+                Split {
+                    category: Some("Fees".to_string()),
+                    memo: None,
+                    amount: Money::new(-250, 2),
+                },
+            ],
+            description: "Card 1234".to_string(),
+            ..Default::default()
+        };
+        for date_format in [QifDateFormat::Us, QifDateFormat::European] {
+            let mut buffer = Vec::new();
+            write_file(&[Statement::from_entries(vec![tx.clone()])], date_format, &mut buffer).unwrap();
+            let text = String::from_utf8(buffer.clone()).unwrap();
+            let expected_date = if date_format == QifDateFormat::Us {
+                "D01/05/2023\n"
+            } else {
+                "D05/01/2023\n"
+            };
+            assert!(text.starts_with("!Account\nNChecking\nTBank\n^\n!Type:Bank\n"), "{}", text);
+            assert!(text.contains(expected_date), "{}", text);
+
+            let options = ConvertOptions {
+                qif_date_format: date_format,
+                ..Default::default()
+            };
+            let parsed = QifParser::parse(&buffer[..]).unwrap().to_statements(&options).unwrap();
+            let entry = &parsed[0].entries[0];
+            assert_eq!(entry.value_date, tx.value_date);
+            assert_eq!(entry.amount, tx.amount);
+            assert_eq!(entry.counterparty, tx.counterparty);
+            assert_eq!(entry.description, tx.description);
+            assert_eq!(entry.splits, tx.splits);
+        }
+    }
+}
@@ -0,0 +1,169 @@
+//! Запись транзакций в лист xlsx: суммы — числа с форматом валюты, даты — ячейки-даты.
+
+use std::io::{self, Write};
+
+use chrono::{Datelike, NaiveDate};
+use rust_xlsxwriter::{ExcelDateTime, Format, Workbook, Worksheet, XlsxError};
+
+use crate::{
+    error::ConvertError,
+    model::{Statement, Transaction},
+    money::currency_exponent,
+};
+
+/// Имя листа с транзакциями.
+pub(crate) const SHEET_NAME: &str = "Statement";
+
+/// Заголовки колонок: первые шесть совпадают с CSV, по ним же читает профиль с именами колонок.
+const HEADER: [&str; 11] = [
+    "reference",
+    "account",
+    "amount",
+    "currency",
+    "date",
+    "description",
+    "booking_date",
+    "counterparty",
+    "counterparty_account",
+    "counterparty_bic",
+    "bank_reference",
+];
+
+fn xlsx_error(error: XlsxError) -> ConvertError {
+    ConvertError::Io(io::Error::other(error))
+}
+
+/// Формат суммы: разряды, знаки по валюте и код валюты, `#,##0.00 "EUR"`.
+fn amount_format(currency: &str) -> Format {
+    let exponent = usize::from(currency_exponent(currency));
+    let digits = if exponent == 0 {
+        "#,##0".to_string()
+    } else {
+        format!("#,##0.{}", "0".repeat(exponent))
+    };
+    match currency.trim() {
+        "" => Format::new().set_num_format(digits),
+        code => Format::new().set_num_format(format!("{} \"{}\"", digits, code.replace('"', ""))),
+    }
+}
+
+fn write_date(sheet: &mut Worksheet, row: u32, col: u16, date: NaiveDate, format: &Format) -> Result<(), XlsxError> {
+    let date = ExcelDateTime::from_ymd(date.year() as u16, date.month() as u8, date.day() as u8)?;
+    sheet.write_datetime_with_format(row, col, date, format)?;
+    Ok(())
+}
+
+fn write_row(sheet: &mut Worksheet, row: u32, tx: &Transaction, amount: &Format, date: &Format) -> Result<(), XlsxError> {
+    let counterparty = tx.counterparty.clone().unwrap_or_default();
+    sheet.write_string(row, 0, &tx.reference)?;
+    sheet.write_string(row, 1, &tx.account)?;
+    // Через десятичную строку: ближайшее к сумме число, без накопления ошибки деления.
+    let value: f64 = tx.amount.to_string().parse().unwrap_or_default();
+    sheet.write_number_with_format(row, 2, value, amount)?;
+    sheet.write_string(row, 3, &tx.currency)?;
+    write_date(sheet, row, 4, tx.value_date, date)?;
+    sheet.write_string(row, 5, &tx.description)?;
+    if let Some(booking_date) = tx.booking_date {
+        write_date(sheet, row, 6, booking_date, date)?;
+    }
+    let optional = [counterparty.name, counterparty.account, counterparty.bic, tx.bank_reference.clone()];
+    for (col, value) in (7..).zip(optional) {
+        if let Some(value) = value {
+            sheet.write_string(row, col, value)?;
+        }
+    }
+    Ok(())
+}
+
+fn write_workbook(statements: &[Statement]) -> Result<Vec<u8>, XlsxError> {
+    let mut workbook = Workbook::new();
+    let sheet = workbook.add_worksheet();
+    sheet.set_name(SHEET_NAME)?;
+    let bold = Format::new().set_bold();
+    for (col, name) in (0..).zip(HEADER) {
+        sheet.write_string_with_format(0, col, name, &bold)?;
+    }
+    let date = Format::new().set_num_format("yyyy-mm-dd");
+    let mut formats: Vec<(String, Format)> = Vec::new();
+    let mut row = 0;
+    for tx in statements.iter().flat_map(|st| &st.entries) {
+        row += 1;
+        let index = match formats.iter().position(|(currency, _)| *currency == tx.currency) {
+            Some(index) => index,
+            None => {
+                formats.push((tx.currency.clone(), amount_format(&tx.currency)));
+                formats.len() - 1
+            }
+        };
+        write_row(sheet, row, tx, &formats[index].1, &date)?;
+    }
+    sheet.set_freeze_panes(1, 0)?;
+    sheet.autofilter(0, 0, row, HEADER.len() as u16 - 1)?;
+    for (col, width) in [(0, 16), (1, 24), (2, 16), (4, 12), (5, 48), (6, 12), (7, 32), (8, 28)] {
+        sheet.set_column_width(col, width)?;
+    }
+    workbook.save_to_buffer()
+}
+
+/// Записывает книгу с одним листом [`SHEET_NAME`]: транзакции всех выписок.
+pub(crate) fn write_file<W: Write>(statements: &[Statement], mut writer: W) -> Result<(), ConvertError> {
+    writer.write_all(&write_workbook(statements).map_err(xlsx_error)?)?;
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use parser::{Parser, XlsxCell, XlsxParser};
+
+    use super::*;
+    use crate::{CsvMapping, FromParser, model::Counterparty, money::Money, options::ConvertOptions};
+
+    #[test]
+    fn typed_cells_round_trip_through_csv_mapping() {
+        let tx = |amount: i64, currency: &str, description: &str| Transaction {
+            reference: "ST1".to_string(),
+            account: "ACC".to_string(),
+            amount: Money::new(amount, currency_exponent(currency)),
+            currency: currency.to_string(),
+            value_date: NaiveDate::from_ymd_opt(2023, 1, 2).unwrap(),
+            description: description.to_string(),
+            ..Default::default()
+        };
+        let mut paid = tx(-123456, "EUR", "Rent");
+        paid.counterparty = Some(Counterparty {
+            name: Some("Landlord".to_string()),
+            ..Default::default()
+        });
+        let statements = [Statement::from_entries(vec![paid, tx(5000, "JPY", "Refund")])];
+        let mut buffer = Vec::new();
+        write_file(&statements, &mut buffer).unwrap();
+
+        let parser = XlsxParser::parse(&buffer[..]).unwrap();
+        assert_eq!(parser.sheet, SHEET_NAME);
+        assert_eq!(parser.rows[1][2], XlsxCell::Number(-1234.56));
+        assert_eq!(parser.rows[1][4], XlsxCell::Date("2023-01-02".to_string()));
+        assert!(XlsxParser::parse_sheet(&buffer[..], Some("Other")).is_err());
+
+        let options = ConvertOptions {
+            csv_mapping: CsvMapping::from_toml(
+                r#"
+                reference = "reference"
+                account = "account"
+                amount = "amount"
+                currency = "currency"
+                value_date = "date"
+                description = "description"
+                counterparty_name = "counterparty"
+                "#,
+            )
+            .unwrap(),
+            ..Default::default()
+        };
+        let txs = parser.to_transactions(&options).unwrap();
+        assert_eq!(txs.len(), 2);
+        assert_eq!(txs[0].amount, Money::new(-123456, 2));
+        assert_eq!(txs[0].counterparty.as_ref().unwrap().name.as_deref(), Some("Landlord"));
+        assert_eq!(txs[1].amount, Money::new(5000, 0));
+        assert_eq!(txs[1].value_date, NaiveDate::from_ymd_opt(2023, 1, 2).unwrap());
+    }
+}
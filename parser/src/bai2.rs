@@ -0,0 +1,264 @@
+//! BAI2: логические записи (с продолжениями `88`), иерархия файл → группа → счет
+//! и проверка контрольных сумм и количества записей в `49`/`98`/`99`.
+
+use crate::ParseError;
+
+fn invalid(msg: &str) -> ParseError {
+    ParseError::InvalidFormat(format!("BAI2 invalid. {}", msg))
+}
+
+/// Logical BAI2 record: physical record with its `88` continuations merged.
+#[derive(Debug, Clone, PartialEq, Default)]
+pub struct Bai2Record {
+    /// Record code: `01`, `02`, `03`, `16`...
+    pub code: String,
+    /// Fields after the record code, without the `/` terminator.
+    /// For `16` the last field is the free text, commas included.
+    pub fields: Vec<String>,
+}
+
+impl Bai2Record {
+    /// Field by index; empty fields are `None`.
+    pub fn field(&self, index: usize) -> Option<&str> {
+        self.fields.get(index).map(|f| f.trim()).filter(|f| !f.is_empty())
+    }
+}
+
+/// `03` account identifier with its `16` transaction details.
+#[derive(Debug, Clone, PartialEq, Default)]
+pub struct Bai2Account {
+    /// `03` record: account number, currency, then summary/status groups.
+    pub identifier: Bai2Record,
+    /// `16` records.
+    pub details: Vec<Bai2Record>,
+}
+
+/// `02` group header with its accounts.
+#[derive(Debug, Clone, PartialEq, Default)]
+pub struct Bai2Group {
+    /// `02` record: receiver, originator, status, as-of date/time, currency.
+    pub header: Bai2Record,
+    /// Accounts of the group.
+    pub accounts: Vec<Bai2Account>,
+}
+
+/// Количество полей после кода доступности средств: `V` — дата и время,
+/// `S` — три суммы, `D` — число и пары (дни, сумма). `None`, если число пар `D` переполняет счетчик.
+pub(crate) fn funds_type_fields(funds_type: &str, following: &[&str]) -> Option<usize> {
+    match funds_type.trim() {
+        "V" => Some(2),
+        "S" => Some(3),
+        "D" => {
+            let count: usize = following.first().and_then(|n| n.trim().parse().ok()).unwrap_or(0);
+            count.checked_mul(2)?.checked_add(1)
+        }
+        _ => Some(0),
+    }
+}
+
+/// Делит тело записи на поля. У `16` текст — все после ссылок, включая запятые.
+fn split_fields(code: &str, body: &str) -> Vec<String> {
+    if code != "16" {
+        let body = body.strip_suffix('/').unwrap_or(body);
+        return body.split(',').map(str::to_string).collect();
+    }
+    let parts: Vec<&str> = body.split(',').collect();
+    // Код операции, сумма, доступность, ее поля, ссылка банка, ссылка клиента.
+    let funds = parts.get(2).copied().unwrap_or_default();
+    let fixed = funds_type_fields(funds, parts.get(3..).unwrap_or_default())
+        .and_then(|extra| extra.checked_add(5))
+        .map_or(parts.len(), |fixed| fixed.min(parts.len()));
+    let mut fields: Vec<String> = parts[..fixed].iter().map(|f| f.to_string()).collect();
+    let text = parts[fixed..].join(",");
+    fields.push(text.strip_suffix('/').unwrap_or(&text).to_string());
+    fields
+}
+
+/// Логические записи и число физических строк каждой (для счетчиков в трейлерах).
+fn logical_records(text: &str) -> Result<Vec<(Bai2Record, usize)>, ParseError> {
+    let mut raw: Vec<(String, String, usize)> = Vec::new();
+    for line in text.lines().map(str::trim).filter(|l| !l.is_empty()) {
+        let (code, body) = line.split_once(',').unwrap_or((line.trim_end_matches('/'), ""));
+        if code == "88" {
+            let (_, previous, count) = raw.last_mut().ok_or_else(|| invalid("88 без предыдущей записи"))?;
+            // Поля продолжаются через запятую; незавершенный `/` текст `16` — через пробел.
+            match previous.strip_suffix('/') {
+                Some(stripped) => *previous = format!("{},{}", stripped, body),
+                None => *previous = format!("{} {}", previous, body),
+            }
+            *count += 1;
+        } else if code.len() == 2 && code.bytes().all(|b| b.is_ascii_digit()) {
+            raw.push((code.to_string(), body.to_string(), 1));
+        } else {
+            return Err(invalid(&format!("неизвестная запись: {}", line)));
+        }
+    }
+    Ok(raw
+        .into_iter()
+        .map(|(code, body, count)| {
+            let fields = split_fields(&code, &body);
+            (Bai2Record { code, fields }, count)
+        })
+        .collect())
+}
+
+/// Сумма BAI2 в минимальных единицах: `+150000`, `-25`, пусто — 0.
+pub(crate) fn parse_amount(value: &str) -> Result<i64, ParseError> {
+    let value = value.trim();
+    if value.is_empty() {
+        return Ok(0);
+    }
+    let digits = value.strip_prefix('+').unwrap_or(value);
+    digits.parse().map_err(|_| invalid(&format!("некорректная сумма: {}", value)))
+}
+
+/// Сложение контрольных сумм без переполнения.
+fn add(total: i64, value: i64) -> Result<i64, ParseError> {
+    total.checked_add(value).ok_or_else(|| invalid("переполнение контрольной суммы"))
+}
+
+/// Суммы `03`: второе поле каждой группы (код, сумма, количество, доступность[, ...]).
+fn identifier_total(record: &Bai2Record) -> Result<i64, ParseError> {
+    let fields: Vec<&str> = record.fields.iter().map(String::as_str).collect();
+    let mut total: i64 = 0;
+    let mut index = 2;
+    while index + 1 < fields.len() {
+        total = add(total, parse_amount(fields[index + 1])?)?;
+        let funds = fields.get(index + 3).copied().unwrap_or_default();
+        index = funds_type_fields(funds, fields.get(index + 4..).unwrap_or_default())
+            .and_then(|extra| index.checked_add(4)?.checked_add(extra))
+            .ok_or_else(|| invalid(&format!("слишком большое число сумм доступности в записи {}", record.code)))?;
+    }
+    Ok(total)
+}
+
+fn check(record: &Bai2Record, total: i64, total_index: usize, records: usize, records_index: usize) -> Result<(), ParseError> {
+    let expected_total = parse_amount(record.field(total_index).unwrap_or_default())?;
+    if expected_total != total {
+        return Err(invalid(&format!(
+            "контрольная сумма {} в записи {}: ожидалось {}",
+            total, record.code, expected_total
+        )));
+    }
+    if let Some(expected) = record.field(records_index)
+        && expected.parse::<usize>().ok() != Some(records)
+    {
+        return Err(invalid(&format!(
+            "число записей {} в записи {}: ожидалось {}",
+            records, record.code, expected
+        )));
+    }
+    Ok(())
+}
+
+/// Заголовок файла и группы; трейлеры проверяются и отбрасываются.
+pub(crate) fn parse_file(text: &str) -> Result<(Bai2Record, Vec<Bai2Group>), ParseError> {
+    let records = logical_records(text)?;
+    let mut iter = records.into_iter();
+    let (header, mut file_records) = match iter.next() {
+        Some((record, count)) if record.code == "01" => (record, count),
+        _ => return Err(invalid("файл должен начинаться с записи 01")),
+    };
+    let mut groups: Vec<Bai2Group> = Vec::new();
+    let (mut file_total, mut group_total, mut account_total) = (0i64, 0i64, 0i64);
+    let (mut group_records, mut account_records) = (0usize, 0usize);
+    let (mut group_open, mut account_open, mut trailer_seen) = (false, false, false);
+
+    for (record, count) in iter {
+        if trailer_seen {
+            return Err(invalid("записи после 99"));
+        }
+        file_records += count;
+        match record.code.as_str() {
+            "02" if !group_open => {
+                groups.push(Bai2Group {
+                    header: record,
+                    accounts: Vec::new(),
+                });
+                (group_open, group_total, group_records) = (true, 0, count);
+            }
+            "03" if group_open && !account_open => {
+                account_total = identifier_total(&record)?;
+                account_records = count;
+                account_open = true;
+                let group = groups.last_mut().expect("группа открыта");
+                group.accounts.push(Bai2Account {
+                    identifier: record,
+                    details: Vec::new(),
+                });
+            }
+            "16" if account_open => {
+                account_total = add(account_total, parse_amount(record.field(1).unwrap_or_default())?)?;
+                account_records += count;
+                let account = groups.last_mut().and_then(|g| g.accounts.last_mut()).expect("счет открыт");
+                account.details.push(record);
+            }
+            "49" if account_open => {
+                account_records += count;
+                check(&record, account_total, 0, account_records, 1)?;
+                group_total = add(group_total, account_total)?;
+                group_records += account_records;
+                account_open = false;
+            }
+            "98" if group_open && !account_open => {
+                group_records += count;
+                check(&record, group_total, 0, group_records, 2)?;
+                file_total = add(file_total, group_total)?;
+                group_open = false;
+            }
+            "99" if !group_open => {
+                check(&record, file_total, 0, file_records, 2)?;
+                trailer_seen = true;
+            }
+            code => return Err(invalid(&format!("запись {} не на своем месте", code))),
+        }
+    }
+    if !trailer_seen {
+        return Err(invalid("нет записи 99"));
+    }
+    Ok((header, groups))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn continuation_and_detail_text() {
+        let text = "01,A,B,230131,1200,1,,,2/\n03,123,USD,010,100,,/\n88,015,+250,,/\n\
+                    16,165,150,V,230201,,BANKREF,CUST,Payment, ref 1\n88,continued/\n";
+        let records = logical_records(text).unwrap();
+        assert_eq!(records.len(), 3);
+        let (identifier, count) = &records[1];
+        assert_eq!(*count, 2);
+        assert_eq!(identifier.fields, ["123", "USD", "010", "100", "", "", "015", "+250", "", ""]);
+        assert_eq!(identifier_total(identifier).unwrap(), 350);
+        let detail = &records[2].0;
+        assert_eq!(detail.field(3), Some("230201"));
+        assert_eq!(detail.field(5), Some("BANKREF"));
+        assert_eq!(detail.field(7), Some("Payment, ref 1 continued"));
+    }
+
+    #[test]
+    fn trailer_totals_are_checked() {
+        let file = "01,A,B,230131,1200,1,,,2/\n02,B,A,1,230131,,USD,/\n03,123,USD,015,500,,/\n\
+                    16,165,500,Z,R1,,Deposit\n49,1000,3/\n98,1000,1,5/\n99,1000,1,7/\n";
+        let (_, groups) = parse_file(file).unwrap();
+        assert_eq!(groups[0].accounts[0].details.len(), 1);
+        assert!(parse_file(&file.replace("49,1000,3/", "49,999,3/")).is_err());
+        assert!(parse_file(&file.replace("99,1000,1,7/", "99,1000,1,8/")).is_err());
+        assert!(parse_file(&file.replace("99,1000,1,7/\n", "")).is_err());
+    }
+
+    #[test]
+    fn totals_and_funds_type_counts_do_not_overflow() {
+        let file = "01,A,B,230131,1200,1,,,2/\n02,B,A,1,230131,,USD,/\n03,123,USD,015,9223372036854775807,,/\n\
+                    16,165,1,Z,R1,,Deposit\n49,0,3/\n98,0,1,5/\n99,0,1,7/\n";
+        assert!(parse_file(file).is_err());
+        let huge = "03,123,USD,015,1,,D,9223372036854775807,1,1/\n";
+        let records = logical_records(huge).unwrap();
+        assert!(identifier_total(&records[0].0).is_err());
+        assert_eq!(funds_type_fields("D", &["18446744073709551615"]), None);
+        assert_eq!(split_fields("16", "165,1,D,18446744073709551615,1,1").len(), 7);
+    }
+}
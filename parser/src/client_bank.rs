@@ -0,0 +1,138 @@
+//! 1CClientBankExchange: заголовок, секции `СекцияРасчСчет` и `СекцияДокумент`
+//! в Windows-1251 или, при `Кодировка=DOS`, в CP866.
+
+use std::io::Read;
+
+use crate::{ParseError, Parser, text};
+
+/// A section of a 1C file: header, `СекцияРасчСчет` or `СекцияДокумент=<kind>`.
+#[derive(Debug, Clone, PartialEq, Default)]
+pub struct ClientBank1CSection {
+    /// Document kind (`Платежное поручение`), `РасчСчет` for account sections, empty for the header.
+    pub kind: String,
+    /// `Key=Value` pairs in file order.
+    pub fields: Vec<(String, String)>,
+}
+
+impl ClientBank1CSection {
+    /// First non-empty value of `key`.
+    pub fn get(&self, key: &str) -> Option<&str> {
+        self.fields
+            .iter()
+            .filter(|(k, _)| k == key)
+            .map(|(_, v)| v.trim())
+            .find(|v| !v.is_empty())
+    }
+
+    /// All non-empty values of `key`, e.g. every `РасчСчет` in the header.
+    pub fn get_all<'a>(&'a self, key: &'a str) -> impl Iterator<Item = &'a str> {
+        self.fields
+            .iter()
+            .filter(move |(k, _)| k == key)
+            .map(|(_, v)| v.trim())
+            .filter(|v| !v.is_empty())
+    }
+}
+
+/// A parser for the 1C:Enterprise `1CClientBankExchange` text format.
+#[derive(Debug)]
+pub struct ClientBank1CParser {
+    /// File header: `ВерсияФормата`, `Кодировка`, `ДатаНачала`, `РасчСчет`...
+    pub header: ClientBank1CSection,
+    /// `СекцияРасчСчет` sections with balances.
+    pub accounts: Vec<ClientBank1CSection>,
+    /// `СекцияДокумент` sections.
+    pub documents: Vec<ClientBank1CSection>,
+}
+
+impl Parser for ClientBank1CParser {
+    fn parse<R: Read>(mut input: R) -> Result<Self, ParseError> {
+        let invalid = |msg: &str| ParseError::InvalidFormat(format!("1CClientBankExchange invalid. {}", msg));
+        let mut bytes = Vec::new();
+        input.read_to_end(&mut bytes).map_err(ParseError::Io)?;
+        // `Кодировка=DOS` — CP866: проверяем, прочтя файл в CP866, иначе по умолчанию Windows-1251.
+        let dos = encoding_rs::IBM866.decode_without_bom_handling(&bytes).0;
+        let encoding = dos.lines().any(|l| l.trim() == "Кодировка=DOS").then_some(encoding_rs::IBM866);
+        let (text, _) = text::decode(&bytes, encoding)?;
+
+        let mut lines = text.lines().map(str::trim).filter(|l| !l.is_empty());
+        if lines.next() != Some("1CClientBankExchange") {
+            return Err(invalid("Нет строки 1CClientBankExchange"));
+        }
+        let mut header = ClientBank1CSection::default();
+        let mut accounts = Vec::new();
+        let mut documents = Vec::new();
+        let mut current: Option<ClientBank1CSection> = None;
+        for line in lines {
+            let (key, value) = line.split_once('=').unwrap_or((line, ""));
+            match (key, &mut current) {
+                ("КонецФайла", None) => break,
+                ("СекцияРасчСчет", None) => {
+                    current = Some(ClientBank1CSection {
+                        kind: "РасчСчет".to_string(),
+                        fields: Vec::new(),
+                    })
+                }
+                ("СекцияДокумент", None) => {
+                    current = Some(ClientBank1CSection {
+                        kind: value.trim().to_string(),
+                        fields: Vec::new(),
+                    })
+                }
+                ("КонецРасчСчет", Some(section)) if section.kind == "РасчСчет" => accounts.extend(current.take()),
+                ("КонецДокумента", Some(section)) if section.kind != "РасчСчет" => {
+                    documents.extend(current.take())
+                }
+                (_, Some(section)) if line.contains('=') => section.fields.push((key.to_string(), value.to_string())),
+                (_, None) if line.contains('=') => header.fields.push((key.to_string(), value.to_string())),
+                _ => return Err(invalid(&format!("Неожиданная строка: {}", line))),
+            }
+        }
+        if let Some(section) = current {
+            return Err(invalid(&format!("Секция {} не закрыта", section.kind)));
+        }
+        Ok(ClientBank1CParser {
+            header,
+            accounts,
+            documents,
+        })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const CLIENT_BANK_1C: &str = "1CClientBankExchange\r\nВерсияФормата=1.03\r\nКодировка=Windows\r\n\
+        РасчСчет=40702810900000000001\r\nСекцияРасчСчет\r\nРасчСчет=40702810900000000001\r\nНачальныйОстаток=100.00\r\n\
+        КонецРасчСчет\r\nСекцияДокумент=Платежное поручение\r\nНомер=15\r\nСумма=200.00\r\nПолучатель=ООО \"Лютик\"\r\n\
+        ПолучательКПП=\r\nКонецДокумента\r\nКонецФайла\r\n";
+
+    #[test]
+    fn test_client_bank_1c_parse() {
+        let (bytes, _, _) = encoding_rs::WINDOWS_1251.encode(CLIENT_BANK_1C);
+        let parser = ClientBank1CParser::parse(&bytes[..]).unwrap();
+        assert_eq!(parser.header.get("ВерсияФормата"), Some("1.03"));
+        assert_eq!(parser.accounts.len(), 1);
+        assert_eq!(parser.accounts[0].get("НачальныйОстаток"), Some("100.00"));
+        assert_eq!(parser.documents[0].kind, "Платежное поручение");
+        assert_eq!(parser.documents[0].get("Получатель"), Some("ООО \"Лютик\""));
+        assert_eq!(parser.documents[0].get("ПолучательКПП"), None);
+
+        let dos = CLIENT_BANK_1C.replace("Кодировка=Windows", "Кодировка=DOS");
+        let (bytes, _, _) = encoding_rs::IBM866.encode(&dos);
+        let parser = ClientBank1CParser::parse(&bytes[..]).unwrap();
+        assert_eq!(parser.documents[0].get("Номер"), Some("15"));
+        assert_eq!(parser.documents[0].get("Получатель"), Some("ООО \"Лютик\""));
+    }
+
+    #[test]
+    fn test_client_bank_1c_parse_invalid() {
+        assert!(ClientBank1CParser::parse("Не 1С".as_bytes()).is_err());
+        let unclosed = CLIENT_BANK_1C.replace("КонецДокумента", "");
+        assert!(matches!(
+            ClientBank1CParser::parse(unclosed.as_bytes()),
+            Err(ParseError::InvalidFormat(_))
+        ));
+    }
+}
@@ -0,0 +1,200 @@
+//! CODA (версия 2): записи фиксированной длины 128 символов, выписка от `0` до `9`
+//! и проверка числа записей `1`, `2`, `3`, `8` в трейлере.
+
+use crate::ParseError;
+
+/// Длина записи CODA.
+pub(crate) const RECORD_LENGTH: usize = 128;
+
+fn invalid(msg: &str) -> ParseError {
+    ParseError::InvalidFormat(format!("CODA invalid. {}", msg))
+}
+
+/// Fixed-width CODA record, padded to 128 characters.
+#[derive(Debug, Clone, PartialEq, Default)]
+pub struct CodaRecord {
+    text: Vec<char>,
+}
+
+impl CodaRecord {
+    /// Record type with article: `0`, `1`, `21`, `22`, `23`, `31`...`33`, `4`, `8`, `9`.
+    pub fn kind(&self) -> String {
+        match self.text.first() {
+            Some(c @ ('2' | '3')) => format!("{}{}", c, self.text.get(1).copied().unwrap_or(' ')),
+            Some(c) => c.to_string(),
+            None => String::new(),
+        }
+    }
+
+    /// Raw field at zero-based `start` of `len` characters, spaces included.
+    pub fn raw(&self, start: usize, len: usize) -> String {
+        self.text.iter().skip(start).take(len).collect()
+    }
+
+    /// Field at zero-based `start` of `len` characters, trimmed; blank fields are `None`.
+    pub fn field(&self, start: usize, len: usize) -> Option<String> {
+        Some(self.raw(start, len).trim().to_string()).filter(|f| !f.is_empty())
+    }
+}
+
+/// Movement: `2.1` record with its `2.2`/`2.3` parts and `3.x` information records.
+#[derive(Debug, Clone, PartialEq, Default)]
+pub struct CodaMovement {
+    /// `2.1` record: references, amount, dates, transaction code, communication.
+    pub record: CodaRecord,
+    /// `2.2` and `2.3` records.
+    pub details: Vec<CodaRecord>,
+    /// `3.1`-`3.3` information records with the same sequence number.
+    pub information: Vec<CodaRecord>,
+}
+
+impl CodaMovement {
+    /// Detail record by kind (`22`, `23`).
+    pub fn detail(&self, kind: &str) -> Option<&CodaRecord> {
+        self.details.iter().find(|r| r.kind() == kind)
+    }
+}
+
+/// One CODA statement: header `0` up to trailer `9`.
+#[derive(Debug, Clone, PartialEq, Default)]
+pub struct CodaStatement {
+    /// `0` header: creation date, bank BIC, file reference.
+    pub header: CodaRecord,
+    /// `1` old balance: account, currency, balance and its date.
+    pub old_balance: CodaRecord,
+    /// Movements in file order.
+    pub movements: Vec<CodaMovement>,
+    /// `4` free communication records.
+    pub free_communication: Vec<CodaRecord>,
+    /// `8` new balance.
+    pub new_balance: CodaRecord,
+    /// `9` trailer: record count and movement totals.
+    pub trailer: CodaRecord,
+}
+
+/// Разбивает текст на записи; короткие строки (обрезанные пробелы) дополняются до 128.
+fn records(text: &str) -> Result<Vec<CodaRecord>, ParseError> {
+    let mut records = Vec::new();
+    for (number, line) in text.lines().enumerate() {
+        let line = line.trim_end_matches(['\r', '\u{1a}']);
+        if line.trim().is_empty() {
+            continue;
+        }
+        let mut chars: Vec<char> = line.chars().collect();
+        if chars.len() > RECORD_LENGTH {
+            return Err(invalid(&format!("строка {} длиннее {} символов", number + 1, RECORD_LENGTH)));
+        }
+        chars.resize(RECORD_LENGTH, ' ');
+        records.push(CodaRecord { text: chars });
+    }
+    Ok(records)
+}
+
+/// Выписки файла; записи вне своего места — ошибка.
+pub(crate) fn parse_file(text: &str) -> Result<Vec<CodaStatement>, ParseError> {
+    let mut statements = Vec::new();
+    let mut current: Option<CodaStatement> = None;
+    // Записи `1`, `2`, `3`, `8` текущей выписки для сверки с `9`.
+    let mut counted = 0usize;
+    for record in records(text)? {
+        let kind = record.kind();
+        match (kind.as_str(), &mut current) {
+            ("0", None) => {
+                current = Some(CodaStatement {
+                    header: record,
+                    ..Default::default()
+                });
+                counted = 0;
+            }
+            ("1", Some(statement)) if statement.old_balance.kind().is_empty() => {
+                statement.old_balance = record;
+                counted += 1;
+            }
+            ("21", Some(statement)) if !statement.old_balance.kind().is_empty() && statement.new_balance.kind().is_empty() => {
+                statement.movements.push(CodaMovement {
+                    record,
+                    ..Default::default()
+                });
+                counted += 1;
+            }
+            ("22" | "23" | "31" | "32" | "33", Some(statement)) if statement.new_balance.kind().is_empty() => {
+                let movement = statement
+                    .movements
+                    .last_mut()
+                    .ok_or_else(|| invalid(&format!("запись {} без движения 2.1", kind)))?;
+                if kind.starts_with('2') {
+                    movement.details.push(record);
+                } else {
+                    movement.information.push(record);
+                }
+                counted += 1;
+            }
+            ("4", Some(statement)) if !statement.old_balance.kind().is_empty() => statement.free_communication.push(record),
+            ("8", Some(statement)) if !statement.old_balance.kind().is_empty() && statement.new_balance.kind().is_empty() => {
+                statement.new_balance = record;
+                counted += 1;
+            }
+            ("9", Some(statement)) if !statement.new_balance.kind().is_empty() => {
+                let expected = record.field(16, 6).and_then(|n| n.parse::<usize>().ok());
+                if expected != Some(counted) {
+                    return Err(invalid(&format!(
+                        "в записи 9 указано {:?} записей, в выписке {}",
+                        expected, counted
+                    )));
+                }
+                statement.trailer = record;
+                statements.push(current.take().expect("выписка открыта"));
+            }
+            _ => return Err(invalid(&format!("запись {} не на своем месте", kind))),
+        }
+    }
+    if current.is_some() {
+        return Err(invalid("нет записи 9"));
+    }
+    if statements.is_empty() {
+        return Err(invalid("нет записи 0"));
+    }
+    Ok(statements)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn line(text: &str) -> String {
+        format!("{:<128}", text)
+    }
+
+    #[test]
+    fn statement_structure_and_trailer_count() {
+        let file = [
+            line("0000001012372505        0123456789ACME NV                   GEBABEBB   00000000000 00000"),
+            line(&format!("12001{:<34}EUR0{:015}311222ACME NV", "BE68539007547034", 1_000_000)),
+            line(&format!(
+                "2100010000{:<21}1{:015}010123001010000{:<53}02012300101 0",
+                "REF1", 50_000, "Invoice 42"
+            )),
+            line(&format!("2200010000{:<53}CUSTREF", "")),
+            line(&format!("3100010001{:<21}001010000Extra information", "REF1")),
+            line("4 00010000                      Free text"),
+            line(&format!("8001{:<34}EUR0{:015}020123", "BE68539007547034", 950_000)),
+            line(&format!("9{:15}{:06}{:015}{:015}", "", 5, 50_000, 0)),
+        ]
+        .join("\n");
+        let statements = parse_file(&file).unwrap();
+        assert_eq!(statements.len(), 1);
+        let movement = &statements[0].movements[0];
+        assert_eq!(movement.record.field(10, 21).as_deref(), Some("REF1"));
+        assert_eq!(movement.detail("22").and_then(|r| r.field(63, 35)).as_deref(), Some("CUSTREF"));
+        assert_eq!(movement.information.len(), 1);
+        assert_eq!(statements[0].free_communication.len(), 1);
+
+        assert!(
+            parse_file(&file.replace("000005000", "000004000")).is_err(),
+            "неверное число записей"
+        );
+        let without_trailer: Vec<&str> = file.lines().take(7).collect();
+        assert!(parse_file(&without_trailer.join("\n")).is_err());
+        assert!(parse_file(&file.replacen("12001", "22001", 1)).is_err());
+    }
+}
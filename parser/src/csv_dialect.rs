@@ -0,0 +1,198 @@
+//! Диалект CSV: разделитель, кавычки, десятичная запятая, кодировка, заголовок.
+
+use encoding_rs::{Encoding, UTF_8};
+
+use crate::{ParseError, text};
+
+/// Когда заключать поле в кавычки при записи.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum CsvQuoting {
+    /// Только поля с разделителем, кавычкой или переводом строки.
+    #[default]
+    Necessary,
+    /// Все поля.
+    Always,
+    /// Никогда; поле с разделителем, кавычкой или переводом строки — ошибка записи.
+    Never,
+}
+
+/// Настройки чтения и записи CSV.
+///
+/// Значения по умолчанию: разделитель и кодировка определяются по содержимому,
+/// при записи — `,` и UTF-8 без BOM.
+#[derive(Debug, Clone, PartialEq)]
+pub struct CsvDialect {
+    /// Разделитель полей. `None` — определить по содержимому (при записи `,`).
+    pub delimiter: Option<u8>,
+    /// Символ кавычки.
+    pub quote: u8,
+    /// Правило кавычек при записи.
+    pub quoting: CsvQuoting,
+    /// Суммы с десятичной запятой: `1.234,56`.
+    pub decimal_comma: bool,
+    /// Кодировка. `None` — по BOM, иначе UTF-8, если текст корректен, иначе Windows-1251
+    /// (при записи UTF-8).
+    pub encoding: Option<&'static Encoding>,
+    /// Писать BOM в начале UTF-8 файла.
+    pub bom: bool,
+    /// Первая строка — заголовок.
+    pub has_header: bool,
+}
+
+impl Default for CsvDialect {
+    fn default() -> Self {
+        Self {
+            delimiter: None,
+            quote: b'"',
+            quoting: CsvQuoting::Necessary,
+            decimal_comma: false,
+            encoding: None,
+            bom: false,
+            has_header: true,
+        }
+    }
+}
+
+/// Кандидаты для определения разделителя.
+const DELIMITERS: [u8; 4] = [b',', b';', b'\t', b'|'];
+
+/// Сколько записей смотреть при определении разделителя.
+const SNIFF_RECORDS: usize = 20;
+
+impl CsvDialect {
+    /// Кодировка по метке: `utf-8`, `windows-1251`, `cp1251`, `koi8-r`...
+    pub fn encoding_for_label(label: &str) -> Option<&'static Encoding> {
+        let label = label.trim();
+        // encoding_rs знает только WHATWG-метки, а cp1251 — привычное имя из Windows.
+        let label = match label.to_ascii_lowercase().as_str() {
+            "cp1251" => "windows-1251",
+            _ => label,
+        };
+        Encoding::for_label(label.as_bytes())
+    }
+
+    /// Разделитель для записи.
+    pub fn write_delimiter(&self) -> u8 {
+        self.delimiter.unwrap_or(b',')
+    }
+
+    /// Текст файла и использованная кодировка. BOM важнее заданной кодировки.
+    pub fn decode(&self, bytes: &[u8]) -> Result<(String, &'static Encoding), ParseError> {
+        text::decode(bytes, self.encoding)
+    }
+
+    /// Текст в байты для записи в выбранной кодировке, с BOM для UTF-8 при `bom`.
+    ///
+    /// Символ, которого нет в кодировке, — ошибка с его кодом и номером строки
+    /// (encoding_rs заменил бы его на `&#NNNN;`).
+    pub fn encode(&self, text: &str) -> Result<Vec<u8>, ParseError> {
+        let encoding = self.encoding.unwrap_or(UTF_8).output_encoding();
+        let (encoded, _, had_errors) = encoding.encode(text);
+        if had_errors {
+            let mut buffer = [0; 4];
+            let unmappable = text
+                .lines()
+                .zip(1..)
+                .flat_map(|(line, number)| line.chars().map(move |c| (number, c)))
+                .find(|(_, c)| encoding.encode(c.encode_utf8(&mut buffer)).2);
+            let (line, c) = unmappable.unwrap_or_default();
+            return Err(ParseError::InvalidFormat(format!(
+                "Символа '{}' (U+{:04X}) в строке {} нет в кодировке {}",
+                c,
+                u32::from(c),
+                line,
+                encoding.name()
+            )));
+        }
+        let mut bytes = Vec::with_capacity(encoded.len() + 3);
+        if self.bom && encoding == UTF_8 {
+            bytes.extend_from_slice(b"\xEF\xBB\xBF");
+        }
+        bytes.extend_from_slice(&encoded);
+        Ok(bytes)
+    }
+
+    /// Разделитель по первым записям: тот, что встречается одинаковое ненулевое
+    /// число раз в каждой записи (без учета кавычек), при равенстве — чаще.
+    pub fn sniff_delimiter(text: &str, quote: u8) -> u8 {
+        let mut counts: Vec<[usize; DELIMITERS.len()]> = vec![[0; DELIMITERS.len()]];
+        let mut quoted = false;
+        for &byte in text.as_bytes() {
+            if byte == quote {
+                quoted = !quoted;
+            } else if quoted {
+                continue;
+            } else if byte == b'\n' {
+                if counts.len() == SNIFF_RECORDS {
+                    break;
+                }
+                counts.push([0; DELIMITERS.len()]);
+            } else if let Some(index) = DELIMITERS.iter().position(|d| *d == byte) {
+                counts.last_mut().expect("всегда есть запись")[index] += 1;
+            }
+        }
+        // Пустые строки (в т.ч. последняя после завершающего \n) не учитываем.
+        counts.retain(|record| record.iter().any(|c| *c > 0));
+
+        let score = |index: usize| {
+            let first = counts.first().map_or(0, |record| record[index]);
+            let consistent = first > 0 && counts.iter().all(|record| record[index] == first);
+            let total: usize = counts.iter().map(|record| record[index]).sum();
+            (consistent, total)
+        };
+        (0..DELIMITERS.len())
+            .filter(|index| score(*index).1 > 0)
+            .max_by_key(|index| score(*index))
+            .map_or(b',', |index| DELIMITERS[index])
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use encoding_rs::WINDOWS_1251;
+
+    use super::*;
+
+    #[test]
+    fn sniff_delimiter_ignores_quoted_text() {
+        assert_eq!(CsvDialect::sniff_delimiter("a;b;c\n1;\"x, y, z\";3\n", b'"'), b';');
+        assert_eq!(CsvDialect::sniff_delimiter("a,b\n1,\"line\nbreak; here\"\n", b'"'), b',');
+        assert_eq!(CsvDialect::sniff_delimiter("a\tb\tc\n1\t2,5\t3\n", b'"'), b'\t');
+        assert_eq!(CsvDialect::sniff_delimiter("single column\n", b'"'), b',');
+    }
+
+    #[test]
+    fn decode_by_bom_and_fallback() {
+        let dialect = CsvDialect::default();
+        let (text, encoding) = dialect.decode(b"\xEF\xBB\xBFa;b").unwrap();
+        assert_eq!((text.as_str(), encoding), ("a;b", UTF_8));
+
+        let (cp1251, _, _) = WINDOWS_1251.encode("Дата;Сумма");
+        let (text, encoding) = dialect.decode(&cp1251).unwrap();
+        assert_eq!((text.as_str(), encoding), ("Дата;Сумма", WINDOWS_1251));
+
+        let strict = CsvDialect {
+            encoding: Some(UTF_8),
+            ..Default::default()
+        };
+        assert!(strict.decode(&cp1251).is_err());
+        assert_eq!(CsvDialect::encoding_for_label("CP1251"), Some(WINDOWS_1251));
+    }
+
+    #[test]
+    fn encode_with_bom_and_codepage() {
+        let utf8 = CsvDialect {
+            bom: true,
+            ..Default::default()
+        };
+        assert_eq!(utf8.encode("a").unwrap(), b"\xEF\xBB\xBFa");
+        let cp1251 = CsvDialect {
+            encoding: Some(WINDOWS_1251),
+            bom: true,
+            ..Default::default()
+        };
+        assert_eq!(cp1251.encode("Я").unwrap(), vec![0xDF]);
+        let error = cp1251.encode("Я\nMüller").unwrap_err().to_string();
+        assert!(error.contains("'ü' (U+00FC) в строке 2"), "{}", error);
+    }
+}
@@ -2,14 +2,40 @@
 //!
 //! Поддерживаемые форматы:
 //! - CSV
-//! - MT940 (SWIFT)
-//! - CAMT.053 (ISO 20022 XML)
+//! - MT940, MT942, MT950, MT900/MT910 (SWIFT)
+//! - CAMT.053, CAMT.052 и CAMT.054 (ISO 20022 XML)
+//! - 1CClientBankExchange (1С:Предприятие)
+//! - OFX 1.x (SGML) и 2.x (XML)
+//! - QIF (Quicken Interchange Format)
+//! - BAI2 (Cash Management Balance Reporting)
+//! - CODA (Belgian Coded Statement of Account)
+//! - JSON и JSON Lines (NDJSON)
+//! - XLSX (Excel)
 
 #![warn(missing_docs)]
 use std::io::Read;
+mod bai2;
+mod client_bank;
+mod coda;
+mod csv_dialect;
 mod error;
+mod ofx;
+mod qif;
+mod swift;
+mod text;
+mod xlsx;
+mod xml;
+pub use bai2::{Bai2Account, Bai2Group, Bai2Record};
+pub use client_bank::{ClientBank1CParser, ClientBank1CSection};
+pub use coda::{CodaMovement, CodaRecord, CodaStatement};
 use csv::ReaderBuilder;
+pub use csv_dialect::{CsvDialect, CsvQuoting};
+pub use encoding_rs::Encoding;
 pub use error::ParseError;
+pub use qif::{QifParser, QifRecord, QifSection};
+pub use swift::SwiftHeader;
+pub use xlsx::XlsxCell;
+pub use xml::XmlElement;
 
 /// Базовый интерфейс для *Parser.
 pub trait Parser {
@@ -31,6 +57,8 @@ pub struct CsvRow {
 pub struct CsvParser {
     /// All parsed rows from the input
     pub rows: Vec<CsvRow>,
+    /// Dialect the input was read with; detected delimiter and encoding are filled in.
+    pub dialect: CsvDialect,
 }
 
 /// A single record from an MT940 file.
@@ -39,14 +67,57 @@ pub struct Mt940Record {
     /// The tag of the MT940 field (e.g., "20", "25").
     pub tag: String,
     /// The value associated with the tag.
+    ///
+    /// Continuation lines (e.g. multi-line `:86:` narrative or `:61:`
+    /// supplementary details) are kept, joined with `\n`.
     pub value: String,
 }
 
+/// A single MT940 message: SWIFT envelope and fields of text block 4.
+#[derive(Debug)]
+pub struct Mt940Message {
+    /// Envelope blocks `{1:}`, `{2:}`, `{3:}`, `{5:}`; empty for plain files.
+    pub header: SwiftHeader,
+    /// Parsed MT940 records.
+    pub records: Vec<Mt940Record>,
+}
+
 /// A parser for MT940 format
 #[derive(Debug)]
 pub struct Mt940Parser {
-    /// Parsed MT940 records.
-    pub data: Vec<Mt940Record>,
+    /// Messages in file order.
+    pub messages: Vec<Mt940Message>,
+}
+
+/// A parser for MT942 interim transaction reports.
+///
+/// Fields are split the same way as in [`Mt940Parser`]; every message must carry
+/// the MT942 mandatory tags `:20:`, `:25:`, `:28C:`, `:34F:` and `:13D:`.
+#[derive(Debug)]
+pub struct Mt942Parser {
+    /// Messages in file order.
+    pub messages: Vec<Mt940Message>,
+}
+
+/// A parser for MT950 correspondent statements: MT940 fields without `:86:`.
+#[derive(Debug)]
+pub struct Mt950Parser {
+    /// Messages in file order.
+    pub messages: Vec<Mt940Message>,
+}
+
+/// A parser for MT900 confirmations of debit: one entry per message.
+#[derive(Debug)]
+pub struct Mt900Parser {
+    /// Messages in file order.
+    pub messages: Vec<Mt940Message>,
+}
+
+/// A parser for MT910 confirmations of credit: one entry per message.
+#[derive(Debug)]
+pub struct Mt910Parser {
+    /// Messages in file order.
+    pub messages: Vec<Mt940Message>,
 }
 
 /// A parser for CAMT.053 XML format
@@ -54,11 +125,46 @@ pub struct Mt940Parser {
 pub struct Camt053Parser {
     /// Raw XML content of the CAMT.053 document.
     pub data: String,
+    /// Parsed `Document` root element.
+    pub document: XmlElement,
 }
 
-impl Parser for CsvParser {
-    fn parse<R: Read>(input: R) -> Result<Self, ParseError> {
-        let reader = ReaderBuilder::new().has_headers(false).from_reader(input);
+/// A parser for CAMT.052 XML format (intraday account report).
+#[derive(Debug)]
+pub struct Camt052Parser {
+    /// Raw XML content of the CAMT.052 document.
+    pub data: String,
+    /// Parsed `Document` root element.
+    pub document: XmlElement,
+}
+
+/// A parser for CAMT.054 XML format (debit/credit notification).
+#[derive(Debug)]
+pub struct Camt054Parser {
+    /// Raw XML content of the CAMT.054 document.
+    pub data: String,
+    /// Parsed `Document` root element.
+    pub document: XmlElement,
+}
+
+impl CsvParser {
+    /// Parses CSV with the given dialect; a missing delimiter or encoding is detected from the input.
+    ///
+    /// All rows are returned, including the header: its meaning is up to the caller.
+    pub fn parse_with<R: Read>(mut input: R, dialect: &CsvDialect) -> Result<Self, ParseError> {
+        let mut bytes = Vec::new();
+        input.read_to_end(&mut bytes).map_err(ParseError::Io)?;
+        let (text, encoding) = dialect.decode(&bytes)?;
+        let delimiter = dialect
+            .delimiter
+            .unwrap_or_else(|| CsvDialect::sniff_delimiter(&text, dialect.quote));
+
+        let reader = ReaderBuilder::new()
+            .has_headers(false)
+            .flexible(true)
+            .delimiter(delimiter)
+            .quote(dialect.quote)
+            .from_reader(text.as_bytes());
 
         let mut rows = Vec::new();
         for result in reader.into_records() {
@@ -67,54 +173,365 @@ impl Parser for CsvParser {
             rows.push(CsvRow { row });
         }
 
-        Ok(CsvParser { rows })
+        Ok(CsvParser {
+            rows,
+            dialect: CsvDialect {
+                delimiter: Some(delimiter),
+                encoding: Some(encoding),
+                ..dialect.clone()
+            },
+        })
     }
 }
 
-impl Parser for Mt940Parser {
-    fn parse<R: Read>(mut input: R) -> Result<Self, ParseError>
-    where
-        Self: Sized,
-    {
-        let mut buffer = String::new();
-        input.read_to_string(&mut buffer).map_err(ParseError::Io)?;
-        if buffer.is_empty() {
-            return Err(ParseError::InvalidFormat("Invalid Mt90 line. Пустая структура!".to_string()));
+/// A parser for OFX: 1.x SGML and 2.x XML.
+#[derive(Debug)]
+pub struct OfxParser {
+    /// Header fields: `KEY:VALUE` lines of 1.x or `<?OFX ...?>` attributes of 2.x.
+    pub header: Vec<(String, String)>,
+    /// `OFX` root element; 1.x leaf elements are closed implicitly.
+    pub document: XmlElement,
+}
+
+impl OfxParser {
+    /// Major version from the header: `1` (SGML) or `2` (XML).
+    pub fn version(&self) -> u8 {
+        let version = self.header.iter().find(|(k, _)| k == "VERSION").map(|(_, v)| v.as_str());
+        match version.and_then(|v| v.chars().next()) {
+            Some('1') => 1,
+            _ => 2,
+        }
+    }
+}
+
+impl Parser for OfxParser {
+    fn parse<R: Read>(mut input: R) -> Result<Self, ParseError> {
+        let mut bytes = Vec::new();
+        input.read_to_end(&mut bytes).map_err(ParseError::Io)?;
+        let start = bytes.iter().position(|b| *b == b'<').unwrap_or(bytes.len());
+        let prefix = String::from_utf8_lossy(&bytes[..start]);
+
+        let (header, document) = if prefix.contains("OFXHEADER:") {
+            // OFX 1.x: ASCII-заголовок, кодировка тела — из CHARSET/ENCODING.
+            let (header, _) = ofx::split_header_v1(&prefix);
+            let (text, _) = text::decode(&bytes[start..], ofx::header_encoding(&header))?;
+            (header, ofx::parse_sgml(&text)?)
+        } else {
+            let (text, _) = text::decode(&bytes, None)?;
+            if !text.contains("<OFX") {
+                return Err(ParseError::InvalidFormat("OFX invalid. Нет элемента <OFX>".to_string()));
+            }
+            (ofx::header_v2(&text), xml::parse_document(&text)?)
+        };
+        if document.name != "OFX" {
+            return Err(ParseError::InvalidFormat("OFX invalid. Ожидался корневой элемент OFX".to_string()));
         }
-        let mut data = Vec::new();
-        for line in buffer.lines() {
-            let line = line.trim();
-            if line.is_empty() || !line.starts_with(':') {
+        Ok(OfxParser { header, document })
+    }
+}
+
+/// A parser for Belgian CODA v2 files; the trailer record count is verified.
+///
+/// The input is UTF-8 or, failing that, Windows-1252.
+#[derive(Debug)]
+pub struct CodaParser {
+    /// Statements (`0` to `9` blocks) in file order.
+    pub statements: Vec<CodaStatement>,
+}
+
+impl Parser for CodaParser {
+    fn parse<R: Read>(mut input: R) -> Result<Self, ParseError> {
+        let mut bytes = Vec::new();
+        input.read_to_end(&mut bytes).map_err(ParseError::Io)?;
+        let encoding = std::str::from_utf8(&bytes).is_err().then_some(encoding_rs::WINDOWS_1252);
+        let (text, _) = text::decode(&bytes, encoding)?;
+        Ok(CodaParser {
+            statements: coda::parse_file(&text)?,
+        })
+    }
+}
+
+/// A parser for a JSON document; only the syntax is checked here.
+#[derive(Debug)]
+pub struct JsonParser {
+    /// The whole document.
+    pub document: serde_json::Value,
+}
+
+impl Parser for JsonParser {
+    fn parse<R: Read>(mut input: R) -> Result<Self, ParseError> {
+        let mut bytes = Vec::new();
+        input.read_to_end(&mut bytes).map_err(ParseError::Io)?;
+        let (text, _) = text::decode(&bytes, None)?;
+        let document = serde_json::from_str(&text).map_err(|e| ParseError::InvalidFormat(format!("JSON invalid. {}", e)))?;
+        Ok(JsonParser { document })
+    }
+}
+
+/// A parser for JSON Lines (NDJSON): one JSON value per line, blank lines skipped.
+#[derive(Debug)]
+pub struct JsonLinesParser {
+    /// Values in file order.
+    pub lines: Vec<serde_json::Value>,
+}
+
+impl Parser for JsonLinesParser {
+    fn parse<R: Read>(mut input: R) -> Result<Self, ParseError> {
+        let mut bytes = Vec::new();
+        input.read_to_end(&mut bytes).map_err(ParseError::Io)?;
+        let (text, _) = text::decode(&bytes, None)?;
+        let lines = text
+            .lines()
+            .enumerate()
+            .filter(|(_, line)| !line.trim().is_empty())
+            .map(|(number, line)| {
+                serde_json::from_str(line)
+                    .map_err(|e| ParseError::InvalidFormat(format!("JSON Lines invalid. Строка {}: {}", number + 1, e)))
+            })
+            .collect::<Result<_, _>>()?;
+        Ok(JsonLinesParser { lines })
+    }
+}
+
+/// A parser for one worksheet of an Excel `.xlsx` workbook.
+#[derive(Debug)]
+pub struct XlsxParser {
+    /// Name of the sheet that was read.
+    pub sheet: String,
+    /// Rows from the top of the sheet; cell 0 is column `A`.
+    pub rows: Vec<Vec<XlsxCell>>,
+}
+
+impl XlsxParser {
+    /// Reads the sheet named `sheet` (case-insensitive), or the first sheet when `None`.
+    pub fn parse_sheet<R: Read>(mut input: R, sheet: Option<&str>) -> Result<Self, ParseError> {
+        let mut bytes = Vec::new();
+        input.read_to_end(&mut bytes).map_err(ParseError::Io)?;
+        let (sheet, rows) = xlsx::read_sheet(bytes, sheet)?;
+        Ok(XlsxParser { sheet, rows })
+    }
+}
+
+impl Parser for XlsxParser {
+    fn parse<R: Read>(input: R) -> Result<Self, ParseError> {
+        Self::parse_sheet(input, None)
+    }
+}
+
+/// A parser for BAI2 cash management files; trailer totals and record counts are verified.
+#[derive(Debug)]
+pub struct Bai2Parser {
+    /// `01` file header: sender, receiver, creation date/time, file id.
+    pub header: Bai2Record,
+    /// `02` groups in file order.
+    pub groups: Vec<Bai2Group>,
+}
+
+impl Bai2Parser {
+    /// Amount of a BAI2 field in minor units: `+150000`, `-25`; empty is zero.
+    pub fn parse_amount(value: &str) -> Result<i64, ParseError> {
+        bai2::parse_amount(value)
+    }
+
+    /// Number of fields following a funds type code (`V` — date and time, `S` — three amounts...).
+    /// `None` if the `D` distribution count overflows.
+    pub fn funds_type_fields(funds_type: &str, following: &[&str]) -> Option<usize> {
+        bai2::funds_type_fields(funds_type, following)
+    }
+}
+
+impl Parser for Bai2Parser {
+    fn parse<R: Read>(mut input: R) -> Result<Self, ParseError> {
+        let mut bytes = Vec::new();
+        input.read_to_end(&mut bytes).map_err(ParseError::Io)?;
+        let (text, _) = text::decode(&bytes, None)?;
+        let (header, groups) = bai2::parse_file(&text)?;
+        Ok(Bai2Parser { header, groups })
+    }
+}
+
+impl Parser for CsvParser {
+    fn parse<R: Read>(input: R) -> Result<Self, ParseError> {
+        CsvParser::parse_with(input, &CsvDialect::default())
+    }
+}
+
+/// Сообщения SWIFT с полями блока 4; общая часть MT940 и MT942.
+fn parse_swift_messages<R: Read>(mut input: R) -> Result<Vec<Mt940Message>, ParseError> {
+    let mut buffer = String::new();
+    input.read_to_string(&mut buffer).map_err(ParseError::Io)?;
+    if buffer.is_empty() {
+        return Err(ParseError::InvalidFormat("Invalid Mt90 line. Пустая структура!".to_string()));
+    }
+    let mut messages = Vec::new();
+    for raw in swift::split_messages(&buffer)? {
+        let mut records: Vec<Mt940Record> = Vec::new();
+        for raw_line in raw.text.lines() {
+            let line = raw_line.trim();
+            if !line.starts_with(':') {
+                // Строка-продолжение предыдущего поля как есть: пробелы в начале и пустые
+                // строки — часть текста `:86:`. Строки до первого поля игнорируются.
+                if let Some(last) = records.last_mut() {
+                    last.value.push('\n');
+                    last.value.push_str(raw_line);
+                }
                 continue;
             }
             if let Some(colon_position) = line[1..].find(':') {
                 let tag = line[1..=colon_position].to_string();
                 let value = line[colon_position + 2..].to_string();
-                data.push(Mt940Record { tag, value });
+                records.push(Mt940Record { tag, value });
             } else {
                 return Err(ParseError::InvalidFormat(
                     "Invalid Mt90 line. Нет типичных двоеточий `:` для структуры".to_string(),
                 ));
             }
         }
-        Ok(Mt940Parser { data })
+        // Пустые строки между полями не относятся к значению.
+        for record in &mut records {
+            record.value.truncate(record.value.trim_end().len());
+        }
+        messages.push(Mt940Message {
+            header: raw.header,
+            records,
+        });
+    }
+    if messages.is_empty() {
+        return Err(ParseError::InvalidFormat("Invalid Mt90 line. Пустая структура!".to_string()));
     }
+    Ok(messages)
 }
 
-impl Parser for Camt053Parser {
-    fn parse<R: Read>(mut input: R) -> Result<Self, ParseError>
+impl Parser for Mt940Parser {
+    fn parse<R: Read>(input: R) -> Result<Self, ParseError>
     where
         Self: Sized,
     {
-        let mut buffer = String::new();
-        input.read_to_string(&mut buffer).map_err(ParseError::Io)?;
+        Ok(Mt940Parser {
+            messages: parse_swift_messages(input)?,
+        })
+    }
+}
 
-        if !buffer.trim_start().starts_with("<") {
-            return Err(ParseError::InvalidFormat(
-                "CAMT.053 invalid. В строке нет открывающей скобки <".to_string(),
-            ));
+/// Проверяет тип сообщения в конверте и обязательные поля блока 4.
+///
+/// Каждый элемент `mandatory` — варианты одного поля (`60F`/`60M`), достаточно любого.
+fn check_swift_messages(messages: &[Mt940Message], message_type: &str, mandatory: &[&[&str]]) -> Result<(), ParseError> {
+    for message in messages {
+        if let Some(kind) = message.header.message_type().filter(|kind| *kind != message_type) {
+            return Err(ParseError::InvalidFormat(format!(
+                "Invalid MT{}. Сообщение MT{}",
+                message_type, kind
+            )));
+        }
+        for variants in mandatory {
+            if !message.records.iter().any(|r| variants.contains(&r.tag.as_str())) {
+                return Err(ParseError::InvalidFormat(format!(
+                    "Invalid MT{}. Нет обязательного поля :{}:",
+                    message_type,
+                    variants.join(":/:")
+                )));
+            }
         }
-        Ok(Camt053Parser { data: buffer })
+    }
+    Ok(())
+}
+
+impl Parser for Mt942Parser {
+    fn parse<R: Read>(input: R) -> Result<Self, ParseError>
+    where
+        Self: Sized,
+    {
+        let messages = parse_swift_messages(input)?;
+        // `:34F:` может повторяться (дебетовый и кредитовый пороги).
+        check_swift_messages(&messages, "942", &[&["20"], &["25", "25P"], &["28C"], &["34F"], &["13D"]])?;
+        Ok(Mt942Parser { messages })
+    }
+}
+
+impl Parser for Mt950Parser {
+    fn parse<R: Read>(input: R) -> Result<Self, ParseError>
+    where
+        Self: Sized,
+    {
+        let messages = parse_swift_messages(input)?;
+        check_swift_messages(&messages, "950", &[&["20"], &["25"], &["28C"], &["60F", "60M"], &["62F", "62M"]])?;
+        Ok(Mt950Parser { messages })
+    }
+}
+
+impl Parser for Mt900Parser {
+    fn parse<R: Read>(input: R) -> Result<Self, ParseError>
+    where
+        Self: Sized,
+    {
+        let messages = parse_swift_messages(input)?;
+        check_swift_messages(&messages, "900", &[&["20"], &["21"], &["25", "25P"], &["32A"]])?;
+        Ok(Mt900Parser { messages })
+    }
+}
+
+impl Parser for Mt910Parser {
+    fn parse<R: Read>(input: R) -> Result<Self, ParseError>
+    where
+        Self: Sized,
+    {
+        let messages = parse_swift_messages(input)?;
+        // Правило сети C1: обязателен плательщик `:50a:` или банк плательщика `:52a:`.
+        let ordering = ["50A", "50F", "50K", "52A", "52D"];
+        check_swift_messages(&messages, "910", &[&["20"], &["21"], &["25", "25P"], &["32A"], &ordering])?;
+        Ok(Mt910Parser { messages })
+    }
+}
+
+/// Читает документ ISO 20022 и проверяет, что под `Document` лежит элемент `root`.
+fn parse_camt<R: Read>(mut input: R, message: &str, root: &str) -> Result<(String, XmlElement), ParseError> {
+    let mut buffer = String::new();
+    input.read_to_string(&mut buffer).map_err(ParseError::Io)?;
+
+    if !buffer.trim_start().starts_with("<") {
+        return Err(ParseError::InvalidFormat(format!(
+            "{} invalid. В строке нет открывающей скобки <",
+            message
+        )));
+    }
+    let document = xml::parse_document(&buffer)?;
+    if document.name != "Document" || document.child(root).is_none() {
+        return Err(ParseError::InvalidFormat(format!(
+            "{} invalid. Ожидался Document/{}",
+            message, root
+        )));
+    }
+    Ok((buffer, document))
+}
+
+impl Parser for Camt053Parser {
+    fn parse<R: Read>(input: R) -> Result<Self, ParseError>
+    where
+        Self: Sized,
+    {
+        let (data, document) = parse_camt(input, "CAMT.053", "BkToCstmrStmt")?;
+        Ok(Camt053Parser { data, document })
+    }
+}
+
+impl Parser for Camt052Parser {
+    fn parse<R: Read>(input: R) -> Result<Self, ParseError>
+    where
+        Self: Sized,
+    {
+        let (data, document) = parse_camt(input, "CAMT.052", "BkToCstmrAcctRpt")?;
+        Ok(Camt052Parser { data, document })
+    }
+}
+
+impl Parser for Camt054Parser {
+    fn parse<R: Read>(input: R) -> Result<Self, ParseError>
+    where
+        Self: Sized,
+    {
+        let (data, document) = parse_camt(input, "CAMT.054", "BkToCstmrDbtCdtNtfctn")?;
+        Ok(Camt054Parser { data, document })
     }
 }
 
@@ -126,9 +543,45 @@ mod tests {
     fn test_mt940_parse() {
         let input = ":20:REF123\n:25:ACC123\n:60F:C230101USD1000,00\n";
         let parser = Mt940Parser::parse(input.as_bytes()).unwrap();
-        assert_eq!(parser.data.len(), 3);
-        assert_eq!(parser.data[0].tag, "20");
-        assert_eq!(parser.data[0].value, "REF123");
+        assert_eq!(parser.messages.len(), 1);
+        let records = &parser.messages[0].records;
+        assert_eq!(records.len(), 3);
+        assert_eq!(records[0].tag, "20");
+        assert_eq!(records[0].value, "REF123");
+    }
+
+    #[test]
+    fn test_mt940_parse_swift_envelope() {
+        let input = "{1:F01RECVDEFFAXXX0000000000}{2:O9401200230101SENDBEBBAXXX00000000002301011200N}{4:\r\n:20:REF1\r\n:25:ACC\r\n-}{5:{CHK:1}}\n\
+                     {1:F01RECVDEFFAXXX0000000000}{2:O9401200230101SENDBEBBAXXX00000000002301011200N}{4:\r\n:20:REF2\r\n-}";
+        let parser = Mt940Parser::parse(input.as_bytes()).unwrap();
+        assert_eq!(parser.messages.len(), 2);
+        assert_eq!(parser.messages[0].records.len(), 2);
+        assert_eq!(parser.messages[1].records[0].value, "REF2");
+        assert_eq!(parser.messages[0].header.sender_bic().as_deref(), Some("SENDBEBBXXX"));
+    }
+
+    #[test]
+    fn test_mt940_parse_continuation_lines() {
+        let narrative = [
+            "?00SEPA-UEBERWEISUNG?109310?20EREF+2023010112345678901234567",
+            "?21MREF+M-2022-0042?22CRED+DE98ZZZ09999999999?23SVWZ+Rechnung",
+            "?24 2023-0001 vom 01.01.2023 Kundennummer 4711 Vertragsnummer",
+            "?25 0815 Zahlung fuer Januar 2023?30COBADEFFXXX?31DE89370400",
+            "?32Max Mustermann GmbH und Co KG Handelsgesellschaft?3344012345",
+            "?34166?60Zusatzinformation zur Zahlung Rechnung 2023-0001 Ende",
+        ];
+        let input = format!(
+            "Bank export header\n:20:REF\n:61:2301010101DR100,50NTRFNONREF//B123\nSUPPLEMENTARY\n:86:{}\n{}\n",
+            narrative[0],
+            narrative[1..].join("\n")
+        );
+        let parser = Mt940Parser::parse(input.as_bytes()).unwrap();
+        let records = &parser.messages[0].records;
+        assert_eq!(records.len(), 3);
+        assert_eq!(records[1].value, "2301010101DR100,50NTRFNONREF//B123\nSUPPLEMENTARY");
+        assert_eq!(records[2].value, narrative.join("\n"));
+        assert_eq!(records[2].value.lines().count(), 6);
     }
 
     #[test]
@@ -156,6 +609,44 @@ mod tests {
         assert_eq!(parser.rows[1].row[0], "Aleks".to_string());
     }
 
+    #[test]
+    fn test_csv_parse_with_dialect() {
+        let (bytes, _, _) = encoding_rs::WINDOWS_1251.encode("Дата;Сумма;Назначение\n01.02.2023;-1 500,50;\"Аренда; офис\"\n");
+        let parser = CsvParser::parse(&bytes[..]).unwrap();
+        assert_eq!(parser.dialect.delimiter, Some(b';'));
+        assert_eq!(parser.dialect.encoding, Some(encoding_rs::WINDOWS_1251));
+        assert_eq!(parser.rows[1].row, vec!["01.02.2023", "-1 500,50", "Аренда; офис"]);
+
+        let dialect = CsvDialect {
+            delimiter: Some(b'|'),
+            ..Default::default()
+        };
+        let parser = CsvParser::parse_with("a|b,c\n".as_bytes(), &dialect).unwrap();
+        assert_eq!(parser.rows[0].row, vec!["a", "b,c"]);
+    }
+
+    #[test]
+    fn test_ofx_parse_sgml_and_xml() {
+        let sgml = "OFXHEADER:100\r\nDATA:OFXSGML\r\nVERSION:102\r\nENCODING:USASCII\r\nCHARSET:1252\r\n\r\n\
+                    <OFX><SIGNONMSGSRSV1><SONRS><DTSERVER>20230105</SONRS></SIGNONMSGSRSV1>\r\n\
+                    <BANKMSGSRSV1><STMTTRNRS><STMTRS><CURDEF>EUR<BANKTRANLIST><STMTTRN><NAME>Caf\u{e9}\r\n\
+                    </STMTTRN></BANKTRANLIST></STMTRS></STMTTRNRS></BANKMSGSRSV1></OFX>";
+        let bytes: Vec<u8> = sgml.chars().map(|c| c as u8).collect();
+        let parser = OfxParser::parse(&bytes[..]).unwrap();
+        assert_eq!(parser.version(), 1);
+        let stmt = parser.document.find(&["BANKMSGSRSV1", "STMTTRNRS", "STMTRS"]).unwrap();
+        assert_eq!(stmt.find_text(&["CURDEF"]), Some("EUR"));
+        assert_eq!(stmt.find_text(&["BANKTRANLIST", "STMTTRN", "NAME"]), Some("Café"));
+
+        let xml = r#"<?xml version="1.0" encoding="UTF-8"?><?OFX OFXHEADER="200" VERSION="220"?><OFX><SIGNONMSGSRSV1/></OFX>"#;
+        let parser = OfxParser::parse(xml.as_bytes()).unwrap();
+        assert_eq!(parser.version(), 2);
+        assert!(parser.document.child("SIGNONMSGSRSV1").is_some());
+
+        assert!(OfxParser::parse("<Document/>".as_bytes()).is_err());
+        assert!(OfxParser::parse("OFXHEADER:100\r\n\r\n<OFX><A>1</B></OFX>".as_bytes()).is_err());
+    }
+
     #[test]
     fn test_camt053_parse() {
         let input = r#"
@@ -185,4 +676,81 @@ mod tests {
         let result = Camt053Parser::parse(input.as_bytes());
         assert!(result.is_err());
     }
+
+    #[test]
+    fn test_camt053_parse_builds_document() {
+        let input = r#"<Document><BkToCstmrStmt><Stmt><Id>S1</Id></Stmt><Stmt/></BkToCstmrStmt></Document>"#;
+        let parser = Camt053Parser::parse(input.as_bytes()).unwrap();
+        let report = parser.document.child("BkToCstmrStmt").unwrap();
+        assert_eq!(report.children("Stmt").count(), 2);
+        assert_eq!(report.find_text(&["Stmt", "Id"]), Some("S1"));
+    }
+
+    #[test]
+    fn test_camt053_parse_invalid_wrong_root_or_broken() {
+        let result = Camt053Parser::parse("<Document><Other/></Document>".as_bytes());
+        assert!(matches!(result.unwrap_err(), ParseError::InvalidFormat(_)));
+        let result = Camt053Parser::parse("<Document><BkToCstmrStmt></Document>".as_bytes());
+        assert!(matches!(result.unwrap_err(), ParseError::InvalidFormat(_)));
+    }
+
+    #[test]
+    fn test_camt052_and_camt054_roots() {
+        let report = r#"<Document><BkToCstmrAcctRpt><Rpt><Id>R1</Id></Rpt></BkToCstmrAcctRpt></Document>"#;
+        let parser = Camt052Parser::parse(report.as_bytes()).unwrap();
+        assert_eq!(parser.document.find_text(&["BkToCstmrAcctRpt", "Rpt", "Id"]), Some("R1"));
+        assert!(Camt053Parser::parse(report.as_bytes()).is_err());
+
+        let notification = r#"<Document><BkToCstmrDbtCdtNtfctn><Ntfctn/></BkToCstmrDbtCdtNtfctn></Document>"#;
+        assert!(Camt054Parser::parse(notification.as_bytes()).is_ok());
+        let result = Camt052Parser::parse(notification.as_bytes());
+        assert!(matches!(result.unwrap_err(), ParseError::InvalidFormat(msg) if msg.contains("BkToCstmrAcctRpt")));
+    }
+
+    #[test]
+    fn test_mt942_requires_its_tags() {
+        let input = ":20:INTRA1\n:25:ACC1\n:28C:1/1\n:34F:EURD100,\n:34F:EURC50,\n:13D:2301021030+0100\n\
+                     :61:230102D150,00NTRFNONREF\n:90D:1EUR150,00\n:90C:0EUR0,\n";
+        let parser = Mt942Parser::parse(input.as_bytes()).unwrap();
+        let records = &parser.messages[0].records;
+        assert_eq!(records.iter().filter(|r| r.tag == "34F").count(), 2);
+        assert_eq!(records[5].value, "2301021030+0100");
+
+        let result = Mt942Parser::parse(input.replace(":13D:2301021030+0100\n", "").as_bytes());
+        assert!(matches!(result.unwrap_err(), ParseError::InvalidFormat(msg) if msg.contains(":13D:")));
+        let mt940 = ":20:S\n:25:A\n:28C:1\n:60F:C230101EUR0,00\n:62F:C230101EUR0,00\n";
+        assert!(Mt942Parser::parse(mt940.as_bytes()).is_err());
+        let enveloped = format!(
+            "{{1:F01BANKBEBBAXXX0000000000}}{{2:O9401200230102BANKDEFFAXXX00000000002301021200N}}{{4:\n{}-}}",
+            input
+        );
+        assert!(Mt942Parser::parse(enveloped.as_bytes()).is_err());
+        assert!(Mt942Parser::parse(enveloped.replace("O940", "O942").as_bytes()).is_ok());
+    }
+
+    #[test]
+    fn test_mt950_and_confirmations_mandatory_tags() {
+        let mt950 = ":20:STMT\n:25:ACC\n:28C:1/1\n:60F:C230101EUR0,00\n:61:230101C5,00NMSCNONREF\n:62F:C230101EUR5,00\n";
+        assert!(Mt950Parser::parse(mt950.as_bytes()).is_ok());
+        let result = Mt950Parser::parse(mt950.replace(":62F:", ":64:").as_bytes());
+        assert!(matches!(result.unwrap_err(), ParseError::InvalidFormat(msg) if msg.contains(":62F:/:62M:")));
+
+        let mt900 = ":20:C11126A1378\n:21:5482ABC\n:25:9-9876543\n:32A:230102USD233530,\n";
+        assert!(Mt900Parser::parse(mt900.as_bytes()).is_ok());
+        assert!(Mt900Parser::parse(mt900.replace(":21:5482ABC\n", "").as_bytes()).is_err());
+
+        let mt910 = format!("{}:52A:BKAUATWW\n", mt900);
+        assert_eq!(Mt910Parser::parse(mt910.as_bytes()).unwrap().messages[0].records.len(), 5);
+        let result = Mt910Parser::parse(mt900.as_bytes());
+        assert!(matches!(result.unwrap_err(), ParseError::InvalidFormat(msg) if msg.contains(":50A:/:50F:/:50K:")));
+    }
+
+    #[test]
+    fn test_json_lines_parse_reports_line_number() {
+        let parser = JsonLinesParser::parse("{\"a\":1}\n\n{\"a\":2}\n".as_bytes()).unwrap();
+        assert_eq!(parser.lines.len(), 2);
+        let result = JsonLinesParser::parse("{\"a\":1}\n{\"a\":\n".as_bytes());
+        assert!(matches!(result.unwrap_err(), ParseError::InvalidFormat(msg) if msg.contains("Строка 2")));
+        assert!(JsonParser::parse("[1, 2".as_bytes()).is_err());
+    }
 }
@@ -0,0 +1,149 @@
+//! OFX: заголовок 1.x (`KEY:VALUE`) и тело SGML без закрывающих тегов у листьев.
+
+use encoding_rs::{Encoding, UTF_8};
+
+use crate::{ParseError, XmlElement};
+
+fn invalid(msg: &str) -> ParseError {
+    ParseError::InvalidFormat(format!("OFX invalid. {}", msg))
+}
+
+/// Заголовок OFX 1.x до первого `<` и смещение начала тела.
+pub(crate) fn split_header_v1(text: &str) -> (Vec<(String, String)>, usize) {
+    let body = text.find('<').unwrap_or(text.len());
+    let header = text[..body]
+        .lines()
+        .filter_map(|line| line.split_once(':'))
+        .map(|(key, value)| (key.trim().to_string(), value.trim().to_string()))
+        .collect();
+    (header, body)
+}
+
+/// Кодировка тела по заголовку 1.x: `ENCODING:UTF-8` или `CHARSET:1252`.
+/// `None` — определить по содержимому.
+pub(crate) fn header_encoding(header: &[(String, String)]) -> Option<&'static Encoding> {
+    let get = |key: &str| header.iter().find(|(k, _)| k == key).map(|(_, v)| v.as_str());
+    if get("ENCODING").is_some_and(|e| e.eq_ignore_ascii_case("UTF-8")) {
+        return Some(UTF_8);
+    }
+    match get("CHARSET")? {
+        charset if charset.bytes().all(|b| b.is_ascii_digit()) => Encoding::for_label(format!("windows-{}", charset).as_bytes()),
+        charset => Encoding::for_label(charset.as_bytes()),
+    }
+}
+
+/// Атрибуты инструкции `<?OFX OFXHEADER="200" VERSION="220" ...?>` заголовка OFX 2.x.
+pub(crate) fn header_v2(text: &str) -> Vec<(String, String)> {
+    let Some(start) = text.find("<?OFX") else {
+        return Vec::new();
+    };
+    let rest = &text[start + 5..];
+    let end = rest.find("?>").unwrap_or(rest.len());
+    rest[..end]
+        .split_whitespace()
+        .filter_map(|pair| pair.split_once('='))
+        .map(|(key, value)| (key.to_string(), value.trim_matches('"').to_string()))
+        .collect()
+}
+
+fn unescape(text: &str) -> String {
+    text.replace("&lt;", "<")
+        .replace("&gt;", ">")
+        .replace("&quot;", "\"")
+        .replace("&apos;", "'")
+        .replace("&nbsp;", " ")
+        .replace("&amp;", "&")
+}
+
+/// Закрывает верхний элемент стека, добавляя его к родителю или делая корнем.
+fn close(stack: &mut Vec<XmlElement>, root: &mut Option<XmlElement>) {
+    if let Some(element) = stack.pop() {
+        match stack.last_mut() {
+            Some(parent) => parent.children.push(element),
+            None => *root = Some(element),
+        }
+    }
+}
+
+/// Разбирает тело OFX 1.x в дерево.
+///
+/// В SGML у элементов с данными (`<TRNAMT>-10.00`) закрывающего тега обычно нет:
+/// элемент с текстом закрывается следующим тегом, а `</X>` закрывает все незакрытые
+/// элементы внутри `X`.
+pub(crate) fn parse_sgml(body: &str) -> Result<XmlElement, ParseError> {
+    let mut stack: Vec<XmlElement> = Vec::new();
+    let mut root = None;
+    let mut rest = body;
+    while let Some(open) = rest.find('<') {
+        let text = rest[..open].trim();
+        if !text.is_empty() {
+            let current = stack.last_mut().ok_or_else(|| invalid("текст вне элементов"))?;
+            current.text.push_str(&unescape(text));
+        }
+        let close_pos = rest[open..].find('>').ok_or_else(|| invalid("незакрытый тег"))? + open;
+        let tag = rest[open + 1..close_pos].trim();
+        rest = &rest[close_pos + 1..];
+
+        if let Some(name) = tag.strip_prefix('/') {
+            if !stack.iter().any(|el| el.name == name) {
+                return Err(invalid(&format!("лишний закрывающий тег </{}>", name)));
+            }
+            while let Some(top) = stack.last() {
+                let done = top.name == name;
+                close(&mut stack, &mut root);
+                if done {
+                    break;
+                }
+            }
+        } else {
+            // Лист с данными без закрывающего тега.
+            if stack.last().is_some_and(|top| !top.text.is_empty() && top.children.is_empty()) {
+                close(&mut stack, &mut root);
+            }
+            stack.push(XmlElement {
+                name: tag.to_string(),
+                ..Default::default()
+            });
+        }
+    }
+    if !rest.trim().is_empty() {
+        return Err(invalid("текст после последнего тега"));
+    }
+    // Незакрытые листья в конце файла допустимы, агрегаты — нет.
+    while stack.last().is_some_and(|top| top.children.is_empty()) && stack.len() > 1 {
+        close(&mut stack, &mut root);
+    }
+    if !stack.is_empty() {
+        return Err(invalid("документ оборван, не все теги закрыты"));
+    }
+    root.ok_or_else(|| invalid("нет корневого элемента"))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn sgml_closes_leaf_elements() {
+        let body = "<OFX><STMTTRN><TRNAMT>-10.00<NAME>A &amp; B</NAME><MEMO>x</STMTTRN><STMTTRN><FITID>2</STMTTRN></OFX>";
+        let root = parse_sgml(body).unwrap();
+        assert_eq!(root.name, "OFX");
+        assert_eq!(root.children("STMTTRN").count(), 2);
+        assert_eq!(root.find_text(&["STMTTRN", "TRNAMT"]), Some("-10.00"));
+        assert_eq!(root.find_text(&["STMTTRN", "NAME"]), Some("A & B"));
+        assert_eq!(root.find_text(&["STMTTRN", "MEMO"]), Some("x"));
+        assert!(parse_sgml("<OFX><A>1</B></OFX>").is_err());
+        assert!(parse_sgml("<OFX><STMTRS><A>1").is_err());
+    }
+
+    #[test]
+    fn header_charset() {
+        let (header, body) = split_header_v1("OFXHEADER:100\r\nDATA:OFXSGML\r\nCHARSET:1252\r\n\r\n<OFX>");
+        assert_eq!(body, 45);
+        assert_eq!(header_encoding(&header), Some(encoding_rs::WINDOWS_1252));
+        assert_eq!(
+            header_v2(r#"<?xml version="1.0"?><?OFX OFXHEADER="200" VERSION="220"?>"#)[1],
+            ("VERSION".to_string(), "220".to_string())
+        );
+    }
+}
@@ -0,0 +1,123 @@
+//! QIF (Quicken Interchange Format): секции `!Type:` с записями до `^`
+//! и необязательной записью `!Account` перед ними.
+
+use std::io::Read;
+
+use crate::{ParseError, Parser, text};
+
+/// A QIF record between `^` lines: one-letter codes with values in file order.
+#[derive(Debug, Clone, PartialEq, Default)]
+pub struct QifRecord {
+    /// `(code, value)` pairs: `('D', "01/05/2023")`, `('T', "-42.50")`, `('S', "Food")`...
+    pub fields: Vec<(char, String)>,
+}
+
+impl QifRecord {
+    /// First non-empty value of `code`.
+    pub fn get(&self, code: char) -> Option<&str> {
+        self.fields
+            .iter()
+            .filter(|(c, _)| *c == code)
+            .map(|(_, v)| v.trim())
+            .find(|v| !v.is_empty())
+    }
+}
+
+/// Records after a `!Type:` line.
+#[derive(Debug, Clone, PartialEq, Default)]
+pub struct QifSection {
+    /// Type name: `Bank`, `CCard`, `Cash`, `Cat`...
+    pub kind: String,
+    /// Preceding `!Account` record (`N` name, `T` type), if any.
+    pub account: Option<QifRecord>,
+    /// Records of the section.
+    pub records: Vec<QifRecord>,
+}
+
+/// A parser for Quicken Interchange Format.
+#[derive(Debug)]
+pub struct QifParser {
+    /// `!Type:` sections in file order.
+    pub sections: Vec<QifSection>,
+}
+
+impl Parser for QifParser {
+    fn parse<R: Read>(mut input: R) -> Result<Self, ParseError> {
+        let invalid = |msg: &str| ParseError::InvalidFormat(format!("QIF invalid. {}", msg));
+        let mut bytes = Vec::new();
+        input.read_to_end(&mut bytes).map_err(ParseError::Io)?;
+        let (text, _) = text::decode(&bytes, None)?;
+
+        let mut sections: Vec<QifSection> = Vec::new();
+        let mut account: Option<QifRecord> = None;
+        let mut in_account = false;
+        let mut current = QifRecord::default();
+        for line in text.lines().map(str::trim_end).filter(|l| !l.trim().is_empty()) {
+            if let Some(directive) = line.strip_prefix('!') {
+                let directive = directive.trim();
+                if directive.eq_ignore_ascii_case("Account") {
+                    in_account = true;
+                } else if let Some(kind) = directive.strip_prefix("Type:") {
+                    in_account = false;
+                    sections.push(QifSection {
+                        kind: kind.trim().to_string(),
+                        account: account.clone(),
+                        records: Vec::new(),
+                    });
+                }
+                // `!Option:AutoSwitch`, `!Clear:AutoSwitch` только управляют импортом в Quicken.
+                continue;
+            }
+            if line.starts_with('^') {
+                let record = std::mem::take(&mut current);
+                if in_account {
+                    account = Some(record);
+                } else if let Some(section) = sections.last_mut() {
+                    section.records.push(record);
+                }
+                continue;
+            }
+            if !in_account && sections.is_empty() {
+                return Err(invalid("Нет строки !Type:"));
+            }
+            let mut chars = line.chars();
+            let code = chars.next().expect("пустые строки отфильтрованы");
+            current.fields.push((code, chars.as_str().to_string()));
+        }
+        if !current.fields.is_empty() {
+            // Последняя запись без `^` — частый случай в выгрузках.
+            if let Some(section) = sections.last_mut() {
+                section.records.push(current);
+            }
+        }
+        if sections.is_empty() {
+            return Err(invalid("Нет строки !Type:"));
+        }
+        Ok(QifParser { sections })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_qif_parse() {
+        let input = "!Account\nNChecking\nTBank\n^\n!Type:Bank\nD01/05/2023\nT-42.50\nPCoffee\nSFood\n$-40.00\nSFees\n$-2.50\n^\nD01/06/2023\nT10\n";
+        let parser = QifParser::parse(input.as_bytes()).unwrap();
+        assert_eq!(parser.sections.len(), 1);
+        let section = &parser.sections[0];
+        assert_eq!(section.kind, "Bank");
+        assert_eq!(section.account.as_ref().unwrap().get('N'), Some("Checking"));
+        assert_eq!(section.records.len(), 2);
+        assert_eq!(section.records[0].get('P'), Some("Coffee"));
+        assert_eq!(section.records[0].fields.iter().filter(|(c, _)| *c == 'S').count(), 2);
+        assert_eq!(section.records[1].get('T'), Some("10"));
+    }
+
+    #[test]
+    fn test_qif_parse_invalid() {
+        assert!(QifParser::parse("D01/05/2023\nT-42.50\n^\n".as_bytes()).is_err());
+        assert!(QifParser::parse("".as_bytes()).is_err());
+    }
+}
@@ -0,0 +1,195 @@
+//! Разбор конверта SWIFT FIN: `{1:}{2:}{3:}{4:...-}{5:}`.
+
+use crate::ParseError;
+
+/// Блоки заголовка и трейлера SWIFT-сообщения.
+///
+/// Для файлов без конверта (только поля `:20:`, `:25:`...) все блоки пустые.
+#[derive(Debug, Clone, PartialEq, Default)]
+pub struct SwiftHeader {
+    /// Basic header block `{1:F01BANKBEBBAXXX0000000000}`, без `{1:` и `}`.
+    pub basic: Option<String>,
+    /// Application header block `{2:O9401200...}`.
+    pub application: Option<String>,
+    /// User header block `{3:{108:MUR}}`.
+    pub user: Option<String>,
+    /// Trailer block `{5:{CHK:...}}`.
+    pub trailer: Option<String>,
+}
+
+/// BIC11 из логического терминального адреса (12 символов): `BANKBEBBAXXX` → `BANKBEBBXXX`.
+fn bic_from_lt_address(lt: &str) -> Option<String> {
+    let lt = lt.get(..12)?;
+    if !lt.bytes().all(|b| b.is_ascii_alphanumeric()) {
+        return None;
+    }
+    Some(format!("{}{}", &lt[..8], &lt[9..12]))
+}
+
+impl SwiftHeader {
+    /// Направление из блока 2: `I` (input, в сеть) или `O` (output, из сети).
+    fn direction(&self) -> Option<char> {
+        self.application.as_deref()?.chars().next()
+    }
+
+    /// Тип сообщения из блока 2: `940`, `942`...
+    pub fn message_type(&self) -> Option<&str> {
+        self.application.as_deref()?.get(1..4)
+    }
+
+    /// BIC отправителя: из MIR блока 2 для output, из блока 1 для input.
+    pub fn sender_bic(&self) -> Option<String> {
+        match self.direction()? {
+            'O' => bic_from_lt_address(self.application.as_deref()?.get(14..)?),
+            'I' => bic_from_lt_address(self.basic.as_deref()?.get(3..)?),
+            _ => None,
+        }
+    }
+
+    /// BIC получателя: из блока 1 для output, из блока 2 для input.
+    pub fn receiver_bic(&self) -> Option<String> {
+        match self.direction()? {
+            'O' => bic_from_lt_address(self.basic.as_deref()?.get(3..)?),
+            'I' => bic_from_lt_address(self.application.as_deref()?.get(4..)?),
+            _ => None,
+        }
+    }
+}
+
+/// Одно сообщение: заголовки и текст блока 4.
+#[derive(Debug, Clone, PartialEq, Default)]
+pub(crate) struct RawMessage {
+    pub header: SwiftHeader,
+    pub text: String,
+}
+
+/// Читает блок `{N:...}` начиная с `{`, учитывая вложенные `{}` блоков 3 и 5.
+/// Возвращает номер блока, содержимое и позицию после закрывающей `}`.
+fn read_block(input: &str, start: usize) -> Result<(&str, &str, usize), ParseError> {
+    let invalid = |msg: &str| ParseError::InvalidFormat(format!("Invalid SWIFT block at {}: {}", start, msg));
+    let colon = input[start..].find(':').map(|p| start + p).ok_or_else(|| invalid("нет `:`"))?;
+    let id = &input[start + 1..colon];
+    if id.is_empty() || !id.bytes().all(|b| b.is_ascii_alphanumeric()) {
+        return Err(invalid("неверный номер блока"));
+    }
+    let mut depth = 1;
+    for (offset, ch) in input[colon + 1..].char_indices() {
+        match ch {
+            '{' => depth += 1,
+            '}' => {
+                depth -= 1;
+                if depth == 0 {
+                    let end = colon + 1 + offset;
+                    return Ok((id, &input[colon + 1..end], end + 1));
+                }
+            }
+            _ => {}
+        }
+    }
+    Err(invalid("блок не закрыт `}`"))
+}
+
+/// Разбивает файл на сообщения.
+///
+/// Сообщения в конверте начинаются с блока `{1:}`; между ними допускаются
+/// пробелы, переводы строк и разделители `$`. Файлы без конверта делятся
+/// на сообщения по строкам `-`.
+pub(crate) fn split_messages(input: &str) -> Result<Vec<RawMessage>, ParseError> {
+    let content = input.trim_start_matches('\u{feff}').trim_start();
+    if !content.starts_with('{') {
+        return Ok(split_plain(content));
+    }
+
+    let mut messages: Vec<RawMessage> = Vec::new();
+    let mut pos = 0;
+    while let Some(offset) = content[pos..].find(|c: char| !c.is_whitespace() && c != '$') {
+        let start = pos + offset;
+        if !content[start..].starts_with('{') {
+            return Err(ParseError::InvalidFormat(format!(
+                "Invalid SWIFT message. Ожидался блок `{{`, найдено: {}",
+                content[start..].chars().take(20).collect::<String>()
+            )));
+        }
+        let (id, body, end) = read_block(content, start)?;
+        if id == "1" || messages.is_empty() {
+            messages.push(RawMessage::default());
+        }
+        let message = messages.last_mut().expect("сообщение добавлено выше");
+        match id {
+            "1" => message.header.basic = Some(body.to_string()),
+            "2" => message.header.application = Some(body.to_string()),
+            "3" => message.header.user = Some(body.to_string()),
+            "4" => message.text = body.trim_end().trim_end_matches('-').to_string(),
+            "5" => message.header.trailer = Some(body.to_string()),
+            // Блок S (system trailer) и прочие служебные блоки не нужны.
+            _ => {}
+        }
+        pos = end;
+    }
+
+    if let Some(index) = messages.iter().position(|m| m.text.trim().is_empty()) {
+        return Err(ParseError::InvalidFormat(format!(
+            "Invalid SWIFT message #{}. Нет текстового блока {{4:",
+            index + 1
+        )));
+    }
+    Ok(messages)
+}
+
+fn split_plain(content: &str) -> Vec<RawMessage> {
+    let mut messages = vec![RawMessage::default()];
+    for line in content.lines() {
+        if line.trim() == "-" {
+            messages.push(RawMessage::default());
+            continue;
+        }
+        let text = &mut messages.last_mut().expect("всегда есть сообщение").text;
+        text.push_str(line);
+        text.push('\n');
+    }
+    messages.retain(|m| !m.text.trim().is_empty());
+    messages
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const FIN: &str = "{1:F01RECVDEFFAXXX0000000000}{2:O9401200230101SENDBEBBAXXX00000000002301011200N}{3:{108:MUR1}}{4:\r\n:20:REF1\r\n:25:ACC\r\n-}{5:{CHK:ABCDEF}}\r\n$\r\n{1:F01RECVDEFFAXXX0000000000}{2:I940SENDBEBBXXXXN}{4:\r\n:20:REF2\r\n-}";
+
+    #[test]
+    fn split_envelope_messages() {
+        let messages = split_messages(FIN).unwrap();
+        assert_eq!(messages.len(), 2);
+        assert_eq!(messages[0].header.user.as_deref(), Some("{108:MUR1}"));
+        assert_eq!(messages[0].header.trailer.as_deref(), Some("{CHK:ABCDEF}"));
+        assert_eq!(messages[0].text.trim(), ":20:REF1\r\n:25:ACC");
+        assert_eq!(messages[1].text.trim(), ":20:REF2");
+    }
+
+    #[test]
+    fn header_bics_and_type() {
+        let messages = split_messages(FIN).unwrap();
+        let output = &messages[0].header;
+        assert_eq!(output.message_type(), Some("940"));
+        assert_eq!(output.sender_bic().as_deref(), Some("SENDBEBBXXX"));
+        assert_eq!(output.receiver_bic().as_deref(), Some("RECVDEFFXXX"));
+        let input = &messages[1].header;
+        assert_eq!(input.sender_bic().as_deref(), Some("RECVDEFFXXX"));
+        assert_eq!(input.receiver_bic().as_deref(), Some("SENDBEBBXXX"));
+    }
+
+    #[test]
+    fn split_plain_by_dash_lines() {
+        let messages = split_messages(":20:A\n:25:X\n-\n:20:B\n-\n").unwrap();
+        assert_eq!(messages.len(), 2);
+        assert_eq!(messages[1].text, ":20:B\n");
+    }
+
+    #[test]
+    fn broken_envelope_is_error() {
+        assert!(split_messages("{1:F01BANK{4:\n:20:A\n-}").is_err());
+        assert!(split_messages("{1:F01RECVDEFFAXXX0000000000}{2:O940}").is_err());
+        assert!(split_messages("{1:F01RECVDEFFAXXX0000000000}garbage").is_err());
+    }
+}
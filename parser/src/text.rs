@@ -0,0 +1,22 @@
+//! Определение кодировки текстовых форматов (CSV, 1C, OFX, CODA).
+
+use encoding_rs::{Encoding, UTF_8, WINDOWS_1251};
+
+use crate::ParseError;
+
+/// Декодирует байты: по BOM, иначе заданной кодировкой, иначе UTF-8, если текст
+/// корректен, иначе Windows-1251. BOM важнее заданной кодировки.
+pub(crate) fn decode(bytes: &[u8], encoding: Option<&'static Encoding>) -> Result<(String, &'static Encoding), ParseError> {
+    let (encoding, bytes) = match Encoding::for_bom(bytes) {
+        Some((encoding, bom_length)) => (encoding, &bytes[bom_length..]),
+        None => {
+            let encoding = encoding.unwrap_or_else(|| if std::str::from_utf8(bytes).is_ok() { UTF_8 } else { WINDOWS_1251 });
+            (encoding, bytes)
+        }
+    };
+    let (text, had_errors) = encoding.decode_without_bom_handling(bytes);
+    if had_errors {
+        return Err(ParseError::InvalidFormat(format!("Текст не в кодировке {}", encoding.name())));
+    }
+    Ok((text.into_owned(), encoding))
+}
@@ -0,0 +1,82 @@
+//! Листы xlsx: типизированные ячейки строками, колонка `A` — индекс 0.
+
+use std::io::Cursor;
+
+use calamine::{Data, Reader, Xlsx};
+
+use crate::ParseError;
+
+fn invalid(msg: &str) -> ParseError {
+    ParseError::InvalidFormat(format!("XLSX invalid. {}", msg))
+}
+
+/// Typed spreadsheet cell.
+#[derive(Debug, Clone, PartialEq, Default)]
+pub enum XlsxCell {
+    /// Empty cell.
+    #[default]
+    Empty,
+    /// Text; booleans and errors are kept as their display text.
+    Text(String),
+    /// Numeric cell as stored in the workbook.
+    Number(f64),
+    /// Date or date-time in ISO 8601: `2023-01-02` or `2023-01-02T10:30:00`.
+    Date(String),
+}
+
+impl XlsxCell {
+    /// The cell has no value.
+    pub fn is_empty(&self) -> bool {
+        match self {
+            XlsxCell::Empty => true,
+            XlsxCell::Text(text) => text.trim().is_empty(),
+            _ => false,
+        }
+    }
+}
+
+/// Ячейка calamine: дата хранится как число дней, переводим ее в ISO.
+fn cell(data: &Data) -> XlsxCell {
+    match data {
+        Data::Empty => XlsxCell::Empty,
+        Data::String(text) => XlsxCell::Text(text.clone()),
+        Data::Int(value) => XlsxCell::Number(*value as f64),
+        Data::Float(value) => XlsxCell::Number(*value),
+        Data::DateTime(value) if value.is_datetime() => {
+            let (year, month, day, hour, minute, second, _) = value.to_ymd_hms_milli();
+            let date = format!("{:04}-{:02}-{:02}", year, month, day);
+            XlsxCell::Date(if (hour, minute, second) == (0, 0, 0) {
+                date
+            } else {
+                format!("{}T{:02}:{:02}:{:02}", date, hour, minute, second)
+            })
+        }
+        Data::DateTimeIso(text) => XlsxCell::Date(text.clone()),
+        other => XlsxCell::Text(other.to_string()),
+    }
+}
+
+/// Имя и строки листа `sheet` (или первого листа).
+///
+/// Пустые строки и колонки перед данными сохраняются, чтобы номера колонок совпадали с буквами.
+pub(crate) fn read_sheet(bytes: Vec<u8>, sheet: Option<&str>) -> Result<(String, Vec<Vec<XlsxCell>>), ParseError> {
+    let mut workbook = Xlsx::new(Cursor::new(bytes)).map_err(|e| invalid(&e.to_string()))?;
+    let names = workbook.sheet_names();
+    let name = match sheet {
+        Some(sheet) => names
+            .iter()
+            .find(|n| n.eq_ignore_ascii_case(sheet))
+            .cloned()
+            .ok_or_else(|| invalid(&format!("нет листа \"{}\", есть: {}", sheet, names.join(", "))))?,
+        None => names.first().cloned().ok_or_else(|| invalid("в книге нет листов"))?,
+    };
+    let range = workbook.worksheet_range(&name).map_err(|e| invalid(&e.to_string()))?;
+    let (first_row, first_column) = range.start().unwrap_or((0, 0));
+    let mut rows = vec![Vec::new(); first_row as usize];
+    for row in range.rows() {
+        let mut cells = vec![XlsxCell::Empty; first_column as usize];
+        cells.extend(row.iter().map(cell));
+        rows.push(cells);
+    }
+    Ok((name, rows))
+}
@@ -0,0 +1,133 @@
+//! Минимальное DOM-дерево для XML-форматов (CAMT и др.).
+
+use quick_xml::{Reader, events::Event};
+
+use crate::ParseError;
+
+/// Элемент XML-документа со всеми вложенными элементами.
+#[derive(Debug, Clone, PartialEq, Default)]
+pub struct XmlElement {
+    /// Локальное имя элемента без префикса пространства имен (`Stmt`, `Ntry`).
+    pub name: String,
+    /// Атрибуты в порядке следования, включая `xmlns`.
+    pub attributes: Vec<(String, String)>,
+    /// Текст элемента без ведущих и завершающих пробелов.
+    pub text: String,
+    /// Дочерние элементы.
+    pub children: Vec<XmlElement>,
+}
+
+impl XmlElement {
+    /// Первый дочерний элемент с именем `name`.
+    pub fn child(&self, name: &str) -> Option<&XmlElement> {
+        self.children.iter().find(|c| c.name == name)
+    }
+
+    /// Все дочерние элементы с именем `name`.
+    pub fn children<'a>(&'a self, name: &'a str) -> impl Iterator<Item = &'a XmlElement> {
+        self.children.iter().filter(move |c| c.name == name)
+    }
+
+    /// Элемент по пути из имен дочерних элементов: `["Acct", "Id", "IBAN"]`.
+    pub fn find(&self, path: &[&str]) -> Option<&XmlElement> {
+        path.iter().try_fold(self, |el, name| el.child(name))
+    }
+
+    /// Текст элемента по пути, если он есть и не пустой.
+    pub fn find_text(&self, path: &[&str]) -> Option<&str> {
+        self.find(path).map(|el| el.text.as_str()).filter(|t| !t.is_empty())
+    }
+
+    /// Значение атрибута.
+    pub fn attribute(&self, name: &str) -> Option<&str> {
+        self.attributes.iter().find(|(k, _)| k == name).map(|(_, v)| v.as_str())
+    }
+}
+
+fn start_element(e: &quick_xml::events::BytesStart<'_>) -> Result<XmlElement, ParseError> {
+    let invalid = |err: &dyn std::fmt::Display| ParseError::InvalidFormat(format!("Invalid XML: {}", err));
+    let mut attributes = Vec::new();
+    for attr in e.attributes() {
+        let attr = attr.map_err(|err| invalid(&err))?;
+        let key = String::from_utf8_lossy(attr.key.as_ref()).into_owned();
+        let value = attr.unescape_value().map_err(|err| invalid(&err))?.into_owned();
+        attributes.push((key, value));
+    }
+    Ok(XmlElement {
+        name: String::from_utf8_lossy(e.local_name().as_ref()).into_owned(),
+        attributes,
+        ..Default::default()
+    })
+}
+
+/// Разбирает XML-документ и возвращает корневой элемент.
+pub(crate) fn parse_document(input: &str) -> Result<XmlElement, ParseError> {
+    let invalid = |err: &dyn std::fmt::Display| ParseError::InvalidFormat(format!("Invalid XML: {}", err));
+    let mut reader = Reader::from_str(input);
+    let mut stack: Vec<XmlElement> = Vec::new();
+    let mut root = None;
+
+    loop {
+        let event = reader.read_event().map_err(|err| invalid(&err))?;
+        match event {
+            Event::Start(e) => stack.push(start_element(&e)?),
+            Event::Empty(e) => {
+                let element = start_element(&e)?;
+                match stack.last_mut() {
+                    Some(parent) => parent.children.push(element),
+                    None => root = Some(element),
+                }
+            }
+            Event::End(_) => {
+                let mut element = stack.pop().ok_or_else(|| invalid(&"лишний закрывающий тег"))?;
+                element.text = element.text.trim().to_string();
+                match stack.last_mut() {
+                    Some(parent) => parent.children.push(element),
+                    None => root = Some(element),
+                }
+            }
+            Event::Text(e) => {
+                if let Some(current) = stack.last_mut() {
+                    current.text.push_str(&e.unescape().map_err(|err| invalid(&err))?);
+                }
+            }
+            Event::CData(e) => {
+                if let Some(current) = stack.last_mut() {
+                    current.text.push_str(&e.decode().map_err(|err| invalid(&err))?);
+                }
+            }
+            Event::Eof => break,
+            _ => {}
+        }
+    }
+
+    if !stack.is_empty() {
+        return Err(invalid(&"документ оборван, не все теги закрыты"));
+    }
+    root.ok_or_else(|| invalid(&"нет корневого элемента"))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn parse_document_builds_tree() {
+        let xml = r#"<?xml version="1.0"?>
+            <ns:Doc xmlns:ns="urn:x"><A Ccy="EUR">1 &amp; 2</A><B/><A><![CDATA[<raw>]]></A></ns:Doc>"#;
+        let root = parse_document(xml).unwrap();
+        assert_eq!(root.name, "Doc");
+        assert_eq!(root.children("A").count(), 2);
+        assert_eq!(root.find_text(&["A"]), Some("1 & 2"));
+        assert_eq!(root.child("A").unwrap().attribute("Ccy"), Some("EUR"));
+        assert_eq!(root.children[2].text, "<raw>");
+        assert!(root.find(&["B"]).is_some());
+    }
+
+    #[test]
+    fn parse_document_rejects_broken_xml() {
+        assert!(parse_document("<A><B></A>").is_err());
+        assert!(parse_document("<A>").is_err());
+        assert!(parse_document("").is_err());
+    }
+}